@@ -8,18 +8,43 @@ export interface DAO {
   authority: PublicKey;
   name: string;
   treasury: PublicKey;
+  governance_mint?: PublicKey | undefined;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
    * For Solana lamports or large values, ensure they stay within safe range.
    * Values exceeding this limit will lose precision during serialization.
    */
   total_members: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  total_voting_power: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  total_quadratic_power: number;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
    * For Solana lamports or large values, ensure they stay within safe range.
    * Values exceeding this limit will lose precision during serialization.
    */
   total_proposals: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  latest_voting_end: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  latest_timelock_end: number;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
    * For Solana lamports or large values, ensure they stay within safe range.
@@ -32,6 +57,12 @@ export interface DAO {
    * Values exceeding this limit will lose precision during serialization.
    */
   timelock_delay: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  grace_period: number;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
    * For Solana lamports or large values, ensure they stay within safe range.
@@ -44,6 +75,23 @@ export interface DAO {
    * Values exceeding this limit will lose precision during serialization.
    */
   approval_threshold: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  optimistic_veto_threshold: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  optimistic_max_transfer: number;
+  voting_strategy: VotingStrategy;
+  deposit: DepositConfig;
+  guardians: PublicKey[];
+  guardian_threshold: number;
+  ragequit_tokens: PublicKey[];
   is_active: boolean;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
@@ -57,12 +105,25 @@ export const DAOSchema = borsh.struct([
   borsh.publicKey('authority'),
   borsh.string('name'),
   borsh.publicKey('treasury'),
+  borsh.option(borsh.publicKey)('governance_mint'),
   borsh.u64('total_members'),
+  borsh.u64('total_voting_power'),
+  borsh.u64('total_quadratic_power'),
   borsh.u64('total_proposals'),
+  borsh.i64('latest_voting_end'),
+  borsh.i64('latest_timelock_end'),
   borsh.i64('voting_period'),
   borsh.i64('timelock_delay'),
+  borsh.i64('grace_period'),
   borsh.u64('quorum_threshold'),
   borsh.u64('approval_threshold'),
+  borsh.u64('optimistic_veto_threshold'),
+  borsh.u64('optimistic_max_transfer'),
+  VotingStrategySchema('voting_strategy'),
+  DepositConfigSchema('deposit'),
+  borsh.vec(borsh.publicKey)('guardians'),
+  borsh.u8('guardian_threshold'),
+  borsh.vec(borsh.publicKey)('ragequit_tokens'),
   borsh.bool('is_active'),
   borsh.i64('created_at'),
 ]);
@@ -79,7 +140,10 @@ export interface Proposal {
   proposer: PublicKey;
   title: string;
   description: string;
-  proposal_type: ProposalType;
+  actions: ProposalType[];
+  kind: ProposalKind;
+  option_votes: number[];
+  winning_option?: number | undefined;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
    * For Solana lamports or large values, ensure they stay within safe range.
@@ -104,6 +168,19 @@ export interface Proposal {
    * Values exceeding this limit will lose precision during serialization.
    */
   total_votes: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  voting_power_snapshot: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  optimistic_veto_threshold: number;
+  voting_strategy: VotingStrategy;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
    * For Solana lamports or large values, ensure they stay within safe range.
@@ -134,7 +211,9 @@ export interface Proposal {
    * Values exceeding this limit will lose precision during serialization.
    */
   cancelled_at?: number | undefined;
+  cancel_refunds_deposit: boolean;
   status: ProposalStatus;
+  deposit: DepositConfig;
 }
 
 export const ProposalSchema = borsh.struct([
@@ -143,17 +222,25 @@ export const ProposalSchema = borsh.struct([
   borsh.publicKey('proposer'),
   borsh.string('title'),
   borsh.string('description'),
-  ProposalTypeSchema('proposal_type'),
+  borsh.vec(ProposalTypeSchema)('actions'),
+  ProposalKindSchema('kind'),
+  borsh.vec(borsh.u64)('option_votes'),
+  borsh.option(borsh.u8)('winning_option'),
   borsh.u64('yes_votes'),
   borsh.u64('no_votes'),
   borsh.u64('abstain_votes'),
   borsh.u64('total_votes'),
+  borsh.u64('voting_power_snapshot'),
+  borsh.u64('optimistic_veto_threshold'),
+  VotingStrategySchema('voting_strategy'),
   borsh.i64('start_time'),
   borsh.i64('end_time'),
   borsh.option(borsh.i64)('queued_at'),
   borsh.option(borsh.i64)('executed_at'),
   borsh.option(borsh.i64)('cancelled_at'),
+  borsh.bool('cancel_refunds_deposit'),
   ProposalStatusSchema('status'),
+  DepositConfigSchema('deposit'),
 ]);
 
 
@@ -162,23 +249,31 @@ export const ProposalSchema = borsh.struct([
  * Large values (e.g., Solana lamports) may lose precision during serialization.
  */
 export type ProposalType =
-  | { kind: 'Transfer'; recipient: PublicKey; amount: number }
-  | { kind: 'ConfigChange'; voting_period: number | undefined; timelock_delay: number | undefined; quorum_threshold: number | undefined; approval_threshold: number | undefined }
+  | { kind: 'Transfer'; recipient: PublicKey; amount: number; mint: PublicKey | undefined }
+  | { kind: 'ConfigChange'; voting_period: number | undefined; timelock_delay: number | undefined; grace_period: number | undefined; quorum_threshold: number | undefined; approval_threshold: number | undefined; optimistic_veto_threshold: number | undefined; optimistic_max_transfer: number | undefined; voting_strategy: VotingStrategy | undefined; deposit: DepositConfig | undefined }
   | { kind: 'AddMember'; member: PublicKey; voting_power: number }
   | { kind: 'RemoveMember'; member: PublicKey }
-  | { kind: 'Custom'; instruction_data: string; target_program: PublicKey }
+  | { kind: 'SetGuardians'; guardians: PublicKey[]; threshold: number }
+  | { kind: 'SetRagequitTokens'; mints: PublicKey[] }
+  | { kind: 'Custom'; program_id: PublicKey; accounts: ProposalAccountMeta[]; data: number[] }
 ;
 
 export const ProposalTypeSchema = borsh.rustEnum([
   borsh.struct([
     borsh.publicKey('recipient'),
     borsh.u64('amount'),
+    borsh.option(borsh.publicKey)('mint'),
   ], 'Transfer'),
   borsh.struct([
     borsh.option(borsh.i64)('voting_period'),
     borsh.option(borsh.i64)('timelock_delay'),
+    borsh.option(borsh.i64)('grace_period'),
     borsh.option(borsh.u64)('quorum_threshold'),
     borsh.option(borsh.u64)('approval_threshold'),
+    borsh.option(borsh.u64)('optimistic_veto_threshold'),
+    borsh.option(borsh.u64)('optimistic_max_transfer'),
+    borsh.option(VotingStrategySchema)('voting_strategy'),
+    borsh.option(DepositConfigSchema)('deposit'),
   ], 'ConfigChange'),
   borsh.struct([
     borsh.publicKey('member'),
@@ -188,12 +283,48 @@ export const ProposalTypeSchema = borsh.rustEnum([
     borsh.publicKey('member'),
   ], 'RemoveMember'),
   borsh.struct([
-    borsh.string('instruction_data'),
-    borsh.publicKey('target_program'),
+    borsh.vec(borsh.publicKey)('guardians'),
+    borsh.u8('threshold'),
+  ], 'SetGuardians'),
+  borsh.struct([
+    borsh.vec(borsh.publicKey)('mints'),
+  ], 'SetRagequitTokens'),
+  borsh.struct([
+    borsh.publicKey('program_id'),
+    borsh.vec(ProposalAccountMetaSchema)('accounts'),
+    borsh.vec(borsh.u8)('data'),
   ], 'Custom'),
 ]);
 
 
+export type ProposalKind =
+  | { kind: 'Standard' }
+  | { kind: 'Optimistic' }
+  | { kind: 'Plurality' }
+  | { kind: 'RankedChoice' }
+;
+
+export const ProposalKindSchema = borsh.rustEnum([
+  borsh.unit('Standard'),
+  borsh.unit('Optimistic'),
+  borsh.unit('Plurality'),
+  borsh.unit('RankedChoice'),
+]);
+
+
+export interface ProposalAccountMeta {
+  pubkey: PublicKey;
+  is_signer: boolean;
+  is_writable: boolean;
+}
+
+export const ProposalAccountMetaSchema = borsh.struct([
+  borsh.publicKey('pubkey'),
+  borsh.bool('is_signer'),
+  borsh.bool('is_writable'),
+]);
+
+
 export type ProposalStatus =
   | { kind: 'Draft' }
   | { kind: 'Active' }
@@ -203,6 +334,7 @@ export type ProposalStatus =
   | { kind: 'Executed' }
   | { kind: 'Cancelled' }
   | { kind: 'Expired' }
+  | { kind: 'Vetoed' }
 ;
 
 export const ProposalStatusSchema = borsh.rustEnum([
@@ -214,6 +346,7 @@ export const ProposalStatusSchema = borsh.rustEnum([
   borsh.unit('Executed'),
   borsh.unit('Cancelled'),
   borsh.unit('Expired'),
+  borsh.unit('Vetoed'),
 ]);
 
 
@@ -227,6 +360,12 @@ export interface Vote {
    * Values exceeding this limit will lose precision during serialization.
    */
   voting_power: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  weight: number;
   comment: string;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
@@ -241,21 +380,99 @@ export const VoteSchema = borsh.struct([
   borsh.publicKey('voter'),
   VoteTypeSchema('vote_type'),
   borsh.u64('voting_power'),
+  borsh.u64('weight'),
   borsh.string('comment'),
   borsh.i64('voted_at'),
 ]);
 
 
+export interface Veto {
+  dao: PublicKey;
+  proposal: PublicKey;
+  guardians: PublicKey[];
+  reason: string;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  vetoed_at: number;
+}
+
+export const VetoSchema = borsh.struct([
+  borsh.publicKey('dao'),
+  borsh.publicKey('proposal'),
+  borsh.vec(borsh.publicKey)('guardians'),
+  borsh.string('reason'),
+  borsh.i64('vetoed_at'),
+]);
+
+
 export type VoteType =
   | { kind: 'Yes' }
   | { kind: 'No' }
   | { kind: 'Abstain' }
+  | { kind: 'Choice'; option: number }
+  | { kind: 'Ranking'; options: number[] }
 ;
 
 export const VoteTypeSchema = borsh.rustEnum([
   borsh.unit('Yes'),
   borsh.unit('No'),
   borsh.unit('Abstain'),
+  borsh.struct([
+    borsh.u8('option'),
+  ], 'Choice'),
+  borsh.struct([
+    borsh.vec(borsh.u8)('options'),
+  ], 'Ranking'),
+]);
+
+
+export type VotingStrategy =
+  | { kind: 'Linear' }
+  | { kind: 'Quadratic' }
+  | { kind: 'Conviction' }
+;
+
+export const VotingStrategySchema = borsh.rustEnum([
+  borsh.unit('Linear'),
+  borsh.unit('Quadratic'),
+  borsh.unit('Conviction'),
+]);
+
+
+export interface DepositConfig {
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  amount: number;
+  asset: DepositAsset;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  refund_threshold: number;
+}
+
+export const DepositConfigSchema = borsh.struct([
+  borsh.u64('amount'),
+  DepositAssetSchema('asset'),
+  borsh.u64('refund_threshold'),
+]);
+
+
+export type DepositAsset =
+  | { kind: 'Sol' }
+  | { kind: 'GovernanceToken' }
+;
+
+export const DepositAssetSchema = borsh.rustEnum([
+  borsh.unit('Sol'),
+  borsh.unit('GovernanceToken'),
 ]);
 
 
@@ -269,6 +486,36 @@ export interface Member {
    */
   voting_power: number;
   delegate?: PublicKey | undefined;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  delegated_power: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  delegated_quadratic_power: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  voting_locked_until: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  last_deposit_at: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  delegation_locked_until: number;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
    * For Solana lamports or large values, ensure they stay within safe range.
@@ -295,6 +542,11 @@ export const MemberSchema = borsh.struct([
   borsh.publicKey('wallet'),
   borsh.u64('voting_power'),
   borsh.option(borsh.publicKey)('delegate'),
+  borsh.u64('delegated_power'),
+  borsh.u64('delegated_quadratic_power'),
+  borsh.i64('voting_locked_until'),
+  borsh.i64('last_deposit_at'),
+  borsh.i64('delegation_locked_until'),
   borsh.u64('proposals_created'),
   borsh.u64('votes_cast'),
   borsh.i64('joined_at'),
@@ -437,6 +689,18 @@ export interface DAOStats {
    * Values exceeding this limit will lose precision during serialization.
    */
   average_participation_rate: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  total_turnout: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  total_proposals_activated: number;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
    * For Solana lamports or large values, ensure they stay within safe range.
@@ -452,6 +716,8 @@ export const DAOStatsSchema = borsh.struct([
   borsh.u64('total_proposals_failed'),
   borsh.u64('total_treasury_spent'),
   borsh.u64('average_participation_rate'),
+  borsh.u64('total_turnout'),
+  borsh.u64('total_proposals_activated'),
   borsh.i64('last_activity_at'),
 ]);
 
@@ -501,6 +767,12 @@ export interface MemberStats {
    * Values exceeding this limit will lose precision during serialization.
    */
   participation_rate: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  proposals_at_join: number;
 }
 
 export const MemberStatsSchema = borsh.struct([
@@ -513,4 +785,5 @@ export const MemberStatsSchema = borsh.struct([
   borsh.u64('voting_power_received'),
   borsh.u64('voting_power_delegated'),
   borsh.u64('participation_rate'),
+  borsh.u64('proposals_at_join'),
 ]);
@@ -4,12 +4,20 @@
  */
 
 import {
+  AccountMeta,
   Connection,
   PublicKey,
   SystemProgram,
   Keypair,
   LAMPORTS_PER_SOL,
+  TransactionInstruction,
 } from '@solana/web3.js';
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  getAssociatedTokenAddressSync,
+} from '@solana/spl-token';
 import { AnchorProvider, Program, BN } from '@coral-xyz/anchor';
 
 // Import LUMOS-generated types
@@ -17,10 +25,15 @@ import {
   DAO,
   Proposal,
   ProposalType,
+  ProposalKind,
   ProposalStatus,
   Vote,
   VoteType,
+  VotingStrategy,
+  DepositConfig,
+  DepositAsset,
   Member,
+  Veto,
   VoteDelegation,
   DAOStats,
   MemberStats,
@@ -29,6 +42,9 @@ import {
 // Program ID (matches lib.rs declare_id!)
 const PROGRAM_ID = new PublicKey('dao1111111111111111111111111111111111111111');
 
+// How long a queued proposal stays executable after its timelock, unless overridden
+const DEFAULT_GRACE_PERIOD = 14 * 24 * 60 * 60; // 14 days
+
 /**
  * Client for interacting with the DAO governance program
  */
@@ -45,26 +61,51 @@ export class GovernanceClient {
   async createDAO(params: {
     authority: Keypair;
     name: string;
-    treasury: PublicKey;
     votingPeriod: number;
     timelockDelay: number;
+    /** Time to execute after the timelock before the proposal expires */
+    gracePeriod?: number;
     quorumThreshold: number;
     approvalThreshold: number;
+    /** Share of the snapshot voting No that stops an optimistic proposal; 0 (the default) disables them */
+    optimisticVetoThreshold?: number;
+    /** Most an optimistic proposal can transfer of any one asset, in lamports or token base units; defaults to 0 */
+    optimisticMaxTransfer?: number;
+    /** Makes this a token DAO: members vote with escrowed tokens of this mint */
+    governanceMint?: PublicKey;
+    /** How votes are weighted; defaults to Linear */
+    votingStrategy?: VotingStrategy;
+    /** Deposit escrowed by each new proposal; defaults to none */
+    deposit?: DepositConfig;
   }): Promise<PublicKey> {
     const daoKeypair = Keypair.generate();
+    const strategy = params.votingStrategy?.kind ?? 'Linear';
+    const deposit = params.deposit ?? { amount: 0, asset: { kind: 'Sol' }, refund_threshold: 0 };
 
     await this.program.methods
       .createDao(
         params.name,
         new BN(params.votingPeriod),
         new BN(params.timelockDelay),
+        new BN(params.gracePeriod ?? DEFAULT_GRACE_PERIOD),
         new BN(params.quorumThreshold),
-        new BN(params.approvalThreshold)
+        new BN(params.approvalThreshold),
+        new BN(params.optimisticVetoThreshold ?? 0),
+        new BN(params.optimisticMaxTransfer ?? 0),
+        // Anchor encodes enum variants as { variantName: {} }
+        { [strategy.toLowerCase()]: {} },
+        {
+          amount: new BN(deposit.amount),
+          asset: deposit.asset.kind === 'Sol' ? { sol: {} } : { governanceToken: {} },
+          refundThreshold: new BN(deposit.refund_threshold),
+        }
       )
       .accounts({
         dao: daoKeypair.publicKey,
         authority: params.authority.publicKey,
-        treasury: params.treasury,
+        treasury: this.getTreasuryAddress(daoKeypair.publicKey),
+        governanceMint: params.governanceMint ?? null,
+        daoStats: this.getDAOStatsAddress(daoKeypair.publicKey),
         systemProgram: SystemProgram.programId,
       })
       .signers([daoKeypair, params.authority])
@@ -75,42 +116,149 @@ export class GovernanceClient {
   }
 
   /**
-   * Create a new proposal
+   * Draft a new proposal, escrowing the DAO's proposal deposit. Its actions
+   * execute in order, all or none. Voting opens once the proposer calls
+   * activateProposal.
    */
   async createProposal(params: {
     dao: PublicKey;
     proposer: Keypair;
     title: string;
     description: string;
-    proposalType: ProposalType;
+    actions: ProposalType[];
+    /**
+     * Optimistic proposals of Transfers pass unless vetoed by No votes.
+     * Plurality and RankedChoice proposals make each action an option and
+     * execute only the winner's. Defaults to Standard.
+     */
+    kind?: ProposalKind;
   }): Promise<PublicKey> {
     const proposalKeypair = Keypair.generate();
+    const kind = params.kind?.kind ?? 'Standard';
 
     const [memberPDA] = PublicKey.findProgramAddressSync(
       [Buffer.from('member'), params.dao.toBuffer(), params.proposer.publicKey.toBuffer()],
       PROGRAM_ID
     );
 
+    const depositEscrow = this.getDepositEscrowAddress(proposalKeypair.publicKey);
+    const { deposit, governanceMint } = await this.getDAO(params.dao);
+
+    // Token deposits go to the escrow PDA's associated token account
+    const preInstructions: TransactionInstruction[] = [];
+    const remainingAccounts: AccountMeta[] = [];
+    if (deposit.amount > 0 && 'governanceToken' in deposit.asset) {
+      const tokenProgram = (await this.connection.getAccountInfo(governanceMint!))!.owner;
+      const escrowTokenAccount = getAssociatedTokenAddressSync(
+        governanceMint!,
+        depositEscrow,
+        true,
+        tokenProgram
+      );
+      preInstructions.push(
+        createAssociatedTokenAccountIdempotentInstruction(
+          params.proposer.publicKey,
+          escrowTokenAccount,
+          depositEscrow,
+          governanceMint!,
+          tokenProgram
+        )
+      );
+      remainingAccounts.push(
+        { pubkey: governanceMint!, isSigner: false, isWritable: false },
+        {
+          pubkey: getAssociatedTokenAddressSync(
+            governanceMint!,
+            params.proposer.publicKey,
+            false,
+            tokenProgram
+          ),
+          isSigner: false,
+          isWritable: true,
+        },
+        { pubkey: escrowTokenAccount, isSigner: false, isWritable: true },
+        { pubkey: tokenProgram, isSigner: false, isWritable: false }
+      );
+    }
+
     await this.program.methods
       .createProposal(
         params.title,
         params.description,
-        params.proposalType
+        params.actions,
+        { [kind.charAt(0).toLowerCase() + kind.slice(1)]: {} }
       )
       .accounts({
         dao: params.dao,
         proposal: proposalKeypair.publicKey,
         proposer: params.proposer.publicKey,
         member: memberPDA,
+        memberStats: this.getMemberStatsAddress(params.dao, params.proposer.publicKey),
+        depositEscrow,
         systemProgram: SystemProgram.programId,
       })
+      .preInstructions(preInstructions)
+      .remainingAccounts(remainingAccounts)
       .signers([proposalKeypair, params.proposer])
       .rpc();
 
-    console.log(`Proposal created: ${proposalKeypair.publicKey.toBase58()}`);
+    console.log(`Proposal drafted: ${proposalKeypair.publicKey.toBase58()}`);
     return proposalKeypair.publicKey;
   }
 
+  /**
+   * Replace a draft's title, description and actions
+   */
+  async updateDraft(params: {
+    dao: PublicKey;
+    proposal: PublicKey;
+    proposer: Keypair;
+    title: string;
+    description: string;
+    actions: ProposalType[];
+  }): Promise<void> {
+    await this.program.methods
+      .updateDraft(params.title, params.description, params.actions)
+      .accounts({
+        dao: params.dao,
+        proposal: params.proposal,
+        proposer: params.proposer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([params.proposer])
+      .rpc();
+
+    console.log('Draft updated');
+  }
+
+  /**
+   * Open a draft for voting
+   */
+  async activateProposal(params: {
+    dao: PublicKey;
+    proposal: PublicKey;
+    proposer: Keypair;
+  }): Promise<void> {
+    const [memberPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from('member'), params.dao.toBuffer(), params.proposer.publicKey.toBuffer()],
+      PROGRAM_ID
+    );
+
+    await this.program.methods
+      .activateProposal()
+      .accounts({
+        dao: params.dao,
+        proposal: params.proposal,
+        proposer: params.proposer.publicKey,
+        member: memberPDA,
+        daoStats: this.getDAOStatsAddress(params.dao),
+      })
+      .signers([params.proposer])
+      .rpc();
+
+    console.log('Proposal activated');
+  }
+
   /**
    * Cast a vote on a proposal
    */
@@ -147,6 +295,8 @@ export class GovernanceClient {
         voteRecord: voteRecordPDA,
         voter: params.voter.publicKey,
         member: memberPDA,
+        daoStats: this.getDAOStatsAddress(proposalData.dao),
+        memberStats: this.getMemberStatsAddress(proposalData.dao, params.voter.publicKey),
         systemProgram: SystemProgram.programId,
       })
       .signers([params.voter])
@@ -155,6 +305,87 @@ export class GovernanceClient {
     console.log(`Vote cast: ${params.voteType}`);
   }
 
+  /**
+   * Change an existing vote while voting is still open
+   */
+  async changeVote(params: {
+    proposal: PublicKey;
+    voter: Keypair;
+    voteType: VoteType;
+    comment: string;
+  }): Promise<void> {
+    const proposalData = await this.getProposal(params.proposal);
+
+    const [voteRecordPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('vote'),
+        params.proposal.toBuffer(),
+        params.voter.publicKey.toBuffer(),
+      ],
+      PROGRAM_ID
+    );
+
+    const [memberPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('member'),
+        proposalData.dao.toBuffer(),
+        params.voter.publicKey.toBuffer(),
+      ],
+      PROGRAM_ID
+    );
+
+    await this.program.methods
+      .changeVote(params.voteType, params.comment)
+      .accounts({
+        proposal: params.proposal,
+        voteRecord: voteRecordPDA,
+        voter: params.voter.publicKey,
+        member: memberPDA,
+      })
+      .signers([params.voter])
+      .rpc();
+
+    console.log(`Vote changed: ${params.voteType}`);
+  }
+
+  /**
+   * Retract a vote while voting is still open, refunding the vote record's rent
+   */
+  async withdrawVote(params: {
+    proposal: PublicKey;
+    voter: Keypair;
+  }): Promise<void> {
+    const { dao } = await this.getProposal(params.proposal);
+    const [voteRecordPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('vote'),
+        params.proposal.toBuffer(),
+        params.voter.publicKey.toBuffer(),
+      ],
+      PROGRAM_ID
+    );
+
+    const [memberPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from('member'), dao.toBuffer(), params.voter.publicKey.toBuffer()],
+      PROGRAM_ID
+    );
+
+    await this.program.methods
+      .withdrawVote()
+      .accounts({
+        proposal: params.proposal,
+        voteRecord: voteRecordPDA,
+        daoStats: this.getDAOStatsAddress(dao),
+        memberStats: this.getMemberStatsAddress(dao, params.voter.publicKey),
+        voter: params.voter.publicKey,
+        member: memberPDA,
+      })
+      .signers([params.voter])
+      .rpc();
+
+    console.log('Vote withdrawn');
+  }
+
   /**
    * Queue a proposal after voting ends
    */
@@ -162,11 +393,15 @@ export class GovernanceClient {
     dao: PublicKey;
     proposal: PublicKey;
   }): Promise<void> {
+    const { proposer } = await this.getProposal(params.proposal);
+
     await this.program.methods
       .queueProposal()
       .accounts({
         dao: params.dao,
         proposal: params.proposal,
+        daoStats: this.getDAOStatsAddress(params.dao),
+        proposerStats: this.getMemberStatsAddress(params.dao, proposer),
       })
       .rpc();
 
@@ -179,18 +414,30 @@ export class GovernanceClient {
   async executeProposal(params: {
     dao: PublicKey;
     proposal: PublicKey;
-    treasuryAccount: PublicKey;
-    recipientAccount: PublicKey;
+    signers?: Keypair[]; // signers Custom instructions need besides the treasury
   }): Promise<void> {
+    const { actions, winningOption } = await this.program.account.proposal.fetch(params.proposal);
+
+    // Each action's accounts, in action order. Multi-option proposals only
+    // execute the winning option's action.
+    const executed = winningOption === null ? actions : [actions[winningOption]];
+    const remainingAccounts: AccountMeta[] = [];
+    for (const action of executed as any[]) {
+      remainingAccounts.push(...(await this.getActionAccounts(params.dao, action)));
+    }
+
     await this.program.methods
       .executeProposal()
       .accounts({
         dao: params.dao,
         proposal: params.proposal,
-        treasuryAccount: params.treasuryAccount,
-        recipientAccount: params.recipientAccount,
+        treasury: this.getTreasuryAddress(params.dao),
+        daoStats: this.getDAOStatsAddress(params.dao),
+        executor: this.provider.wallet.publicKey,
         systemProgram: SystemProgram.programId,
       })
+      .remainingAccounts(remainingAccounts)
+      .signers(params.signers ?? [])
       .rpc();
 
     console.log('Proposal executed');
@@ -217,6 +464,123 @@ export class GovernanceClient {
     console.log('Proposal cancelled');
   }
 
+  /**
+   * Mark a lost vote Defeated, or a proposal left unexecuted past its grace
+   * period Expired. Anyone can call this.
+   */
+  async finalizeProposal(params: {
+    dao: PublicKey;
+    proposal: PublicKey;
+  }): Promise<void> {
+    const { proposer } = await this.getProposal(params.proposal);
+
+    await this.program.methods
+      .finalizeProposal()
+      .accounts({
+        dao: params.dao,
+        proposal: params.proposal,
+        daoStats: this.getDAOStatsAddress(params.dao),
+        proposerStats: this.getMemberStatsAddress(params.dao, proposer),
+      })
+      .rpc();
+
+    console.log('Proposal finalized');
+  }
+
+  /**
+   * Refund or forfeit a finished proposal's deposit. Anyone can call this.
+   */
+  async settleDeposit(params: {
+    dao: PublicKey;
+    proposal: PublicKey;
+  }): Promise<void> {
+    const { proposer, deposit } = await this.program.account.proposal.fetch(params.proposal);
+    const treasury = this.getTreasuryAddress(params.dao);
+    const depositEscrow = this.getDepositEscrowAddress(params.proposal);
+
+    const remainingAccounts: AccountMeta[] = [];
+    if ('governanceToken' in deposit.asset) {
+      const { governanceMint } = await this.getDAO(params.dao);
+      const tokenProgram = (await this.connection.getAccountInfo(governanceMint!))!.owner;
+      const proposalData = await this.getProposal(params.proposal);
+
+      // Mirrors settle_deposit: refunds go to the proposer, the rest to the treasury
+      const support =
+        proposalData.votingPowerSnapshot > 0
+          ? (proposalData.yesVotes * 10000) / proposalData.votingPowerSnapshot
+          : 0;
+      const refund =
+        'executed' in proposalData.status ||
+        'expired' in proposalData.status ||
+        ('defeated' in proposalData.status && support >= deposit.refundThreshold.toNumber());
+      const recipient = refund ? proposer : treasury;
+
+      remainingAccounts.push(
+        { pubkey: governanceMint!, isSigner: false, isWritable: false },
+        {
+          pubkey: getAssociatedTokenAddressSync(governanceMint!, depositEscrow, true, tokenProgram),
+          isSigner: false,
+          isWritable: true,
+        },
+        {
+          pubkey: getAssociatedTokenAddressSync(governanceMint!, recipient, true, tokenProgram),
+          isSigner: false,
+          isWritable: true,
+        },
+        { pubkey: tokenProgram, isSigner: false, isWritable: false }
+      );
+    }
+
+    await this.program.methods
+      .settleDeposit()
+      .accounts({
+        dao: params.dao,
+        proposal: params.proposal,
+        proposer,
+        treasury,
+        depositEscrow,
+      })
+      .remainingAccounts(remainingAccounts)
+      .rpc();
+
+    console.log('Proposal deposit settled');
+  }
+
+  /**
+   * Veto a succeeded proposal before it executes. `guardians` must include
+   * at least the DAO's guardian threshold of its guardians; the first pays
+   * for the veto record.
+   */
+  async vetoProposal(params: {
+    dao: PublicKey;
+    proposal: PublicKey;
+    guardians: Keypair[];
+    reason: string;
+  }): Promise<void> {
+    const [guardian, ...cosigners] = params.guardians;
+
+    await this.program.methods
+      .vetoProposal(params.reason)
+      .accounts({
+        dao: params.dao,
+        proposal: params.proposal,
+        veto: this.getVetoAddress(params.proposal),
+        guardian: guardian.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(
+        cosigners.map((cosigner) => ({
+          pubkey: cosigner.publicKey,
+          isSigner: true,
+          isWritable: false,
+        }))
+      )
+      .signers(params.guardians)
+      .rpc();
+
+    console.log(`Proposal vetoed: ${params.reason}`);
+  }
+
   /**
    * Add a member to the DAO
    */
@@ -238,6 +602,8 @@ export class GovernanceClient {
         member: memberPDA,
         authority: params.authority.publicKey,
         newMember: params.newMember,
+        memberStats: this.getMemberStatsAddress(params.dao, params.newMember),
+        daoStats: this.getDAOStatsAddress(params.dao),
         systemProgram: SystemProgram.programId,
       })
       .signers([params.authority])
@@ -246,6 +612,60 @@ export class GovernanceClient {
     console.log(`Member added: ${params.newMember.toBase58()}`);
   }
 
+  /**
+   * Remove a member from the DAO (admin only)
+   */
+  async removeMember(params: {
+    dao: PublicKey;
+    authority: Keypair;
+    member: PublicKey;
+  }): Promise<void> {
+    const [memberPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from('member'), params.dao.toBuffer(), params.member.toBuffer()],
+      PROGRAM_ID
+    );
+
+    await this.program.methods
+      .removeMember()
+      .accounts({
+        dao: params.dao,
+        member: memberPDA,
+        authority: params.authority.publicKey,
+      })
+      .remainingAccounts(await this.getReclaimedDelegationAccounts(params.dao, params.member))
+      .signers([params.authority])
+      .rpc();
+
+    console.log(`Member removed: ${params.member.toBase58()}`);
+  }
+
+  /**
+   * Change a member's voting power (admin only)
+   */
+  async updateVotingPower(params: {
+    dao: PublicKey;
+    authority: Keypair;
+    member: PublicKey;
+    votingPower: number;
+  }): Promise<void> {
+    const [memberPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from('member'), params.dao.toBuffer(), params.member.toBuffer()],
+      PROGRAM_ID
+    );
+
+    await this.program.methods
+      .updateVotingPower(new BN(params.votingPower))
+      .accounts({
+        dao: params.dao,
+        member: memberPDA,
+        authority: params.authority.publicKey,
+      })
+      .signers([params.authority])
+      .rpc();
+
+    console.log(`Voting power of ${params.member.toBase58()} set to ${params.votingPower}`);
+  }
+
   /**
    * Delegate voting power
    */
@@ -254,8 +674,6 @@ export class GovernanceClient {
     delegator: Keypair;
     delegatee: PublicKey;
   }): Promise<void> {
-    const delegationKeypair = Keypair.generate();
-
     const [memberPDA] = PublicKey.findProgramAddressSync(
       [
         Buffer.from('member'),
@@ -275,13 +693,15 @@ export class GovernanceClient {
       .accounts({
         dao: params.dao,
         member: memberPDA,
-        delegation: delegationKeypair.publicKey,
+        delegation: this.getDelegationAddress(params.dao, params.delegator.publicKey),
         delegator: params.delegator.publicKey,
         delegateeWallet: params.delegatee,
         delegatee: delegateePDA,
+        delegatorStats: this.getMemberStatsAddress(params.dao, params.delegator.publicKey),
+        delegateeStats: this.getMemberStatsAddress(params.dao, params.delegatee),
         systemProgram: SystemProgram.programId,
       })
-      .signers([delegationKeypair, params.delegator])
+      .signers([params.delegator])
       .rpc();
 
     console.log(`Vote delegated to ${params.delegatee.toBase58()}`);
@@ -303,10 +723,21 @@ export class GovernanceClient {
       PROGRAM_ID
     );
 
+    const delegation = this.getDelegationAddress(params.dao, params.delegator.publicKey);
+    const { delegatee } = await this.program.account.voteDelegation.fetch(delegation);
+
+    const [delegateePDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from('member'), params.dao.toBuffer(), delegatee.toBuffer()],
+      PROGRAM_ID
+    );
+
     await this.program.methods
       .revokeDelegation()
       .accounts({
+        dao: params.dao,
         member: memberPDA,
+        delegation,
+        delegatee: delegateePDA,
         delegator: params.delegator.publicKey,
       })
       .signers([params.delegator])
@@ -315,8 +746,399 @@ export class GovernanceClient {
     console.log('Delegation revoked');
   }
 
+  /**
+   * Deposit governance tokens into the member's escrow (token DAOs only).
+   * Tokens come from the depositor's associated token account.
+   */
+  async depositGovernanceTokens(params: {
+    dao: PublicKey;
+    depositor: Keypair;
+    amount: number;
+  }): Promise<void> {
+    const accounts = await this.getEscrowAccounts(params.dao, params.depositor.publicKey);
+
+    await this.program.methods
+      .depositGovernanceTokens(new BN(params.amount))
+      .accounts({
+        dao: params.dao,
+        member: accounts.member,
+        governanceMint: accounts.governanceMint,
+        escrow: accounts.escrow,
+        depositorTokenAccount: accounts.depositorTokenAccount,
+        depositor: params.depositor.publicKey,
+        tokenProgram: accounts.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([params.depositor])
+      .rpc();
+
+    console.log(`Deposited ${params.amount} governance tokens`);
+  }
+
+  /**
+   * Withdraw governance tokens from the member's escrow back to the
+   * depositor's associated token account. Fails while any proposal the
+   * member voted on is still open.
+   */
+  async withdrawGovernanceTokens(params: {
+    dao: PublicKey;
+    depositor: Keypair;
+    amount: number;
+  }): Promise<void> {
+    const accounts = await this.getEscrowAccounts(params.dao, params.depositor.publicKey);
+
+    await this.program.methods
+      .withdrawGovernanceTokens(new BN(params.amount))
+      .accounts({
+        dao: params.dao,
+        member: accounts.member,
+        governanceMint: accounts.governanceMint,
+        escrow: accounts.escrow,
+        depositorTokenAccount: accounts.depositorTokenAccount,
+        depositor: params.depositor.publicKey,
+        tokenProgram: accounts.tokenProgram,
+      })
+      .signers([params.depositor])
+      .rpc();
+
+    console.log(`Withdrew ${params.amount} governance tokens`);
+  }
+
+  /**
+   * Leave the DAO while a succeeded proposal the member voted against, or
+   * didn't vote on, waits out its timelock. The member receives their share
+   * of the treasury's SOL and of each allow-listed token, paid into their
+   * associated token accounts.
+   */
+  async ragequit(params: {
+    dao: PublicKey;
+    proposal: PublicKey;
+    member: Keypair;
+  }): Promise<void> {
+    const wallet = params.member.publicKey;
+    const treasury = this.getTreasuryAddress(params.dao);
+    const { ragequitTokens } = await this.getDAO(params.dao);
+
+    const [memberPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from('member'), params.dao.toBuffer(), wallet.toBuffer()],
+      PROGRAM_ID
+    );
+    const [voteRecord] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vote'), params.proposal.toBuffer(), wallet.toBuffer()],
+      PROGRAM_ID
+    );
+
+    // Each allow-listed mint's accounts, creating any missing token accounts first
+    const preInstructions: TransactionInstruction[] = [];
+    const remainingAccounts: AccountMeta[] = [];
+    for (const mint of ragequitTokens) {
+      const tokenProgram = (await this.connection.getAccountInfo(mint))!.owner;
+      const treasuryTokenAccount = getAssociatedTokenAddressSync(mint, treasury, true, tokenProgram);
+      const memberTokenAccount = getAssociatedTokenAddressSync(mint, wallet, false, tokenProgram);
+
+      preInstructions.push(
+        createAssociatedTokenAccountIdempotentInstruction(
+          wallet,
+          treasuryTokenAccount,
+          treasury,
+          mint,
+          tokenProgram
+        ),
+        createAssociatedTokenAccountIdempotentInstruction(
+          wallet,
+          memberTokenAccount,
+          wallet,
+          mint,
+          tokenProgram
+        )
+      );
+      remainingAccounts.push(
+        { pubkey: mint, isSigner: false, isWritable: false },
+        { pubkey: treasuryTokenAccount, isSigner: false, isWritable: true },
+        { pubkey: memberTokenAccount, isSigner: false, isWritable: true },
+        { pubkey: tokenProgram, isSigner: false, isWritable: false }
+      );
+    }
+
+    await this.program.methods
+      .ragequit()
+      .accounts({
+        dao: params.dao,
+        proposal: params.proposal,
+        member: memberPDA,
+        voteRecord,
+        treasury,
+        wallet,
+        systemProgram: SystemProgram.programId,
+      })
+      .preInstructions(preInstructions)
+      .remainingAccounts(remainingAccounts)
+      .signers([params.member])
+      .rpc();
+
+    console.log(`Member ragequit: ${wallet.toBase58()}`);
+  }
+
+  /**
+   * Accounts a governance token deposit or withdrawal needs
+   */
+  private async getEscrowAccounts(dao: PublicKey, depositor: PublicKey) {
+    const { governanceMint } = await this.getDAO(dao);
+    if (!governanceMint) {
+      throw new Error('DAO has no governance mint');
+    }
+
+    // Token or Token-2022, whichever owns the mint
+    const mintInfo = await this.connection.getAccountInfo(governanceMint);
+    const tokenProgram = mintInfo.owner;
+
+    const [member] = PublicKey.findProgramAddressSync(
+      [Buffer.from('member'), dao.toBuffer(), depositor.toBuffer()],
+      PROGRAM_ID
+    );
+
+    return {
+      member,
+      governanceMint,
+      escrow: this.getEscrowAddress(dao, depositor, governanceMint, tokenProgram),
+      depositorTokenAccount: getAssociatedTokenAddressSync(
+        governanceMint,
+        depositor,
+        true,
+        tokenProgram
+      ),
+      tokenProgram,
+    };
+  }
+
+  /**
+   * Remaining accounts an action takes on execution, in the order
+   * execute_proposal reads them
+   */
+  private async getActionAccounts(dao: PublicKey, action: any): Promise<AccountMeta[]> {
+    if (action.transfer) {
+      return this.getTransferAccounts(dao, action.transfer);
+    }
+    if (action.addMember || action.removeMember) {
+      return this.getMembershipAccounts(dao, action);
+    }
+    if (action.custom) {
+      return this.getCustomInstructionMetas(dao, action.custom);
+    }
+    return [];
+  }
+
+  /**
+   * Accounts a Transfer action needs on execution. Token transfers go
+   * between the treasury's and the recipient's associated token accounts.
+   */
+  private async getTransferAccounts(dao: PublicKey, transfer: any): Promise<AccountMeta[]> {
+    if (!transfer.mint) {
+      return [{ pubkey: transfer.recipient, isSigner: false, isWritable: true }];
+    }
+
+    // Token or Token-2022, whichever owns the mint
+    const mintInfo = await this.connection.getAccountInfo(transfer.mint);
+    const tokenProgram = mintInfo.owner;
+
+    return [
+      { pubkey: transfer.mint, isSigner: false, isWritable: false },
+      {
+        pubkey: getAssociatedTokenAddressSync(
+          transfer.mint,
+          this.getTreasuryAddress(dao),
+          true,
+          tokenProgram
+        ),
+        isSigner: false,
+        isWritable: true,
+      },
+      {
+        pubkey: getAssociatedTokenAddressSync(transfer.mint, transfer.recipient, true, tokenProgram),
+        isSigner: false,
+        isWritable: true,
+      },
+      { pubkey: tokenProgram, isSigner: false, isWritable: false },
+    ];
+  }
+
+  /**
+   * Accounts an AddMember or RemoveMember action needs on execution
+   */
+  private async getMembershipAccounts(dao: PublicKey, action: any): Promise<AccountMeta[]> {
+    const wallet: PublicKey = action.addMember?.member ?? action.removeMember.member;
+
+    const [memberAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from('member'), dao.toBuffer(), wallet.toBuffer()],
+      PROGRAM_ID
+    );
+    const accounts: AccountMeta[] = [
+      { pubkey: memberAccount, isSigner: false, isWritable: true },
+    ];
+
+    // New members also get a stats account
+    if (action.addMember) {
+      accounts.push({
+        pubkey: this.getMemberStatsAddress(dao, wallet),
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+
+    if (action.removeMember) {
+      accounts.push(...(await this.getReclaimedDelegationAccounts(dao, wallet)));
+    }
+
+    return accounts;
+  }
+
+  /**
+   * Accounts removing a member needs to take back the power they delegated,
+   * if any: their delegation, the delegatee and their wallet
+   */
+  private async getReclaimedDelegationAccounts(dao: PublicKey, wallet: PublicKey): Promise<AccountMeta[]> {
+    const member = await this.getMember(dao, wallet);
+    if (!member?.delegate) {
+      return [];
+    }
+
+    const [delegatee] = PublicKey.findProgramAddressSync(
+      [Buffer.from('member'), dao.toBuffer(), member.delegate.toBuffer()],
+      PROGRAM_ID
+    );
+    return [
+      { pubkey: this.getDelegationAddress(dao, wallet), isSigner: false, isWritable: true },
+      { pubkey: delegatee, isSigner: false, isWritable: true },
+      { pubkey: wallet, isSigner: false, isWritable: true },
+    ];
+  }
+
+  /**
+   * Accounts a Custom action's instruction needs on execution: its
+   * accounts, then its program. The treasury PDA signs inside the program.
+   */
+  private getCustomInstructionMetas(dao: PublicKey, custom: any): AccountMeta[] {
+    const treasury = this.getTreasuryAddress(dao);
+    return [
+      ...custom.accounts.map((meta: any) => ({
+        pubkey: meta.pubkey,
+        isSigner: meta.isSigner && !meta.pubkey.equals(treasury),
+        isWritable: meta.isWritable,
+      })),
+      { pubkey: custom.programId, isSigner: false, isWritable: false },
+    ];
+  }
+
+  /**
+   * Build a Custom action from an instruction the treasury should sign
+   */
+  customProposalType(instruction: TransactionInstruction): any {
+    return {
+      custom: {
+        programId: instruction.programId,
+        accounts: instruction.keys.map((key) => ({
+          pubkey: key.pubkey,
+          isSigner: key.isSigner,
+          isWritable: key.isWritable,
+        })),
+        data: instruction.data,
+      },
+    };
+  }
+
   // ===== VIEW FUNCTIONS =====
 
+  /**
+   * Lamports held by the DAO treasury PDA
+   */
+  async getTreasuryBalance(dao: PublicKey): Promise<number> {
+    return this.connection.getBalance(this.getTreasuryAddress(dao));
+  }
+
+  /**
+   * Derive the DAO treasury PDA
+   */
+  getTreasuryAddress(dao: PublicKey): PublicKey {
+    const [treasury] = PublicKey.findProgramAddressSync(
+      [Buffer.from('treasury'), dao.toBuffer()],
+      PROGRAM_ID
+    );
+    return treasury;
+  }
+
+  /**
+   * Derive a member's governance token escrow: the associated token account
+   * of their member PDA
+   */
+  getEscrowAddress(
+    dao: PublicKey,
+    wallet: PublicKey,
+    governanceMint: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID
+  ): PublicKey {
+    const [member] = PublicKey.findProgramAddressSync(
+      [Buffer.from('member'), dao.toBuffer(), wallet.toBuffer()],
+      PROGRAM_ID
+    );
+    return getAssociatedTokenAddressSync(governanceMint, member, true, tokenProgram);
+  }
+
+  /**
+   * Derive the delegation PDA of a delegator
+   */
+  getDelegationAddress(dao: PublicKey, delegator: PublicKey): PublicKey {
+    const [delegation] = PublicKey.findProgramAddressSync(
+      [Buffer.from('delegation'), dao.toBuffer(), delegator.toBuffer()],
+      PROGRAM_ID
+    );
+    return delegation;
+  }
+
+  /**
+   * Derive the DAO's stats PDA
+   */
+  getDAOStatsAddress(dao: PublicKey): PublicKey {
+    const [stats] = PublicKey.findProgramAddressSync(
+      [Buffer.from('stats'), dao.toBuffer()],
+      PROGRAM_ID
+    );
+    return stats;
+  }
+
+  /**
+   * Derive a member's stats PDA
+   */
+  getMemberStatsAddress(dao: PublicKey, wallet: PublicKey): PublicKey {
+    const [stats] = PublicKey.findProgramAddressSync(
+      [Buffer.from('member_stats'), dao.toBuffer(), wallet.toBuffer()],
+      PROGRAM_ID
+    );
+    return stats;
+  }
+
+  /**
+   * Derive the PDA that escrows a proposal's deposit
+   */
+  getDepositEscrowAddress(proposal: PublicKey): PublicKey {
+    const [escrow] = PublicKey.findProgramAddressSync(
+      [Buffer.from('deposit'), proposal.toBuffer()],
+      PROGRAM_ID
+    );
+    return escrow;
+  }
+
+  /**
+   * Derive the veto record PDA of a proposal
+   */
+  getVetoAddress(proposal: PublicKey): PublicKey {
+    const [veto] = PublicKey.findProgramAddressSync(
+      [Buffer.from('veto'), proposal.toBuffer()],
+      PROGRAM_ID
+    );
+    return veto;
+  }
+
   /**
    * Fetch DAO data with type safety from LUMOS-generated types
    */
@@ -327,12 +1149,29 @@ export class GovernanceClient {
       authority: dao.authority,
       name: dao.name,
       treasury: dao.treasury,
+      governanceMint: dao.governanceMint || undefined,
       totalMembers: dao.totalMembers.toNumber(),
+      totalVotingPower: dao.totalVotingPower.toNumber(),
+      totalQuadraticPower: dao.totalQuadraticPower.toNumber(),
       totalProposals: dao.totalProposals.toNumber(),
+      latestVotingEnd: dao.latestVotingEnd.toNumber(),
+      latestTimelockEnd: dao.latestTimelockEnd.toNumber(),
       votingPeriod: dao.votingPeriod.toNumber(),
       timelockDelay: dao.timelockDelay.toNumber(),
+      gracePeriod: dao.gracePeriod.toNumber(),
       quorumThreshold: dao.quorumThreshold.toNumber(),
       approvalThreshold: dao.approvalThreshold.toNumber(),
+      optimisticVetoThreshold: dao.optimisticVetoThreshold.toNumber(),
+      optimisticMaxTransfer: dao.optimisticMaxTransfer.toNumber(),
+      votingStrategy: dao.votingStrategy as VotingStrategy,
+      deposit: {
+        amount: dao.deposit.amount.toNumber(),
+        asset: dao.deposit.asset as DepositAsset,
+        refundThreshold: dao.deposit.refundThreshold.toNumber(),
+      },
+      guardians: dao.guardians,
+      guardianThreshold: dao.guardianThreshold,
+      ragequitTokens: dao.ragequitTokens,
       isActive: dao.isActive,
       createdAt: dao.createdAt.toNumber(),
     };
@@ -350,20 +1189,51 @@ export class GovernanceClient {
       proposer: proposal.proposer,
       title: proposal.title,
       description: proposal.description,
-      proposalType: proposal.proposalType as ProposalType,
+      actions: proposal.actions as ProposalType[],
+      kind: proposal.kind as ProposalKind,
+      optionVotes: proposal.optionVotes.map((votes: BN) => votes.toNumber()),
+      winningOption: proposal.winningOption ?? undefined,
       yesVotes: proposal.yesVotes.toNumber(),
       noVotes: proposal.noVotes.toNumber(),
       abstainVotes: proposal.abstainVotes.toNumber(),
       totalVotes: proposal.totalVotes.toNumber(),
+      votingPowerSnapshot: proposal.votingPowerSnapshot.toNumber(),
+      optimisticVetoThreshold: proposal.optimisticVetoThreshold.toNumber(),
+      votingStrategy: proposal.votingStrategy as VotingStrategy,
       startTime: proposal.startTime.toNumber(),
       endTime: proposal.endTime.toNumber(),
       queuedAt: proposal.queuedAt?.toNumber(),
       executedAt: proposal.executedAt?.toNumber(),
       cancelledAt: proposal.cancelledAt?.toNumber(),
+      cancelRefundsDeposit: proposal.cancelRefundsDeposit,
       status: proposal.status as ProposalStatus,
+      deposit: {
+        amount: proposal.deposit.amount.toNumber(),
+        asset: proposal.deposit.asset as DepositAsset,
+        refundThreshold: proposal.deposit.refundThreshold.toNumber(),
+      },
     };
   }
 
+  /**
+   * Fetch the guardians and reason behind a vetoed proposal, if it was vetoed
+   */
+  async getVeto(proposal: PublicKey): Promise<Veto | null> {
+    try {
+      const veto = await this.program.account.veto.fetch(this.getVetoAddress(proposal));
+
+      return {
+        dao: veto.dao,
+        proposal: veto.proposal,
+        guardians: veto.guardians,
+        reason: veto.reason,
+        vetoedAt: veto.vetoedAt.toNumber(),
+      };
+    } catch (e) {
+      return null;
+    }
+  }
+
   /**
    * Fetch member data
    */
@@ -381,6 +1251,11 @@ export class GovernanceClient {
         wallet: member.wallet,
         votingPower: member.votingPower.toNumber(),
         delegate: member.delegate || undefined,
+        delegatedPower: member.delegatedPower.toNumber(),
+        delegatedQuadraticPower: member.delegatedQuadraticPower.toNumber(),
+        votingLockedUntil: member.votingLockedUntil.toNumber(),
+        lastDepositAt: member.lastDepositAt.toNumber(),
+        delegationLockedUntil: member.delegationLockedUntil.toNumber(),
         proposalsCreated: member.proposalsCreated.toNumber(),
         votesCast: member.votesCast.toNumber(),
         joinedAt: member.joinedAt.toNumber(),
@@ -391,6 +1266,58 @@ export class GovernanceClient {
     }
   }
 
+  /**
+   * Fetch a DAO's governance health stats. Rates are in basis points.
+   */
+  async getDAOStats(dao: PublicKey): Promise<DAOStats> {
+    const stats = await this.program.account.daoStats.fetch(this.getDAOStatsAddress(dao));
+
+    return {
+      dao: stats.dao,
+      totalVotesCast: stats.totalVotesCast.toNumber(),
+      totalProposalsPassed: stats.totalProposalsPassed.toNumber(),
+      totalProposalsFailed: stats.totalProposalsFailed.toNumber(),
+      totalTreasurySpent: stats.totalTreasurySpent.toNumber(),
+      averageParticipationRate: stats.averageParticipationRate.toNumber(),
+      totalTurnout: stats.totalTurnout.toNumber(),
+      totalProposalsActivated: stats.totalProposalsActivated.toNumber(),
+      lastActivityAt: stats.lastActivityAt.toNumber(),
+    };
+  }
+
+  /**
+   * Fetch a member's governance stats. `participationRate` is recomputed
+   * against the proposals opened so far; the stored value is only updated
+   * when the member votes.
+   */
+  async getMemberStats(dao: PublicKey, wallet: PublicKey): Promise<MemberStats | null> {
+    try {
+      const stats = await this.program.account.memberStats.fetch(
+        this.getMemberStatsAddress(dao, wallet)
+      );
+      const { totalProposalsActivated } = await this.getDAOStats(dao);
+      const eligible = Math.max(totalProposalsActivated - stats.proposalsAtJoin.toNumber(), 1);
+
+      return {
+        member: stats.member,
+        dao: stats.dao,
+        totalProposalsCreated: stats.totalProposalsCreated.toNumber(),
+        totalVotesCast: stats.totalVotesCast.toNumber(),
+        proposalsPassed: stats.proposalsPassed.toNumber(),
+        proposalsFailed: stats.proposalsFailed.toNumber(),
+        votingPowerReceived: stats.votingPowerReceived.toNumber(),
+        votingPowerDelegated: stats.votingPowerDelegated.toNumber(),
+        participationRate: Math.min(
+          Math.floor((stats.totalVotesCast.toNumber() * 10000) / eligible),
+          10000
+        ),
+        proposalsAtJoin: stats.proposalsAtJoin.toNumber(),
+      };
+    } catch (e) {
+      return null;
+    }
+  }
+
   /**
    * Check if proposal passed
    */
@@ -400,20 +1327,36 @@ export class GovernanceClient {
 
     const currentTime = Math.floor(Date.now() / 1000);
 
-    // Check if voting ended
-    if (currentTime <= proposalData.endTime) {
+    // Check voting opened and has ended
+    if (proposalData.startTime === 0 || currentTime <= proposalData.endTime) {
       return false;
     }
 
-    // Calculate participation
-    const totalVotingPower = daoData.totalMembers * 1000; // Simplified
-    const participationRate = (proposalData.totalVotes * 10000) / totalVotingPower;
+    // Optimistic proposals pass unless enough of the snapshot voted No
+    if ('optimistic' in proposalData.kind) {
+      return (
+        proposalData.noVotes * 10000 <
+        proposalData.optimisticVetoThreshold * proposalData.votingPowerSnapshot
+      );
+    }
+
+    // Quorum is measured against the voting power snapshot taken at creation
+    if (proposalData.votingPowerSnapshot === 0) {
+      return false;
+    }
+    const participationRate =
+      (proposalData.totalVotes * 10000) / proposalData.votingPowerSnapshot;
 
     // Check quorum
     if (participationRate < daoData.quorumThreshold) {
       return false;
     }
 
+    // Multi-option proposals always have a winning option once quorum is reached
+    if ('plurality' in proposalData.kind || 'rankedChoice' in proposalData.kind) {
+      return true;
+    }
+
     // Check approval
     const approvalRate =
       proposalData.totalVotes > 0
@@ -441,7 +1384,9 @@ export class GovernanceClient {
     const currentTime = Math.floor(Date.now() / 1000);
     const executionTime = proposalData.queuedAt + daoData.timelockDelay;
 
-    return currentTime >= executionTime;
+    return (
+      currentTime >= executionTime && currentTime <= executionTime + daoData.gracePeriod
+    );
   }
 
   /**
@@ -458,9 +1403,7 @@ export class GovernanceClient {
     participationRate: number;
   }> {
     const proposalData = await this.getProposal(proposal);
-    const daoData = await this.getDAO(proposalData.dao);
-
-    const totalVotingPower = daoData.totalMembers * 1000; // Simplified
+    const totalVotingPower = proposalData.votingPowerSnapshot;
 
     return {
       yesVotes: proposalData.yesVotes,
@@ -479,7 +1422,8 @@ export class GovernanceClient {
         proposalData.totalVotes > 0
           ? (proposalData.abstainVotes / proposalData.totalVotes) * 100
           : 0,
-      participationRate: (proposalData.totalVotes / totalVotingPower) * 100,
+      participationRate:
+        totalVotingPower > 0 ? (proposalData.totalVotes / totalVotingPower) * 100 : 0,
     };
   }
 }
@@ -501,9 +1445,9 @@ async function example() {
   const dao = await client.createDAO({
     authority: wallet,
     name: 'My DAO',
-    treasury: Keypair.generate().publicKey,
     votingPeriod: 7 * 24 * 60 * 60, // 7 days
     timelockDelay: 2 * 24 * 60 * 60, // 2 days
+    gracePeriod: 7 * 24 * 60 * 60, // 7 days to execute
     quorumThreshold: 3000, // 30%
     approvalThreshold: 5100, // 51%
   });
@@ -522,14 +1466,20 @@ async function example() {
     proposer: wallet,
     title: 'Fund Development',
     description: 'Transfer 10 SOL to development team',
-    proposalType: {
-      Transfer: {
-        recipient: Keypair.generate().publicKey,
-        amount: 10 * LAMPORTS_PER_SOL,
-      },
-    } as ProposalType,
+    actions: [
+      {
+        Transfer: {
+          recipient: Keypair.generate().publicKey,
+          amount: 10 * LAMPORTS_PER_SOL,
+          mint: null, // or a token mint the treasury holds
+        },
+      } as ProposalType,
+    ],
   });
 
+  // Open the draft for voting
+  await client.activateProposal({ dao, proposal, proposer: wallet });
+
   // Cast vote
   await client.castVote({
     proposal,
@@ -550,11 +1500,6 @@ async function example() {
   // After timelock...
   const canExecute = await client.canExecuteProposal(proposal);
   if (canExecute) {
-    await client.executeProposal({
-      dao,
-      proposal,
-      treasuryAccount: Keypair.generate().publicKey,
-      recipientAccount: Keypair.generate().publicKey,
-    });
+    await client.executeProposal({ dao, proposal });
   }
 }
@@ -9,12 +9,25 @@ pub struct DAO {
     pub authority: Pubkey,
     pub name: String,
     pub treasury: Pubkey,
+    pub governance_mint: Option<Pubkey>,
     pub total_members: u64,
+    pub total_voting_power: u64,
+    pub total_quadratic_power: u64,
     pub total_proposals: u64,
+    pub latest_voting_end: i64,
+    pub latest_timelock_end: i64,
     pub voting_period: i64,
     pub timelock_delay: i64,
+    pub grace_period: i64,
     pub quorum_threshold: u64,
     pub approval_threshold: u64,
+    pub optimistic_veto_threshold: u64,
+    pub optimistic_max_transfer: u64,
+    pub voting_strategy: VotingStrategy,
+    pub deposit: DepositConfig,
+    pub guardians: Vec<Pubkey>,
+    pub guardian_threshold: u8,
+    pub ragequit_tokens: Vec<Pubkey>,
     pub is_active: bool,
     pub created_at: i64,
 }
@@ -26,17 +39,25 @@ pub struct Proposal {
     pub proposer: Pubkey,
     pub title: String,
     pub description: String,
-    pub proposal_type: ProposalType,
+    pub actions: Vec<ProposalType>,
+    pub kind: ProposalKind,
+    pub option_votes: Vec<u64>,
+    pub winning_option: Option<u8>,
     pub yes_votes: u64,
     pub no_votes: u64,
     pub abstain_votes: u64,
     pub total_votes: u64,
+    pub voting_power_snapshot: u64,
+    pub optimistic_veto_threshold: u64,
+    pub voting_strategy: VotingStrategy,
     pub start_time: i64,
     pub end_time: i64,
     pub queued_at: Option<i64>,
     pub executed_at: Option<i64>,
     pub cancelled_at: Option<i64>,
+    pub cancel_refunds_deposit: bool,
     pub status: ProposalStatus,
+    pub deposit: DepositConfig,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
@@ -44,12 +65,18 @@ pub enum ProposalType {
     Transfer {
         recipient: Pubkey,
         amount: u64,
+        mint: Option<Pubkey>,
     },
     ConfigChange {
         voting_period: Option<i64>,
         timelock_delay: Option<i64>,
+        grace_period: Option<i64>,
         quorum_threshold: Option<u64>,
         approval_threshold: Option<u64>,
+        optimistic_veto_threshold: Option<u64>,
+        optimistic_max_transfer: Option<u64>,
+        voting_strategy: Option<VotingStrategy>,
+        deposit: Option<DepositConfig>,
     },
     AddMember {
         member: Pubkey,
@@ -58,12 +85,35 @@ pub enum ProposalType {
     RemoveMember {
         member: Pubkey,
     },
+    SetGuardians {
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+    },
+    SetRagequitTokens {
+        mints: Vec<Pubkey>,
+    },
     Custom {
-        instruction_data: String,
-        target_program: Pubkey,
+        program_id: Pubkey,
+        accounts: Vec<ProposalAccountMeta>,
+        data: Vec<u8>,
     },
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub enum ProposalKind {
+    Standard,
+    Optimistic,
+    Plurality,
+    RankedChoice,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ProposalAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
 pub enum ProposalStatus {
     Draft,
@@ -74,6 +124,7 @@ pub enum ProposalStatus {
     Executed,
     Cancelled,
     Expired,
+    Vetoed,
 }
 
 #[account]
@@ -82,15 +133,51 @@ pub struct Vote {
     pub voter: Pubkey,
     pub vote_type: VoteType,
     pub voting_power: u64,
+    pub weight: u64,
     pub comment: String,
     pub voted_at: i64,
 }
 
+#[account]
+pub struct Veto {
+    pub dao: Pubkey,
+    pub proposal: Pubkey,
+    pub guardians: Vec<Pubkey>,
+    pub reason: String,
+    pub vetoed_at: i64,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
 pub enum VoteType {
     Yes,
     No,
     Abstain,
+    Choice {
+        option: u8,
+    },
+    Ranking {
+        options: Vec<u8>,
+    },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub enum VotingStrategy {
+    Linear,
+    Quadratic,
+    Conviction,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct DepositConfig {
+    pub amount: u64,
+    pub asset: DepositAsset,
+    pub refund_threshold: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub enum DepositAsset {
+    Sol,
+    GovernanceToken,
 }
 
 #[account]
@@ -99,6 +186,11 @@ pub struct Member {
     pub wallet: Pubkey,
     pub voting_power: u64,
     pub delegate: Option<Pubkey>,
+    pub delegated_power: u64,
+    pub delegated_quadratic_power: u64,
+    pub voting_locked_until: i64,
+    pub last_deposit_at: i64,
+    pub delegation_locked_until: i64,
     pub proposals_created: u64,
     pub votes_cast: u64,
     pub joined_at: i64,
@@ -163,6 +255,8 @@ pub struct DAOStats {
     pub total_proposals_failed: u64,
     pub total_treasury_spent: u64,
     pub average_participation_rate: u64,
+    pub total_turnout: u64,
+    pub total_proposals_activated: u64,
     pub last_activity_at: i64,
 }
 
@@ -177,4 +271,5 @@ pub struct MemberStats {
     pub voting_power_received: u64,
     pub voting_power_delegated: u64,
     pub participation_rate: u64,
+    pub proposals_at_join: u64,
 }
@@ -1,4 +1,11 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 
 // Import LUMOS-generated types
 mod generated;
@@ -6,58 +13,186 @@ use generated::*;
 
 declare_id!("dao1111111111111111111111111111111111111111");
 
+/// Maximum number of actions in a proposal
+pub const MAX_PROPOSAL_ACTIONS: usize = 8;
+
+/// Maximum number of accounts across a proposal's Custom instructions
+pub const MAX_INSTRUCTION_ACCOUNTS: usize = 16;
+
+/// Maximum size of a proposal's Custom instruction data, in bytes
+pub const MAX_INSTRUCTION_DATA: usize = 512;
+
+/// Maximum size of a vote comment, in bytes
+pub const MAX_COMMENT_LEN: usize = 200;
+
+/// Maximum number of guardians on a DAO's veto council
+pub const MAX_GUARDIANS: usize = 10;
+
+/// Maximum size of a veto reason, in bytes
+pub const MAX_VETO_REASON_LEN: usize = 200;
+
+/// Maximum number of token mints ragequitting members get a share of
+pub const MAX_RAGEQUIT_TOKENS: usize = 8;
+
+/// Maximum number of options in a ranked-choice proposal, which keeps one
+/// tally per full ranking (5! = 120)
+pub const MAX_RANKED_OPTIONS: usize = 5;
+
 #[program]
 pub mod dao_governance {
     use super::*;
 
     /// Create a new DAO with governance parameters
+    #[allow(clippy::too_many_arguments)]
     pub fn create_dao(
         ctx: Context<CreateDAO>,
         name: String,
         voting_period: i64,
         timelock_delay: i64,
+        grace_period: i64,
         quorum_threshold: u64,
         approval_threshold: u64,
+        optimistic_veto_threshold: u64,
+        optimistic_max_transfer: u64,
+        voting_strategy: VotingStrategy,
+        deposit: DepositConfig,
     ) -> Result<()> {
         require!(voting_period > 0, GovernanceError::InvalidVotingPeriod);
         require!(timelock_delay >= 0, GovernanceError::InvalidTimelock);
+        require!(grace_period > 0, GovernanceError::InvalidGracePeriod);
         require!(quorum_threshold > 0 && quorum_threshold <= 10000, GovernanceError::InvalidQuorum);
         require!(approval_threshold > 0 && approval_threshold <= 10000, GovernanceError::InvalidThreshold);
+        require!(optimistic_veto_threshold <= 10000, GovernanceError::InvalidOptimisticThreshold);
 
         let dao = &mut ctx.accounts.dao;
         let clock = Clock::get()?;
 
         dao.authority = ctx.accounts.authority.key();
         dao.name = name;
-        dao.treasury = ctx.accounts.treasury.key();
+        dao.treasury = ctx.accounts.treasury.key(); // PDA ["treasury", dao]
+        // With a governance mint, voting power comes from escrowed tokens
+        dao.governance_mint = ctx.accounts.governance_mint.as_ref().map(|mint| mint.key());
         dao.total_members = 0;
+        dao.total_voting_power = 0;
+        dao.total_quadratic_power = 0;
         dao.total_proposals = 0;
+        dao.latest_voting_end = 0;
+        dao.latest_timelock_end = 0;
         dao.voting_period = voting_period;
         dao.timelock_delay = timelock_delay;
+        dao.grace_period = grace_period;
         dao.quorum_threshold = quorum_threshold;
         dao.approval_threshold = approval_threshold;
+        // Zero disables optimistic proposals
+        dao.optimistic_veto_threshold = optimistic_veto_threshold;
+        dao.optimistic_max_transfer = optimistic_max_transfer;
+        dao.voting_strategy = voting_strategy;
+        check_deposit_config(dao, &deposit)?;
+        dao.deposit = deposit;
+        // The guardian council starts empty and only changes through proposals
+        dao.guardians = Vec::new();
+        dao.guardian_threshold = 0;
+        dao.ragequit_tokens = Vec::new();
         dao.is_active = true;
         dao.created_at = clock.unix_timestamp;
 
+        let stats = &mut ctx.accounts.dao_stats;
+        stats.dao = dao.key();
+        stats.total_votes_cast = 0;
+        stats.total_proposals_passed = 0;
+        stats.total_proposals_failed = 0;
+        stats.total_treasury_spent = 0;
+        stats.average_participation_rate = 0;
+        stats.total_turnout = 0;
+        stats.total_proposals_activated = 0;
+        stats.last_activity_at = clock.unix_timestamp;
+
         msg!("DAO created: {}", dao.name);
         Ok(())
     }
 
-    /// Create a new proposal
-    pub fn create_proposal(
-        ctx: Context<CreateProposal>,
+    /// Create a new proposal, escrowing the DAO's proposal deposit. Token
+    /// deposits take [mint, proposer token account, escrow token account,
+    /// token program] as remaining accounts.
+    ///
+    /// Optimistic proposals pay out Transfers without needing quorum: they
+    /// pass unless No votes reach the DAO's optimistic veto threshold.
+    /// Plurality and ranked-choice proposals make each action an option, and
+    /// only the winning option's action executes.
+    pub fn create_proposal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateProposal<'info>>,
         title: String,
         description: String,
-        proposal_type: ProposalType,
+        actions: Vec<ProposalType>,
+        kind: ProposalKind,
     ) -> Result<()> {
         let dao = &mut ctx.accounts.dao;
         let proposal = &mut ctx.accounts.proposal;
         let member = &ctx.accounts.member;
-        let clock = Clock::get()?;
 
         require!(dao.is_active, GovernanceError::DAONotActive);
         require!(member.is_active, GovernanceError::MemberNotActive);
         require!(member.voting_power > 0, GovernanceError::InsufficientVotingPower);
+        validate_actions(dao, &kind, &actions)?;
+
+        // Escrowed under the deposit PDA ["deposit", proposal] until settle_deposit
+        let escrow = &ctx.accounts.deposit_escrow;
+        let amount = dao.deposit.amount;
+        match dao.deposit.asset {
+            _ if amount == 0 => {}
+            DepositAsset::Sol => {
+                // A program-owned account with no data, so settle_deposit can
+                // move its lamports out directly
+                let proposal_key = proposal.key();
+                create_pda_account(
+                    &ctx.accounts.proposer,
+                    escrow,
+                    &ctx.accounts.system_program,
+                    0,
+                    &[&[b"deposit", proposal_key.as_ref(), &[ctx.bumps.deposit_escrow]]],
+                )?;
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.proposer.to_account_info(),
+                            to: escrow.to_account_info(),
+                        },
+                    ),
+                    amount,
+                )?;
+            }
+            DepositAsset::GovernanceToken => {
+                let mut remaining = ctx.remaining_accounts;
+                let [mint, from, to, token_program] =
+                    take_accounts(&mut remaining, GovernanceError::MissingDepositAccounts)?;
+                let mint = InterfaceAccount::<Mint>::try_from(mint)?;
+                let to = InterfaceAccount::<TokenAccount>::try_from(to)?;
+                let token_program = Interface::<TokenInterface>::try_from(token_program)?;
+
+                require!(
+                    dao.governance_mint == Some(mint.key()),
+                    GovernanceError::InvalidGovernanceMint
+                );
+                require_keys_eq!(to.owner, escrow.key(), GovernanceError::InvalidDepositAccounts);
+
+                token_interface::transfer_checked(
+                    CpiContext::new(
+                        token_program.to_account_info(),
+                        TransferChecked {
+                            from: from.clone(),
+                            mint: mint.to_account_info(),
+                            to: to.to_account_info(),
+                            authority: ctx.accounts.proposer.to_account_info(),
+                        },
+                    ),
+                    amount,
+                    mint.decimals,
+                )?;
+            }
+        }
+        proposal.deposit = dao.deposit.clone();
+        ctx.accounts.member_stats.total_proposals_created += 1;
 
         let proposal_id = dao.total_proposals;
         dao.total_proposals += 1;
@@ -67,19 +202,99 @@ pub mod dao_governance {
         proposal.proposer = ctx.accounts.proposer.key();
         proposal.title = title;
         proposal.description = description;
-        proposal.proposal_type = proposal_type;
+        proposal.option_votes = vec![0; tally_slots(&kind, actions.len())];
+        proposal.winning_option = None;
+        proposal.actions = actions;
+        proposal.kind = kind;
         proposal.yes_votes = 0;
         proposal.no_votes = 0;
         proposal.abstain_votes = 0;
         proposal.total_votes = 0;
-        proposal.start_time = clock.unix_timestamp;
-        proposal.end_time = clock.unix_timestamp + dao.voting_period;
+        // The proposal votes under the DAO's strategy at creation, even if a
+        // ConfigChange switches it before activation. Snapshot and voting
+        // period are set when voting opens.
+        proposal.voting_strategy = dao.voting_strategy.clone();
+        proposal.voting_power_snapshot = 0;
+        proposal.optimistic_veto_threshold = 0;
+        proposal.start_time = 0;
+        proposal.end_time = 0;
         proposal.queued_at = None;
         proposal.executed_at = None;
         proposal.cancelled_at = None;
+        proposal.cancel_refunds_deposit = false;
+        proposal.status = ProposalStatus::Draft;
+
+        msg!("Proposal {} drafted: {}", proposal_id, proposal.title);
+        Ok(())
+    }
+
+    /// Replace a draft's title, description and actions (proposer only)
+    pub fn update_draft(
+        ctx: Context<UpdateDraft>,
+        title: String,
+        description: String,
+        actions: Vec<ProposalType>,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+
+        require!(
+            matches!(proposal.status, ProposalStatus::Draft),
+            GovernanceError::ProposalNotDraft
+        );
+        require!(
+            proposal.proposer == ctx.accounts.proposer.key(),
+            GovernanceError::Unauthorized
+        );
+        validate_actions(&ctx.accounts.dao, &proposal.kind, &actions)?;
+
+        proposal.title = title;
+        proposal.description = description;
+        proposal.option_votes = vec![0; tally_slots(&proposal.kind, actions.len())];
+        proposal.actions = actions;
+
+        msg!("Draft {} updated", proposal.id);
+        Ok(())
+    }
+
+    /// Open a draft for voting (proposer only)
+    pub fn activate_proposal(
+        ctx: Context<ActivateProposal>,
+    ) -> Result<()> {
+        let dao = &mut ctx.accounts.dao;
+        let proposal = &mut ctx.accounts.proposal;
+        let member = &ctx.accounts.member;
+        let clock = Clock::get()?;
+
+        require!(
+            matches!(proposal.status, ProposalStatus::Draft),
+            GovernanceError::ProposalNotDraft
+        );
+        require!(
+            proposal.proposer == ctx.accounts.proposer.key(),
+            GovernanceError::Unauthorized
+        );
+        require!(dao.is_active, GovernanceError::DAONotActive);
+        require!(member.is_active, GovernanceError::MemberNotActive);
+        require!(member.voting_power > 0, GovernanceError::InsufficientVotingPower);
+
+        // Quorum is measured against the power that existed when voting opened,
+        // in the units the proposal's strategy tallies votes in
+        proposal.voting_power_snapshot = match proposal.voting_strategy {
+            VotingStrategy::Linear | VotingStrategy::Conviction => dao.total_voting_power,
+            VotingStrategy::Quadratic => dao.total_quadratic_power,
+        };
+        // Like the snapshot, the veto threshold can't move once voting opens
+        proposal.optimistic_veto_threshold = dao.optimistic_veto_threshold;
+        proposal.start_time = clock.unix_timestamp;
+        proposal.end_time = clock.unix_timestamp + dao.voting_period;
         proposal.status = ProposalStatus::Active;
+        dao.latest_voting_end = dao.latest_voting_end.max(proposal.end_time);
 
-        msg!("Proposal {} created: {}", proposal_id, proposal.title);
+        let stats = &mut ctx.accounts.dao_stats;
+        stats.total_proposals_activated += 1;
+        stats.last_activity_at = clock.unix_timestamp;
+
+        msg!("Proposal {} activated", proposal.id);
         Ok(())
     }
 
@@ -91,7 +306,7 @@ pub mod dao_governance {
     ) -> Result<()> {
         let proposal = &mut ctx.accounts.proposal;
         let vote_record = &mut ctx.accounts.vote_record;
-        let member = &ctx.accounts.member;
+        let member = &mut ctx.accounts.member;
         let clock = Clock::get()?;
 
         // Validations
@@ -104,41 +319,143 @@ pub mod dao_governance {
             GovernanceError::VotingPeriodEnded
         );
         require!(member.is_active, GovernanceError::MemberNotActive);
+        require!(member.delegate.is_none(), GovernanceError::VotingPowerDelegated);
+        require!(comment.len() <= MAX_COMMENT_LEN, GovernanceError::CommentTooLong);
+        // Neither members admitted nor tokens deposited after voting opened
+        // are in the snapshot
+        require!(
+            member.joined_at < proposal.start_time,
+            GovernanceError::JoinedAfterSnapshot
+        );
+        require!(
+            member.last_deposit_at < proposal.start_time,
+            GovernanceError::DepositAfterSnapshot
+        );
 
-        let voting_power = member.voting_power;
+        // Own power plus everything currently delegated to this member
+        let voting_power = member.voting_power + member.delegated_power;
         require!(voting_power > 0, GovernanceError::InsufficientVotingPower);
 
+        let weight = vote_weight(
+            proposal,
+            voting_power,
+            quadratic_power(member),
+            clock.unix_timestamp,
+        );
+
         // Record vote
         vote_record.proposal = proposal.key();
         vote_record.voter = ctx.accounts.voter.key();
         vote_record.vote_type = vote_type.clone();
         vote_record.voting_power = voting_power;
+        vote_record.weight = weight;
         vote_record.comment = comment;
         vote_record.voted_at = clock.unix_timestamp;
 
         // Update proposal vote counts
-        match vote_type {
-            VoteType::Yes => {
-                proposal.yes_votes += voting_power;
-            }
-            VoteType::No => {
-                proposal.no_votes += voting_power;
-            }
-            VoteType::Abstain => {
-                proposal.abstain_votes += voting_power;
-            }
-        }
-        proposal.total_votes += voting_power;
+        *vote_tally(proposal, &vote_type)? += weight;
+        proposal.total_votes += weight;
+
+        // Delegations to or from this member can't move the power just used
+        // until the vote is final
+        member.voting_locked_until = member.voting_locked_until.max(proposal.end_time);
+
+        let dao_stats = &mut ctx.accounts.dao_stats;
+        dao_stats.total_votes_cast += 1;
+        dao_stats.last_activity_at = clock.unix_timestamp;
+
+        let member_stats = &mut ctx.accounts.member_stats;
+        member_stats.total_votes_cast += 1;
+        member_stats.participation_rate = participation_rate(member_stats, dao_stats);
 
         msg!("Vote cast on proposal {}: {:?}", proposal.id, vote_type);
         Ok(())
     }
 
+    /// Change an existing vote while voting is still open
+    pub fn change_vote(
+        ctx: Context<ChangeVote>,
+        vote_type: VoteType,
+        comment: String,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let vote_record = &mut ctx.accounts.vote_record;
+        let clock = Clock::get()?;
+
+        require!(
+            matches!(proposal.status, ProposalStatus::Active),
+            GovernanceError::ProposalNotActive
+        );
+        require!(
+            clock.unix_timestamp <= proposal.end_time,
+            GovernanceError::VotingPeriodEnded
+        );
+        require!(ctx.accounts.member.is_active, GovernanceError::MemberNotActive);
+        require!(comment.len() <= MAX_COMMENT_LEN, GovernanceError::CommentTooLong);
+
+        // The recorded power moves as a whole; delegations can't have changed
+        // it while the vote is open. Under conviction voting the new vote only
+        // counts from now on, and a quadratic weight carries over unchanged.
+        let weight = vote_weight(
+            proposal,
+            vote_record.voting_power,
+            vote_record.weight,
+            clock.unix_timestamp,
+        );
+        *vote_tally(proposal, &vote_record.vote_type)? -= vote_record.weight;
+        *vote_tally(proposal, &vote_type)? += weight;
+        proposal.total_votes = proposal.total_votes - vote_record.weight + weight;
+
+        vote_record.vote_type = vote_type.clone();
+        vote_record.weight = weight;
+        vote_record.comment = comment;
+        vote_record.voted_at = clock.unix_timestamp;
+
+        msg!("Vote changed on proposal {}: {:?}", proposal.id, vote_type);
+        Ok(())
+    }
+
+    /// Retract a vote while voting is still open
+    pub fn withdraw_vote(
+        ctx: Context<WithdrawVote>,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let vote_record = &ctx.accounts.vote_record;
+        let clock = Clock::get()?;
+
+        require!(
+            matches!(proposal.status, ProposalStatus::Active),
+            GovernanceError::ProposalNotActive
+        );
+        require!(
+            clock.unix_timestamp <= proposal.end_time,
+            GovernanceError::VotingPeriodEnded
+        );
+        // A removed member's vote stays counted; they can't reclaim it
+        require!(ctx.accounts.member.is_active, GovernanceError::MemberNotActive);
+
+        // The member stays vote-locked until end_time, so the withdrawn power
+        // can only come back through cast_vote on this same proposal
+        *vote_tally(proposal, &vote_record.vote_type)? -= vote_record.weight;
+        proposal.total_votes -= vote_record.weight;
+
+        // A vote cast again later is counted again
+        let dao_stats = &mut ctx.accounts.dao_stats;
+        dao_stats.total_votes_cast -= 1;
+        dao_stats.last_activity_at = clock.unix_timestamp;
+        let member_stats = &mut ctx.accounts.member_stats;
+        member_stats.total_votes_cast -= 1;
+        member_stats.participation_rate = participation_rate(member_stats, dao_stats);
+
+        msg!("Vote withdrawn on proposal {}", proposal.id);
+        Ok(())
+    }
+
     /// Queue a successful proposal for execution
     pub fn queue_proposal(
         ctx: Context<QueueProposal>,
     ) -> Result<()> {
-        let dao = &ctx.accounts.dao;
+        let dao = &mut ctx.accounts.dao;
         let proposal = &mut ctx.accounts.proposal;
         let clock = Clock::get()?;
 
@@ -152,38 +469,37 @@ pub mod dao_governance {
             GovernanceError::VotingPeriodNotEnded
         );
 
-        // Calculate total voting power in DAO (simplified - use actual member count)
-        let total_voting_power = dao.total_members * 1000; // Assume 1000 power per member
-
-        // Check quorum
-        let participation_rate = (proposal.total_votes as u128 * 10000) / total_voting_power as u128;
-        require!(
-            participation_rate >= dao.quorum_threshold as u128,
-            GovernanceError::QuorumNotReached
-        );
-
-        // Check approval threshold
-        let approval_rate = if proposal.total_votes > 0 {
-            (proposal.yes_votes as u128 * 10000) / proposal.total_votes as u128
-        } else {
-            0
-        };
+        let (quorum_reached, approved) = tally_result(dao, proposal);
+        require!(quorum_reached, GovernanceError::QuorumNotReached);
 
-        if approval_rate >= dao.approval_threshold as u128 {
+        if approved {
+            if matches!(proposal.kind, ProposalKind::Plurality | ProposalKind::RankedChoice) {
+                let winner = winning_option(proposal);
+                proposal.winning_option = Some(winner);
+                msg!("Option {} won proposal {}", winner, proposal.id);
+            }
             proposal.status = ProposalStatus::Succeeded;
             proposal.queued_at = Some(clock.unix_timestamp);
+            dao.latest_timelock_end = dao.latest_timelock_end.max(clock.unix_timestamp + dao.timelock_delay);
             msg!("Proposal {} succeeded and queued", proposal.id);
         } else {
             proposal.status = ProposalStatus::Defeated;
             msg!("Proposal {} defeated", proposal.id);
         }
+        record_outcome(
+            &mut ctx.accounts.dao_stats,
+            &mut ctx.accounts.proposer_stats,
+            proposal,
+            approved,
+            clock.unix_timestamp,
+        );
 
         Ok(())
     }
 
     /// Execute a queued proposal after timelock
-    pub fn execute_proposal(
-        ctx: Context<ExecuteProposal>,
+    pub fn execute_proposal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteProposal<'info>>,
     ) -> Result<()> {
         let dao = &ctx.accounts.dao;
         let proposal = &mut ctx.accounts.proposal;
@@ -201,48 +517,36 @@ pub mod dao_governance {
             clock.unix_timestamp >= execution_time,
             GovernanceError::TimelockNotExpired
         );
+        require!(
+            clock.unix_timestamp <= execution_time + dao.grace_period,
+            GovernanceError::ProposalExpired
+        );
 
-        // Execute based on proposal type
-        match &proposal.proposal_type {
-            ProposalType::Transfer { recipient, amount } => {
-                // Transfer SOL from treasury to recipient
-                **ctx.accounts.treasury_account.try_borrow_mut_lamports()? -= amount;
-                **ctx.accounts.recipient_account.try_borrow_mut_lamports()? += amount;
-                msg!("Transferred {} lamports to {}", amount, recipient);
-            }
-            ProposalType::ConfigChange {
-                voting_period,
-                timelock_delay,
-                quorum_threshold,
-                approval_threshold,
-            } => {
-                let dao_mut = &mut ctx.accounts.dao;
-                if let Some(period) = voting_period {
-                    dao_mut.voting_period = *period;
-                }
-                if let Some(delay) = timelock_delay {
-                    dao_mut.timelock_delay = *delay;
-                }
-                if let Some(quorum) = quorum_threshold {
-                    dao_mut.quorum_threshold = *quorum;
-                }
-                if let Some(threshold) = approval_threshold {
-                    dao_mut.approval_threshold = *threshold;
-                }
-                msg!("DAO configuration updated");
-            }
-            ProposalType::AddMember { member, voting_power } => {
-                msg!("Member {} added with {} voting power", member, voting_power);
-            }
-            ProposalType::RemoveMember { member } => {
-                msg!("Member {} removed", member);
-            }
-            ProposalType::Custom { instruction_data, target_program } => {
-                msg!("Custom instruction executed on program {}", target_program);
-                msg!("Instruction data: {}", instruction_data);
-            }
+        // Each action takes its accounts from remaining_accounts in order. Any
+        // failing action fails the transaction, so either all actions run or none.
+        // Multi-option proposals run only the winning option's action.
+        let actions = match proposal.winning_option {
+            Some(option) => vec![proposal.actions[option as usize].clone()],
+            None => proposal.actions.clone(),
+        };
+        let mut remaining = ctx.remaining_accounts;
+        for action in &actions {
+            execute_action(
+                &mut ctx.accounts.dao,
+                &mut ctx.accounts.dao_stats,
+                &ctx.accounts.treasury,
+                ctx.bumps.treasury,
+                &ctx.accounts.executor,
+                &ctx.accounts.system_program,
+                action,
+                &mut remaining,
+                clock.unix_timestamp,
+            )?;
         }
+        require!(remaining.is_empty(), GovernanceError::UnusedActionAccounts);
+        ctx.accounts.dao_stats.last_activity_at = clock.unix_timestamp;
 
+        let proposal = &mut ctx.accounts.proposal;
         proposal.status = ProposalStatus::Executed;
         proposal.executed_at = Some(clock.unix_timestamp);
 
@@ -258,7 +562,10 @@ pub mod dao_governance {
         let clock = Clock::get()?;
 
         require!(
-            matches!(proposal.status, ProposalStatus::Active | ProposalStatus::Succeeded),
+            matches!(
+                proposal.status,
+                ProposalStatus::Draft | ProposalStatus::Active | ProposalStatus::Succeeded
+            ),
             GovernanceError::CannotCancelProposal
         );
 
@@ -271,6 +578,13 @@ pub mod dao_governance {
             GovernanceError::Unauthorized
         );
 
+        // A proposer withdrawing their own draft, or the authority stopping a
+        // passed proposal, isn't spam; cancelling a vote in progress is
+        proposal.cancel_refunds_deposit = match proposal.status {
+            ProposalStatus::Draft => is_proposer,
+            ProposalStatus::Succeeded => is_authority,
+            _ => false,
+        };
         proposal.status = ProposalStatus::Cancelled;
         proposal.cancelled_at = Some(clock.unix_timestamp);
 
@@ -278,6 +592,214 @@ pub mod dao_governance {
         Ok(())
     }
 
+    /// Settle a proposal whose outcome is decided (anyone can call): a lost
+    /// vote becomes Defeated, and a succeeded proposal not executed within the
+    /// grace period after its timelock becomes Expired
+    pub fn finalize_proposal(
+        ctx: Context<FinalizeProposal>,
+    ) -> Result<()> {
+        let dao = &ctx.accounts.dao;
+        let proposal = &mut ctx.accounts.proposal;
+        let clock = Clock::get()?;
+
+        match proposal.status {
+            ProposalStatus::Active => {
+                require!(
+                    clock.unix_timestamp > proposal.end_time,
+                    GovernanceError::VotingPeriodNotEnded
+                );
+                // Passing proposals go through queue_proposal instead
+                let (quorum_reached, approved) = tally_result(dao, proposal);
+                require!(!(quorum_reached && approved), GovernanceError::ProposalPassed);
+
+                proposal.status = ProposalStatus::Defeated;
+                record_outcome(
+                    &mut ctx.accounts.dao_stats,
+                    &mut ctx.accounts.proposer_stats,
+                    proposal,
+                    false,
+                    clock.unix_timestamp,
+                );
+                msg!("Proposal {} defeated", proposal.id);
+            }
+            ProposalStatus::Succeeded => {
+                let queued_at = proposal.queued_at.ok_or(GovernanceError::ProposalNotQueued)?;
+                require!(
+                    clock.unix_timestamp > queued_at + dao.timelock_delay + dao.grace_period,
+                    GovernanceError::GracePeriodNotEnded
+                );
+
+                proposal.status = ProposalStatus::Expired;
+                msg!("Proposal {} expired", proposal.id);
+            }
+            _ => return err!(GovernanceError::NothingToFinalize),
+        }
+
+        Ok(())
+    }
+
+    /// Veto a succeeded proposal before it executes. Needs `guardian_threshold`
+    /// of the DAO's guardians to sign: `guardian` plus any others passed as
+    /// remaining accounts. Proposals that replace the guardian council can't
+    /// be vetoed, so the guardians can't entrench themselves.
+    pub fn veto_proposal(
+        ctx: Context<VetoProposal>,
+        reason: String,
+    ) -> Result<()> {
+        let dao = &ctx.accounts.dao;
+        let proposal = &mut ctx.accounts.proposal;
+        let clock = Clock::get()?;
+
+        require!(dao.guardian_threshold > 0, GovernanceError::NoGuardians);
+        require!(
+            matches!(proposal.status, ProposalStatus::Succeeded),
+            GovernanceError::CannotVetoProposal
+        );
+        require!(
+            !proposal
+                .actions
+                .iter()
+                .any(|action| matches!(action, ProposalType::SetGuardians { .. })),
+            GovernanceError::CannotVetoGuardianChange
+        );
+        require!(reason.len() <= MAX_VETO_REASON_LEN, GovernanceError::VetoReasonTooLong);
+
+        let mut guardians = vec![ctx.accounts.guardian.key()];
+        for account in ctx.remaining_accounts {
+            require!(account.is_signer, GovernanceError::NotGuardian);
+            if !guardians.contains(account.key) {
+                guardians.push(*account.key);
+            }
+        }
+        require!(
+            guardians.iter().all(|guardian| dao.guardians.contains(guardian)),
+            GovernanceError::NotGuardian
+        );
+        require!(
+            guardians.len() >= dao.guardian_threshold as usize,
+            GovernanceError::InsufficientGuardians
+        );
+
+        let veto = &mut ctx.accounts.veto;
+        veto.dao = dao.key();
+        veto.proposal = proposal.key();
+        veto.guardians = guardians;
+        veto.reason = reason;
+        veto.vetoed_at = clock.unix_timestamp;
+
+        proposal.status = ProposalStatus::Vetoed;
+
+        msg!("Proposal {} vetoed by {} guardians", proposal.id, veto.guardians.len());
+        Ok(())
+    }
+
+    /// Return or forfeit a settled proposal's deposit (anyone can call). It
+    /// goes back to the proposer if the proposal executed, expired after
+    /// passing, or was defeated with at least `refund_threshold` of the
+    /// snapshot voting yes; otherwise it goes to the treasury. Token deposits
+    /// take [mint, escrow token account, destination token account, token
+    /// program] as remaining accounts.
+    pub fn settle_deposit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleDeposit<'info>>,
+    ) -> Result<()> {
+        let dao = &ctx.accounts.dao;
+        let proposal = &mut ctx.accounts.proposal;
+        let escrow = &ctx.accounts.deposit_escrow;
+
+        let amount = proposal.deposit.amount;
+        require!(amount > 0, GovernanceError::NoDeposit);
+
+        let refund = match proposal.status {
+            ProposalStatus::Executed | ProposalStatus::Expired => true,
+            ProposalStatus::Defeated => {
+                // Multi-option proposals have no No side, so every vote is support
+                let support = match proposal.kind {
+                    ProposalKind::Plurality | ProposalKind::RankedChoice => proposal.total_votes,
+                    _ => proposal.yes_votes,
+                };
+                (support as u128 * 10000)
+                    >= proposal.deposit.refund_threshold as u128 * proposal.voting_power_snapshot as u128
+            }
+            ProposalStatus::Cancelled => proposal.cancel_refunds_deposit,
+            ProposalStatus::Vetoed => false,
+            _ => return err!(GovernanceError::ProposalNotSettled),
+        };
+        let recipient = if refund {
+            ctx.accounts.proposer.key()
+        } else {
+            ctx.accounts.treasury.key()
+        };
+
+        match proposal.deposit.asset {
+            DepositAsset::Sol => {
+                // The deposit goes to the recipient and the escrow's rent back
+                // to the proposer, which closes the escrow
+                let destination = if refund {
+                    ctx.accounts.proposer.to_account_info()
+                } else {
+                    ctx.accounts.treasury.to_account_info()
+                };
+                let rent = escrow.lamports() - amount;
+                **escrow.try_borrow_mut_lamports()? -= amount + rent;
+                **destination.try_borrow_mut_lamports()? += amount;
+                **ctx.accounts.proposer.try_borrow_mut_lamports()? += rent;
+            }
+            DepositAsset::GovernanceToken => {
+                let mut remaining = ctx.remaining_accounts;
+                let [mint, from, to, token_program] =
+                    take_accounts(&mut remaining, GovernanceError::MissingDepositAccounts)?;
+                let mint = InterfaceAccount::<Mint>::try_from(mint)?;
+                let from = InterfaceAccount::<TokenAccount>::try_from(from)?;
+                let to = InterfaceAccount::<TokenAccount>::try_from(to)?;
+                let token_program = Interface::<TokenInterface>::try_from(token_program)?;
+
+                require!(
+                    dao.governance_mint == Some(mint.key()),
+                    GovernanceError::InvalidGovernanceMint
+                );
+                require_keys_eq!(from.owner, escrow.key(), GovernanceError::InvalidDepositAccounts);
+                require_keys_eq!(to.owner, recipient, GovernanceError::InvalidDepositAccounts);
+
+                let proposal_key = proposal.key();
+                let escrow_seeds: &[&[&[u8]]] =
+                    &[&[b"deposit", proposal_key.as_ref(), &[ctx.bumps.deposit_escrow]]];
+
+                token_interface::transfer_checked(
+                    CpiContext::new_with_signer(
+                        token_program.to_account_info(),
+                        TransferChecked {
+                            from: from.to_account_info(),
+                            mint: mint.to_account_info(),
+                            to: to.to_account_info(),
+                            authority: escrow.to_account_info(),
+                        },
+                        escrow_seeds,
+                    ),
+                    amount,
+                    mint.decimals,
+                )?;
+                // Return the escrow token account's rent to the proposer
+                token_interface::close_account(CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    CloseAccount {
+                        account: from.to_account_info(),
+                        destination: ctx.accounts.proposer.to_account_info(),
+                        authority: escrow.to_account_info(),
+                    },
+                    escrow_seeds,
+                ))?;
+            }
+        }
+        proposal.deposit.amount = 0;
+
+        if refund {
+            msg!("Deposit of {} refunded to proposer", amount);
+        } else {
+            msg!("Deposit of {} forfeited to treasury", amount);
+        }
+        Ok(())
+    }
+
     /// Add a member to the DAO
     pub fn add_member(
         ctx: Context<AddMember>,
@@ -287,39 +809,129 @@ pub mod dao_governance {
         let member = &mut ctx.accounts.member;
         let clock = Clock::get()?;
 
+        require!(dao.authority == ctx.accounts.authority.key(), GovernanceError::Unauthorized);
         require!(dao.is_active, GovernanceError::DAONotActive);
-        require!(voting_power > 0, GovernanceError::InvalidVotingPower);
+        check_assigned_power(dao, voting_power)?;
+        check_ragequit_window(dao, clock.unix_timestamp)?;
 
         member.dao = dao.key();
         member.wallet = ctx.accounts.new_member.key();
         member.voting_power = voting_power;
         member.delegate = None;
+        member.delegated_power = 0;
+        member.delegated_quadratic_power = 0;
+        member.voting_locked_until = 0;
+        member.last_deposit_at = 0;
+        // Like a deposit, new power can't be delegated into open proposals
+        member.delegation_locked_until = dao.latest_voting_end;
         member.proposals_created = 0;
         member.votes_cast = 0;
         member.joined_at = clock.unix_timestamp;
         member.is_active = true;
 
+        *ctx.accounts.member_stats = new_member_stats(
+            dao.key(),
+            ctx.accounts.new_member.key(),
+            &ctx.accounts.dao_stats,
+        );
+
         dao.total_members += 1;
+        adjust_total_power(dao, 0, voting_power);
 
         msg!("Member {} added with {} voting power", member.wallet, voting_power);
         Ok(())
     }
 
+    /// Deactivate a member and remove their voting power from the DAO
+    ///
+    /// Like a RemoveMember proposal, this takes back any power the member
+    /// delegated. Remaining accounts, if they delegated: their `VoteDelegation`
+    /// (writable), the delegatee's `Member` (writable), their wallet (writable)
+    pub fn remove_member<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RemoveMember<'info>>,
+    ) -> Result<()> {
+        let dao = &mut ctx.accounts.dao;
+        let member = &mut ctx.accounts.member;
+
+        require!(dao.authority == ctx.accounts.authority.key(), GovernanceError::Unauthorized);
+        require!(member.is_active, GovernanceError::MemberNotActive);
+
+        let mut remaining = ctx.remaining_accounts;
+        reclaim_delegation(dao.key(), member, &mut remaining)?;
+        member.is_active = false;
+
+        dao.total_members -= 1;
+        adjust_total_power(dao, member.voting_power, 0);
+
+        msg!("Member {} removed", member.wallet);
+        Ok(())
+    }
+
+    /// Change a member's voting power once no proposal is open for voting or
+    /// can be ragequit
+    pub fn update_voting_power(
+        ctx: Context<UpdateVotingPower>,
+        voting_power: u64,
+    ) -> Result<()> {
+        let dao = &mut ctx.accounts.dao;
+        let member = &mut ctx.accounts.member;
+
+        require!(dao.authority == ctx.accounts.authority.key(), GovernanceError::Unauthorized);
+        require!(dao.governance_mint.is_none(), GovernanceError::TokenWeightedDAO);
+        require!(member.is_active, GovernanceError::MemberNotActive);
+        require!(member.delegate.is_none(), GovernanceError::ActiveDelegation);
+        require!(voting_power > 0, GovernanceError::InvalidVotingPower);
+        // Open proposals' snapshots hold the member's current power
+        let now = Clock::get()?.unix_timestamp;
+        require!(now > dao.latest_voting_end, GovernanceError::VotesStillActive);
+        check_ragequit_window(dao, now)?;
+
+        adjust_total_power(dao, member.voting_power, voting_power);
+        member.voting_power = voting_power;
+
+        msg!("Member {} voting power set to {}", member.wallet, voting_power);
+        Ok(())
+    }
+
     /// Delegate voting power to another member
     pub fn delegate_vote(
         ctx: Context<DelegateVote>,
     ) -> Result<()> {
         let member = &mut ctx.accounts.member;
+        let delegatee = &mut ctx.accounts.delegatee;
         let delegation = &mut ctx.accounts.delegation;
         let clock = Clock::get()?;
 
         require!(member.is_active, GovernanceError::MemberNotActive);
+        require!(delegatee.is_active, GovernanceError::DelegateeNotActive);
+        require!(member.delegate.is_none(), GovernanceError::ActiveDelegation);
+        require!(
+            ctx.accounts.delegatee_wallet.key() != ctx.accounts.delegator.key(),
+            GovernanceError::InvalidDelegatee
+        );
+
+        // Delegation is one level deep, so delegated power is never passed on again
+        require!(
+            member.delegated_power == 0 && delegatee.delegate.is_none(),
+            GovernanceError::DelegationChain
+        );
+
+        // Power already used on an open proposal can't be handed to someone
+        // who votes on it again
         require!(
-            ctx.accounts.delegatee.is_active,
-            GovernanceError::DelegateeNotActive
+            clock.unix_timestamp > member.voting_locked_until,
+            GovernanceError::VotesStillActive
+        );
+        // Nor can tokens deposited after an open proposal's snapshot, which the
+        // delegatee could otherwise vote on it
+        require!(
+            clock.unix_timestamp > member.delegation_locked_until,
+            GovernanceError::DepositAfterSnapshot
         );
 
         member.delegate = Some(ctx.accounts.delegatee_wallet.key());
+        delegatee.delegated_power += member.voting_power;
+        delegatee.delegated_quadratic_power += isqrt(member.voting_power);
 
         delegation.delegator = ctx.accounts.delegator.key();
         delegation.delegatee = ctx.accounts.delegatee_wallet.key();
@@ -327,6 +939,9 @@ pub mod dao_governance {
         delegation.delegated_power = member.voting_power;
         delegation.created_at = clock.unix_timestamp;
 
+        ctx.accounts.delegator_stats.voting_power_delegated += member.voting_power;
+        ctx.accounts.delegatee_stats.voting_power_received += member.voting_power;
+
         msg!(
             "Voting power delegated from {} to {}",
             delegation.delegator,
@@ -340,124 +955,1457 @@ pub mod dao_governance {
         ctx: Context<RevokeDelegation>,
     ) -> Result<()> {
         let member = &mut ctx.accounts.member;
+        let delegatee = &mut ctx.accounts.delegatee;
+        let clock = Clock::get()?;
 
         require!(
             member.delegate.is_some(),
             GovernanceError::NoDelegationToRevoke
         );
 
+        // The delegatee may have voted with this power on proposals still open
+        require!(
+            clock.unix_timestamp > delegatee.voting_locked_until,
+            GovernanceError::VotesStillActive
+        );
+
+        delegatee.delegated_power -= ctx.accounts.delegation.delegated_power;
+        delegatee.delegated_quadratic_power -= isqrt(ctx.accounts.delegation.delegated_power);
         member.delegate = None;
 
         msg!("Delegation revoked for {}", ctx.accounts.delegator.key());
         Ok(())
     }
-}
-
-// ===== ACCOUNT CONTEXTS =====
-
-#[derive(Accounts)]
-pub struct CreateDAO<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + std::mem::size_of::<DAO>() + 200 // Extra space for name string
-    )]
-    pub dao: Account<'info, DAO>,
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Deposit governance tokens into the member's escrow, adding to their voting power
+    pub fn deposit_governance_tokens(
+        ctx: Context<DepositGovernanceTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        let dao = &mut ctx.accounts.dao;
+        let member = &mut ctx.accounts.member;
+        let clock = Clock::get()?;
 
-    /// CHECK: Treasury account for DAO funds
-    pub treasury: AccountInfo<'info>,
+        require!(dao.is_active, GovernanceError::DAONotActive);
+        require!(member.is_active, GovernanceError::MemberNotActive);
+        require!(member.delegate.is_none(), GovernanceError::ActiveDelegation);
+        require!(amount > 0, GovernanceError::InvalidVotingPower);
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.depositor_token_account.to_account_info(),
+                    mint: ctx.accounts.governance_mint.to_account_info(),
+                    to: ctx.accounts.escrow.to_account_info(),
+                    authority: ctx.accounts.depositor.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.governance_mint.decimals,
+        )?;
+
+        adjust_total_power(dao, member.voting_power, member.voting_power + amount);
+        member.voting_power += amount;
+        member.last_deposit_at = clock.unix_timestamp;
+        // Every proposal open now snapshotted the DAO without these tokens
+        member.delegation_locked_until = member.delegation_locked_until.max(dao.latest_voting_end);
+
+        msg!("Member {} deposited {} governance tokens", member.wallet, amount);
+        Ok(())
+    }
+
+    /// Withdraw governance tokens from the member's escrow once no proposal
+    /// they voted on is still open
+    pub fn withdraw_governance_tokens(
+        ctx: Context<WithdrawGovernanceTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        let dao = &mut ctx.accounts.dao;
+        let member = &mut ctx.accounts.member;
+        let clock = Clock::get()?;
+
+        require!(member.delegate.is_none(), GovernanceError::ActiveDelegation);
+        require!(amount <= member.voting_power, GovernanceError::InsufficientVotingPower);
+        // Escrowed tokens that voted can't move to another wallet and vote again
+        require!(
+            clock.unix_timestamp > member.voting_locked_until,
+            GovernanceError::VotesStillActive
+        );
+
+        let dao_key = dao.key();
+        let member_seeds: &[&[&[u8]]] = &[&[
+            b"member",
+            dao_key.as_ref(),
+            member.wallet.as_ref(),
+            &[ctx.bumps.member],
+        ]];
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.escrow.to_account_info(),
+                    mint: ctx.accounts.governance_mint.to_account_info(),
+                    to: ctx.accounts.depositor_token_account.to_account_info(),
+                    authority: member.to_account_info(),
+                },
+                member_seeds,
+            ),
+            amount,
+            ctx.accounts.governance_mint.decimals,
+        )?;
+
+        // Removed members' power already left the DAO totals
+        if member.is_active {
+            adjust_total_power(dao, member.voting_power, member.voting_power - amount);
+        }
+        member.voting_power -= amount;
+
+        msg!("Member {} withdrew {} governance tokens", member.wallet, amount);
+        Ok(())
+    }
+
+    /// Leave the DAO during a succeeded proposal's timelock, taking a share of
+    /// the treasury's SOL and allow-listed tokens proportional to the member's
+    /// voting power. Only members who voted No, didn't vote, or put a losing
+    /// option first can ragequit.
+    ///
+    /// Remaining accounts, for each mint in `dao.ragequit_tokens` in order:
+    /// mint, treasury token account, member token account, token program.
+    pub fn ragequit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Ragequit<'info>>,
+    ) -> Result<()> {
+        let dao = &mut ctx.accounts.dao;
+        let proposal = &ctx.accounts.proposal;
+        let member = &mut ctx.accounts.member;
+        let treasury = &ctx.accounts.treasury;
+        let clock = Clock::get()?;
+
+        // Voting power in token DAOs is escrowed tokens, which members withdraw instead
+        require!(dao.governance_mint.is_none(), GovernanceError::TokenWeightedDAO);
+        require!(member.is_active, GovernanceError::MemberNotActive);
+        require!(member.delegate.is_none(), GovernanceError::ActiveDelegation);
+        require!(member.voting_power > 0, GovernanceError::InsufficientVotingPower);
+        // Only members who could have voted on the proposal can leave over it
+        require!(member.joined_at < proposal.start_time, GovernanceError::JoinedAfterSnapshot);
+        // Votes on open proposals would still count after the member left
+        require!(
+            clock.unix_timestamp > member.voting_locked_until,
+            GovernanceError::VotesStillActive
+        );
+
+        let queued_at = match (&proposal.status, proposal.queued_at) {
+            (ProposalStatus::Succeeded, Some(queued_at)) => queued_at,
+            _ => return err!(GovernanceError::NotInTimelock),
+        };
+        require!(
+            clock.unix_timestamp < queued_at + dao.timelock_delay,
+            GovernanceError::NotInTimelock
+        );
+
+        // On multi-option proposals, voters whose first choice lost count as opposed
+        let vote_record = &ctx.accounts.vote_record;
+        if vote_record.owner == &crate::ID {
+            let vote = Vote::try_deserialize(&mut &vote_record.try_borrow_data()?[..])?;
+            let opposed = match &vote.vote_type {
+                VoteType::No => true,
+                VoteType::Choice { option } => proposal.winning_option != Some(*option),
+                VoteType::Ranking { options } => proposal.winning_option != options.first().copied(),
+                VoteType::Yes | VoteType::Abstain => false,
+            };
+            require!(opposed, GovernanceError::VotedForProposal);
+        }
+
+        let power = member.voting_power as u128;
+        let total_power = dao.total_voting_power as u128;
+        let dao_key = dao.key();
+        let treasury_seeds: &[&[&[u8]]] =
+            &[&[b"treasury", dao_key.as_ref(), &[ctx.bumps.treasury]]];
+
+        // The treasury keeps its rent exemption
+        let available = treasury
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+        let lamports = (available as u128 * power / total_power) as u64;
+        if lamports > 0 {
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: treasury.to_account_info(),
+                        to: ctx.accounts.wallet.to_account_info(),
+                    },
+                    treasury_seeds,
+                ),
+                lamports,
+            )?;
+        }
+
+        let mut remaining = ctx.remaining_accounts;
+        for listed_mint in &dao.ragequit_tokens {
+            let [mint, from, to, token_program] =
+                take_accounts(&mut remaining, GovernanceError::MissingRagequitAccounts)?;
+            let mint = InterfaceAccount::<Mint>::try_from(mint)?;
+            let from = InterfaceAccount::<TokenAccount>::try_from(from)?;
+            let to = InterfaceAccount::<TokenAccount>::try_from(to)?;
+            let token_program = Interface::<TokenInterface>::try_from(token_program)?;
+
+            require_keys_eq!(mint.key(), *listed_mint, GovernanceError::InvalidRagequitAccounts);
+            require_keys_eq!(from.mint, *listed_mint, GovernanceError::InvalidRagequitAccounts);
+            require_keys_eq!(from.owner, treasury.key(), GovernanceError::InvalidRagequitAccounts);
+            require_keys_eq!(to.owner, member.wallet, GovernanceError::InvalidRagequitAccounts);
+
+            let amount = (from.amount as u128 * power / total_power) as u64;
+            if amount > 0 {
+                token_interface::transfer_checked(
+                    CpiContext::new_with_signer(
+                        token_program.to_account_info(),
+                        TransferChecked {
+                            from: from.to_account_info(),
+                            mint: mint.to_account_info(),
+                            to: to.to_account_info(),
+                            authority: treasury.to_account_info(),
+                        },
+                        treasury_seeds,
+                    ),
+                    amount,
+                    mint.decimals,
+                )?;
+            }
+            msg!("Ragequit share of mint {}: {}", listed_mint, amount);
+        }
+
+        // Power delegated to the member stays with their delegators, as on removal
+        adjust_total_power(dao, member.voting_power, 0);
+        dao.total_members -= 1;
+        member.voting_power = 0;
+        member.is_active = false;
+
+        msg!("Member {} ragequit with {} lamports", member.wallet, lamports);
+        Ok(())
+    }
+}
+
+// ===== ACTIONS =====
+
+/// Run one action of an executed proposal, taking the accounts it needs
+/// from the front of `remaining`:
+///
+/// - SOL Transfer: recipient
+/// - token Transfer: mint, treasury token account, recipient token account,
+///   token program
+/// - AddMember: member PDA
+/// - RemoveMember: member PDA, then their delegation, delegatee and wallet
+///   if they have delegated
+/// - Custom: the instruction's accounts, then its program
+#[allow(clippy::too_many_arguments)]
+fn execute_action<'info>(
+    dao: &mut Account<'info, DAO>,
+    dao_stats: &mut Account<'info, DAOStats>,
+    treasury: &UncheckedAccount<'info>,
+    treasury_bump: u8,
+    executor: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    action: &ProposalType,
+    remaining: &mut &'info [AccountInfo<'info>],
+    now: i64,
+) -> Result<()> {
+    let dao_key = dao.key();
+    let treasury_seeds: &[&[&[u8]]] = &[&[b"treasury", dao_key.as_ref(), &[treasury_bump]]];
+
+    match action {
+        ProposalType::Transfer { recipient, amount, mint } => match mint {
+            // Transfer SOL from treasury to recipient
+            None => {
+                let [recipient_account] =
+                    take_accounts(remaining, GovernanceError::MissingTransferAccounts)?;
+                require_keys_eq!(
+                    recipient_account.key(),
+                    *recipient,
+                    GovernanceError::InvalidTransferAccounts
+                );
+
+                system_program::transfer(
+                    CpiContext::new_with_signer(
+                        system_program.to_account_info(),
+                        Transfer {
+                            from: treasury.to_account_info(),
+                            to: recipient_account.clone(),
+                        },
+                        treasury_seeds,
+                    ),
+                    *amount,
+                )?;
+                dao_stats.total_treasury_spent += amount;
+                msg!("Transferred {} lamports to {}", amount, recipient);
+            }
+            // Transfer tokens from the treasury's token account to the recipient's
+            Some(mint) => {
+                let [mint_account, from, to, token_program] =
+                    take_accounts(remaining, GovernanceError::MissingTransferAccounts)?;
+                let mint_account = InterfaceAccount::<Mint>::try_from(mint_account)?;
+                let from = InterfaceAccount::<TokenAccount>::try_from(from)?;
+                let to = InterfaceAccount::<TokenAccount>::try_from(to)?;
+                let token_program = Interface::<TokenInterface>::try_from(token_program)?;
+
+                require_keys_eq!(mint_account.key(), *mint, GovernanceError::InvalidTransferAccounts);
+                require_keys_eq!(from.owner, treasury.key(), GovernanceError::InvalidTransferAccounts);
+                require_keys_eq!(to.owner, *recipient, GovernanceError::InvalidTransferAccounts);
+
+                token_interface::transfer_checked(
+                    CpiContext::new_with_signer(
+                        token_program.to_account_info(),
+                        TransferChecked {
+                            from: from.to_account_info(),
+                            mint: mint_account.to_account_info(),
+                            to: to.to_account_info(),
+                            authority: treasury.to_account_info(),
+                        },
+                        treasury_seeds,
+                    ),
+                    *amount,
+                    mint_account.decimals,
+                )?;
+                msg!("Transferred {} of mint {} to {}", amount, mint, recipient);
+            }
+        },
+        ProposalType::ConfigChange {
+            voting_period,
+            timelock_delay,
+            grace_period,
+            quorum_threshold,
+            approval_threshold,
+            optimistic_veto_threshold,
+            optimistic_max_transfer,
+            voting_strategy,
+            deposit,
+        } => {
+            if let Some(period) = voting_period {
+                dao.voting_period = *period;
+            }
+            if let Some(delay) = timelock_delay {
+                dao.timelock_delay = *delay;
+                // Proposals already queued ragequit under the new delay
+                dao.latest_timelock_end = dao.latest_timelock_end.max(now + *delay);
+            }
+            if let Some(grace) = grace_period {
+                dao.grace_period = *grace;
+            }
+            if let Some(quorum) = quorum_threshold {
+                dao.quorum_threshold = *quorum;
+            }
+            if let Some(threshold) = approval_threshold {
+                dao.approval_threshold = *threshold;
+            }
+            if let Some(threshold) = optimistic_veto_threshold {
+                dao.optimistic_veto_threshold = *threshold;
+            }
+            if let Some(max_transfer) = optimistic_max_transfer {
+                dao.optimistic_max_transfer = *max_transfer;
+            }
+            // Proposals already open keep the strategy they were created with
+            if let Some(strategy) = voting_strategy {
+                dao.voting_strategy = strategy.clone();
+            }
+            // Existing proposals settle under the deposit terms they paid
+            if let Some(deposit) = deposit {
+                dao.deposit = deposit.clone();
+            }
+            msg!("DAO configuration updated");
+        }
+        ProposalType::AddMember { member, voting_power } => {
+            let [member_account, stats_account] =
+                take_accounts(remaining, GovernanceError::MissingMemberAccount)?;
+
+            admit_member(
+                dao,
+                dao_stats,
+                member_account,
+                stats_account,
+                executor,
+                system_program,
+                *member,
+                *voting_power,
+                now,
+            )?;
+            msg!("Member {} added with {} voting power", member, voting_power);
+        }
+        ProposalType::RemoveMember { member } => {
+            let [member_account] = take_accounts(remaining, GovernanceError::MissingMemberAccount)?;
+
+            expel_member(dao, member_account, remaining, *member)?;
+            msg!("Member {} removed", member);
+        }
+        ProposalType::SetGuardians { guardians, threshold } => {
+            dao.guardians = guardians.clone();
+            dao.guardian_threshold = *threshold;
+            msg!("Guardian council set to {} of {}", threshold, guardians.len());
+        }
+        ProposalType::SetRagequitTokens { mints } => {
+            dao.ragequit_tokens = mints.clone();
+            msg!("Ragequit token list set to {} mints", mints.len());
+        }
+        ProposalType::Custom { program_id, accounts, data } => {
+            require!(remaining.len() > accounts.len(), GovernanceError::MissingCustomAccounts);
+            let (account_infos, rest) = remaining.split_at(accounts.len() + 1);
+            *remaining = rest;
+
+            require!(
+                account_infos
+                    .iter()
+                    .map(|info| info.key)
+                    .eq(accounts.iter().map(|meta| &meta.pubkey).chain([program_id])),
+                GovernanceError::InvalidCustomAccounts
+            );
+
+            let instruction = Instruction {
+                program_id: *program_id,
+                accounts: accounts
+                    .iter()
+                    .map(|meta| AccountMeta {
+                        pubkey: meta.pubkey,
+                        is_signer: meta.is_signer,
+                        is_writable: meta.is_writable,
+                    })
+                    .collect(),
+                data: data.clone(),
+            };
+
+            let mut account_infos = account_infos.to_vec();
+            account_infos.push(treasury.to_account_info());
+            invoke_signed(&instruction, &account_infos, treasury_seeds)?;
+            msg!("Custom instruction executed on program {}", program_id);
+        }
+    }
+    Ok(())
+}
+
+/// Split the next `N` accounts off `remaining`, or fail with `error`
+fn take_accounts<'info, const N: usize>(
+    remaining: &mut &'info [AccountInfo<'info>],
+    error: GovernanceError,
+) -> Result<&'info [AccountInfo<'info>; N]> {
+    if remaining.len() < N {
+        return Err(error.into());
+    }
+    let (taken, rest) = remaining.split_at(N);
+    *remaining = rest;
+    Ok(taken.try_into().unwrap())
+}
+
+// ===== MEMBERSHIP =====
+
+/// Create or reactivate the member PDA of an executed AddMember proposal
+#[allow(clippy::too_many_arguments)]
+fn admit_member<'info>(
+    dao: &mut Account<'info, DAO>,
+    dao_stats: &Account<'info, DAOStats>,
+    member_account: &AccountInfo<'info>,
+    stats_account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    wallet: Pubkey,
+    voting_power: u64,
+    now: i64,
+) -> Result<()> {
+    let dao_key = dao.key();
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"member", dao_key.as_ref(), wallet.as_ref()], &crate::ID);
+    require_keys_eq!(member_account.key(), address, GovernanceError::InvalidMemberAccount);
+
+    let member = if member_account.owner == &crate::ID {
+        // A removed member rejoins with their history and any delegations to them intact
+        let mut member = Member::try_deserialize(&mut &member_account.try_borrow_data()?[..])?;
+        require!(!member.is_active, GovernanceError::MemberAlreadyActive);
+
+        // In token DAOs the member's power is whatever they still have in escrow
+        if dao.governance_mint.is_none() {
+            member.voting_power = voting_power;
+        }
+        // Their power is back in no open proposal's snapshot, so they rejoin
+        // as a new member would
+        member.joined_at = now;
+        member.delegation_locked_until = member.delegation_locked_until.max(dao.latest_voting_end);
+        member.is_active = true;
+        member
+    } else {
+        create_pda_account(
+            payer,
+            member_account,
+            system_program,
+            8 + std::mem::size_of::<Member>(),
+            &[&[b"member", dao_key.as_ref(), wallet.as_ref(), &[bump]]],
+        )?;
+
+        Member {
+            dao: dao_key,
+            wallet,
+            voting_power,
+            delegate: None,
+            delegated_power: 0,
+            delegated_quadratic_power: 0,
+            voting_locked_until: 0,
+            last_deposit_at: 0,
+            delegation_locked_until: dao.latest_voting_end,
+            proposals_created: 0,
+            votes_cast: 0,
+            joined_at: now,
+            is_active: true,
+        }
+    };
+    member.try_serialize(&mut &mut member_account.try_borrow_mut_data()?[..])?;
+
+    // A rejoining member keeps their stats
+    let (stats_address, stats_bump) = Pubkey::find_program_address(
+        &[b"member_stats", dao_key.as_ref(), wallet.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(stats_account.key(), stats_address, GovernanceError::InvalidMemberAccount);
+    if stats_account.owner != &crate::ID {
+        create_pda_account(
+            payer,
+            stats_account,
+            system_program,
+            8 + std::mem::size_of::<MemberStats>(),
+            &[&[b"member_stats", dao_key.as_ref(), wallet.as_ref(), &[stats_bump]]],
+        )?;
+        new_member_stats(dao_key, wallet, dao_stats)
+            .try_serialize(&mut &mut stats_account.try_borrow_mut_data()?[..])?;
+    }
+
+    dao.total_members += 1;
+    adjust_total_power(dao, 0, member.voting_power);
+    Ok(())
+}
+
+/// Deactivate the member of an executed RemoveMember proposal, taking back
+/// any power they delegated. Power delegated to them stays with their
+/// delegators, who can revoke it as usual.
+fn expel_member<'info>(
+    dao: &mut Account<'info, DAO>,
+    member_account: &'info AccountInfo<'info>,
+    remaining: &mut &'info [AccountInfo<'info>],
+    wallet: Pubkey,
+) -> Result<()> {
+    let dao_key = dao.key();
+    let (address, _) =
+        Pubkey::find_program_address(&[b"member", dao_key.as_ref(), wallet.as_ref()], &crate::ID);
+    require_keys_eq!(member_account.key(), address, GovernanceError::InvalidMemberAccount);
+    require_keys_eq!(*member_account.owner, crate::ID, GovernanceError::InvalidMemberAccount);
+
+    let mut member = Member::try_deserialize(&mut &member_account.try_borrow_data()?[..])?;
+    require!(member.is_active, GovernanceError::MemberNotActive);
+
+    reclaim_delegation(dao_key, &mut member, remaining)?;
+    member.is_active = false;
+    member.try_serialize(&mut &mut member_account.try_borrow_mut_data()?[..])?;
+
+    dao.total_members -= 1;
+    adjust_total_power(dao, member.voting_power, 0);
+    Ok(())
+}
+
+/// Close the delegation of a member being removed and take its power back
+/// from the delegatee, taking the delegation, delegatee and member wallet
+/// accounts from `remaining` if the member has delegated
+fn reclaim_delegation<'info>(
+    dao_key: Pubkey,
+    member: &mut Member,
+    remaining: &mut &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let Some(delegatee_wallet) = member.delegate else {
+        return Ok(());
+    };
+
+    let [delegation, delegatee, member_wallet] =
+        take_accounts(remaining, GovernanceError::MissingDelegationAccounts)?;
+    let delegation = Account::<VoteDelegation>::try_from(delegation)?;
+    let mut delegatee = Account::<Member>::try_from(delegatee)?;
+
+    let (delegation_address, _) = Pubkey::find_program_address(
+        &[b"delegation", dao_key.as_ref(), member.wallet.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(delegation.key(), delegation_address, GovernanceError::InvalidDelegationAccounts);
+    require_keys_eq!(delegatee.dao, dao_key, GovernanceError::InvalidDelegationAccounts);
+    require_keys_eq!(delegatee.wallet, delegatee_wallet, GovernanceError::InvalidDelegationAccounts);
+    require_keys_eq!(member_wallet.key(), member.wallet, GovernanceError::InvalidDelegationAccounts);
+
+    // The removed member can't vote again, so unlike revoke_delegation this
+    // needn't wait for the delegatee's open votes
+    delegatee.delegated_power -= delegation.delegated_power;
+    delegatee.delegated_quadratic_power -= isqrt(delegation.delegated_power);
+    delegatee.exit(&crate::ID)?;
+    delegation.close(member_wallet.clone())?;
+    member.delegate = None;
+    Ok(())
+}
+
+/// Power given by add_member or an AddMember proposal: required outside
+/// token DAOs, where admins assign it, and zero inside them, where members
+/// deposit governance tokens instead
+fn check_assigned_power(dao: &DAO, voting_power: u64) -> Result<()> {
+    if dao.governance_mint.is_some() {
+        require!(voting_power == 0, GovernanceError::TokenWeightedDAO);
+    } else {
+        require!(voting_power > 0, GovernanceError::InvalidVotingPower);
+    }
+    Ok(())
+}
+
+/// Create a program account at a PDA, even if someone already sent it lamports
+fn create_pda_account<'info>(
+    payer: &Signer<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let lamports = account.lamports();
+
+    if lamports == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                CreateAccount {
+                    from: payer.to_account_info(),
+                    to: account.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        );
+    }
+
+    if lamports < rent {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: account.to_account_info(),
+                },
+            ),
+            rent - lamports,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Allocate {
+                account_to_allocate: account.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Assign {
+                account_to_assign: account.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        &crate::ID,
+    )
+}
+
+/// Check a proposal's actions against the per-proposal limits, that
+/// optimistic proposals are enabled and only pay out Transfers, and that
+/// multi-option proposals have a valid number of options
+fn validate_actions(dao: &DAO, kind: &ProposalKind, actions: &[ProposalType]) -> Result<()> {
+    require!(
+        !actions.is_empty() && actions.len() <= MAX_PROPOSAL_ACTIONS,
+        GovernanceError::InvalidActionCount
+    );
+    match kind {
+        ProposalKind::Standard => {}
+        ProposalKind::Optimistic => {
+            require!(dao.optimistic_veto_threshold > 0, GovernanceError::OptimisticProposalsDisabled);
+            require!(
+                actions.iter().all(|action| matches!(action, ProposalType::Transfer { .. })),
+                GovernanceError::OptimisticTransfersOnly
+            );
+            // Nothing stops a small optimistic proposal but No votes, so each
+            // asset it pays out is capped in total
+            for action in actions {
+                let ProposalType::Transfer { mint, .. } = action else {
+                    continue;
+                };
+                let total = actions
+                    .iter()
+                    .filter_map(|other| match other {
+                        ProposalType::Transfer { amount, mint: other_mint, .. }
+                            if other_mint == mint =>
+                        {
+                            Some(*amount as u128)
+                        }
+                        _ => None,
+                    })
+                    .sum::<u128>();
+                require!(
+                    total <= dao.optimistic_max_transfer as u128,
+                    GovernanceError::OptimisticTransferTooLarge
+                );
+            }
+        }
+        ProposalKind::Plurality => {
+            require!(actions.len() >= 2, GovernanceError::InvalidOptionCount);
+        }
+        ProposalKind::RankedChoice => {
+            require!(
+                actions.len() >= 2 && actions.len() <= MAX_RANKED_OPTIONS,
+                GovernanceError::InvalidOptionCount
+            );
+        }
+    }
+
+    // All actions execute in one transaction, so Custom instruction limits
+    // apply to the proposal as a whole
+    let mut instruction_accounts = 0;
+    let mut instruction_data = 0;
+    for action in actions {
+        match action {
+            ProposalType::AddMember { voting_power, .. } => {
+                check_assigned_power(dao, *voting_power)?;
+            }
+            ProposalType::Custom { accounts, data, .. } => {
+                instruction_accounts += accounts.len();
+                instruction_data += data.len();
+            }
+            ProposalType::SetGuardians { guardians, threshold } => {
+                check_guardian_set(guardians, *threshold)?;
+            }
+            ProposalType::SetRagequitTokens { mints } => {
+                require!(
+                    mints.len() <= MAX_RAGEQUIT_TOKENS
+                        && mints.iter().enumerate().all(|(i, mint)| !mints[..i].contains(mint)),
+                    GovernanceError::InvalidRagequitTokens
+                );
+            }
+            ProposalType::ConfigChange {
+                voting_period,
+                timelock_delay,
+                grace_period,
+                quorum_threshold,
+                approval_threshold,
+                optimistic_veto_threshold,
+                deposit,
+                ..
+            } => {
+                // The same bounds create_dao enforces
+                if let Some(period) = voting_period {
+                    require!(*period > 0, GovernanceError::InvalidVotingPeriod);
+                }
+                if let Some(delay) = timelock_delay {
+                    require!(*delay >= 0, GovernanceError::InvalidTimelock);
+                }
+                if let Some(period) = grace_period {
+                    require!(*period > 0, GovernanceError::InvalidGracePeriod);
+                }
+                if let Some(threshold) = quorum_threshold {
+                    require!(*threshold > 0 && *threshold <= 10000, GovernanceError::InvalidQuorum);
+                }
+                if let Some(threshold) = approval_threshold {
+                    require!(*threshold > 0 && *threshold <= 10000, GovernanceError::InvalidThreshold);
+                }
+                if let Some(threshold) = optimistic_veto_threshold {
+                    require!(*threshold <= 10000, GovernanceError::InvalidOptimisticThreshold);
+                }
+                if let Some(deposit) = deposit {
+                    check_deposit_config(dao, deposit)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    require!(
+        instruction_accounts <= MAX_INSTRUCTION_ACCOUNTS && instruction_data <= MAX_INSTRUCTION_DATA,
+        GovernanceError::InstructionTooLarge
+    );
+    Ok(())
+}
+
+/// Ragequit shares are a member's power over the DAO's total, so the
+/// authority can't change either while a queued proposal can be ragequit.
+/// Token DAOs can't ragequit.
+fn check_ragequit_window(dao: &DAO, now: i64) -> Result<()> {
+    require!(
+        dao.governance_mint.is_some() || now >= dao.latest_timelock_end,
+        GovernanceError::RagequitWindowOpen
+    );
+    Ok(())
+}
+
+/// Check a guardian council: up to MAX_GUARDIANS distinct keys, and a
+/// threshold of 1..=N, or 0 with no guardians to disable vetoes
+fn check_guardian_set(guardians: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(guardians.len() <= MAX_GUARDIANS, GovernanceError::InvalidGuardianSet);
+    require!(
+        guardians.iter().enumerate().all(|(i, guardian)| !guardians[..i].contains(guardian)),
+        GovernanceError::InvalidGuardianSet
+    );
+    require!(
+        (threshold as usize) <= guardians.len() && (threshold == 0) == guardians.is_empty(),
+        GovernanceError::InvalidGuardianSet
+    );
+    Ok(())
+}
+
+/// Check proposal deposit terms: token deposits need a governance mint, and
+/// the refund threshold is in basis points
+fn check_deposit_config(dao: &DAO, deposit: &DepositConfig) -> Result<()> {
+    require!(
+        deposit.refund_threshold <= 10000
+            && !(matches!(deposit.asset, DepositAsset::GovernanceToken) && dao.governance_mint.is_none()),
+        GovernanceError::InvalidDepositConfig
+    );
+    Ok(())
+}
+
+/// Stats for a member joining now, counting participation from the next
+/// proposal to open
+fn new_member_stats(dao: Pubkey, wallet: Pubkey, dao_stats: &DAOStats) -> MemberStats {
+    MemberStats {
+        member: wallet,
+        dao,
+        total_proposals_created: 0,
+        total_votes_cast: 0,
+        proposals_passed: 0,
+        proposals_failed: 0,
+        voting_power_received: 0,
+        voting_power_delegated: 0,
+        participation_rate: 0,
+        proposals_at_join: dao_stats.total_proposals_activated,
+    }
+}
+
+/// Share of the proposals opened since the member joined that they voted on,
+/// in basis points. Stored in `MemberStats` whenever the member votes, so it
+/// goes stale as later proposals open.
+fn participation_rate(member_stats: &MemberStats, dao_stats: &DAOStats) -> u64 {
+    let eligible = dao_stats
+        .total_proposals_activated
+        .saturating_sub(member_stats.proposals_at_join)
+        .max(1);
+    (member_stats.total_votes_cast * 10000 / eligible).min(10000)
+}
+
+/// Count a decided vote in the DAO's and proposer's stats. Turnout is the
+/// share of the snapshot that voted, averaged over every decided proposal.
+fn record_outcome(
+    dao_stats: &mut DAOStats,
+    proposer_stats: &mut MemberStats,
+    proposal: &Proposal,
+    passed: bool,
+    now: i64,
+) {
+    if passed {
+        dao_stats.total_proposals_passed += 1;
+        proposer_stats.proposals_passed += 1;
+    } else {
+        dao_stats.total_proposals_failed += 1;
+        proposer_stats.proposals_failed += 1;
+    }
+
+    let turnout = if proposal.voting_power_snapshot > 0 {
+        (proposal.total_votes as u128 * 10000 / proposal.voting_power_snapshot as u128) as u64
+    } else {
+        0
+    };
+    dao_stats.total_turnout += turnout;
+    dao_stats.average_participation_rate = dao_stats.total_turnout
+        / (dao_stats.total_proposals_passed + dao_stats.total_proposals_failed);
+    dao_stats.last_activity_at = now;
+}
+
+/// Whether a finished vote reached quorum and the approval threshold. Votes
+/// and the snapshot are both weighted by the proposal's strategy, so quorum
+/// and approval compare like with like.
+///
+/// Optimistic proposals have no quorum and are approved unless No votes
+/// reach the optimistic veto threshold of the snapshot. Multi-option
+/// proposals only need quorum.
+fn tally_result(dao: &DAO, proposal: &Proposal) -> (bool, bool) {
+    if matches!(proposal.kind, ProposalKind::Optimistic) {
+        let vetoed = proposal.no_votes as u128 * 10000
+            >= proposal.optimistic_veto_threshold as u128 * proposal.voting_power_snapshot as u128;
+        return (true, !vetoed);
+    }
+
+    let quorum_reached = proposal.voting_power_snapshot > 0
+        && (proposal.total_votes as u128 * 10000) / proposal.voting_power_snapshot as u128
+            >= dao.quorum_threshold as u128;
+
+    // Multi-option proposals always have a winner once quorum is reached
+    if matches!(proposal.kind, ProposalKind::Plurality | ProposalKind::RankedChoice) {
+        return (quorum_reached, true);
+    }
+
+    let approval_rate = if proposal.total_votes > 0 {
+        (proposal.yes_votes as u128 * 10000) / proposal.total_votes as u128
+    } else {
+        0
+    };
+
+    (quorum_reached, approval_rate >= dao.approval_threshold as u128)
+}
+
+/// Move the DAO's power totals from a member having `old` voting power to
+/// `new`. The quadratic total is the sum of each member's square root.
+fn adjust_total_power(dao: &mut DAO, old: u64, new: u64) {
+    dao.total_voting_power = dao.total_voting_power - old + new;
+    dao.total_quadratic_power = dao.total_quadratic_power - isqrt(old) + isqrt(new);
+}
+
+/// Weight a vote of `voting_power`, or `quadratic_power` under quadratic
+/// voting, cast at `now` adds to the proposal's tallies. Conviction weight is
+/// the share of the voting period the vote stays committed for, so a vote
+/// cast when voting opens counts in full.
+fn vote_weight(proposal: &Proposal, voting_power: u64, quadratic_power: u64, now: i64) -> u64 {
+    match proposal.voting_strategy {
+        VotingStrategy::Linear => voting_power,
+        VotingStrategy::Quadratic => quadratic_power,
+        VotingStrategy::Conviction => {
+            let period = (proposal.end_time - proposal.start_time).max(1) as u128;
+            let committed = (proposal.end_time - now).clamp(0, period as i64) as u128;
+            (voting_power as u128 * committed / period) as u64
+        }
+    }
+}
+
+/// Quadratic voting power of a member and their delegators. Each delegator's
+/// power is square-rooted separately, as in `DAO.total_quadratic_power`, so
+/// delegating doesn't lower the turnout a proposal can reach.
+fn quadratic_power(member: &Member) -> u64 {
+    isqrt(member.voting_power) + member.delegated_quadratic_power
+}
+
+/// Integer square root, rounded down
+fn isqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method from above converges to the floor
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+/// Tally a vote of `vote_type` counts towards. Yes/No/Abstain votes go to
+/// standard and optimistic proposals, choices to plurality proposals and
+/// full rankings of the options to ranked-choice proposals.
+fn vote_tally<'a>(proposal: &'a mut Proposal, vote_type: &VoteType) -> Result<&'a mut u64> {
+    let options = proposal.actions.len();
+    match (&proposal.kind, vote_type) {
+        (ProposalKind::Standard | ProposalKind::Optimistic, VoteType::Yes) => Ok(&mut proposal.yes_votes),
+        (ProposalKind::Standard | ProposalKind::Optimistic, VoteType::No) => Ok(&mut proposal.no_votes),
+        (ProposalKind::Standard | ProposalKind::Optimistic, VoteType::Abstain) => {
+            Ok(&mut proposal.abstain_votes)
+        }
+        (ProposalKind::Plurality, VoteType::Choice { option }) if (*option as usize) < options => {
+            Ok(&mut proposal.option_votes[*option as usize])
+        }
+        (ProposalKind::RankedChoice, VoteType::Ranking { options: ranking }) => {
+            let index = ranking_index(ranking, options).ok_or(GovernanceError::InvalidVoteType)?;
+            Ok(&mut proposal.option_votes[index])
+        }
+        _ => err!(GovernanceError::InvalidVoteType),
+    }
+}
+
+/// Tallies a proposal keeps in `option_votes`: one per option for plurality,
+/// one per full ranking of the options for ranked choice
+fn tally_slots(kind: &ProposalKind, options: usize) -> usize {
+    match kind {
+        ProposalKind::Standard | ProposalKind::Optimistic => 0,
+        ProposalKind::Plurality => options,
+        ProposalKind::RankedChoice => (1..=options).product(),
+    }
+}
+
+/// Position of a full ranking of `options` options among all of them in
+/// lexicographic order (its Lehmer code), or None if it isn't a permutation
+fn ranking_index(ranking: &[u8], options: usize) -> Option<usize> {
+    if ranking.len() != options
+        || (0..options).any(|option| !ranking.contains(&(option as u8)))
+    {
+        return None;
+    }
+    Some(ranking.iter().enumerate().fold(0, |index, (i, &option)| {
+        let smaller_later = ranking[i + 1..].iter().filter(|&&later| later < option).count();
+        index * (options - i) + smaller_later
+    }))
+}
+
+/// The full ranking at `index` in lexicographic order; the inverse of ranking_index
+fn nth_ranking(mut index: usize, options: usize) -> Vec<u8> {
+    let mut unused: Vec<u8> = (0..options as u8).collect();
+    let mut ranking = Vec::with_capacity(options);
+    for i in 0..options {
+        let block: usize = (1..options - i).product();
+        ranking.push(unused.remove(index / block));
+        index %= block;
+    }
+    ranking
+}
+
+/// Winning option of a plurality or ranked-choice proposal.
+///
+/// Plurality takes the option with the most votes. Ranked choice runs an
+/// instant runoff: each ballot counts for its highest-ranked remaining
+/// option, and the option with the fewest is eliminated until one holds a
+/// majority. Ties go to the lower-numbered option.
+fn winning_option(proposal: &Proposal) -> u8 {
+    let options = proposal.actions.len();
+    if matches!(proposal.kind, ProposalKind::Plurality) {
+        return leading_option(&proposal.option_votes, &vec![false; options]);
+    }
+
+    let mut eliminated = vec![false; options];
+    loop {
+        let mut counts = vec![0u64; options];
+        for (index, &weight) in proposal.option_votes.iter().enumerate() {
+            if weight == 0 {
+                continue;
+            }
+            // Ballots rank every option, so one always remains
+            if let Some(&top) = nth_ranking(index, options)
+                .iter()
+                .find(|&&option| !eliminated[option as usize])
+            {
+                counts[top as usize] += weight;
+            }
+        }
+
+        let leader = leading_option(&counts, &eliminated);
+        let ballots: u128 = counts.iter().map(|&count| count as u128).sum();
+        if counts[leader as usize] as u128 * 2 > ballots
+            || eliminated.iter().filter(|&&out| !out).count() == 1
+        {
+            return leader;
+        }
+
+        // Eliminate the fewest votes, the higher-numbered option on ties
+        let last = (0..options)
+            .filter(|&option| !eliminated[option])
+            .fold(None, |last: Option<usize>, option| match last {
+                Some(last) if counts[last] < counts[option] => Some(last),
+                _ => Some(option),
+            });
+        if let Some(last) = last {
+            eliminated[last] = true;
+        }
+    }
+}
+
+/// Remaining option with the most votes, the lower-numbered one on ties
+fn leading_option(counts: &[u64], eliminated: &[bool]) -> u8 {
+    (0..counts.len())
+        .filter(|&option| !eliminated[option])
+        .fold(None, |best: Option<usize>, option| match best {
+            Some(best) if counts[best] >= counts[option] => Some(best),
+            _ => Some(option),
+        })
+        .unwrap_or(0) as u8
+}
+
+/// Proposal space the actions need beyond `size_of::<Proposal>()`
+fn actions_space(actions: &[ProposalType]) -> usize {
+    actions
+        .iter()
+        .map(|action| {
+            std::mem::size_of::<ProposalType>()
+                + match action {
+                    ProposalType::Custom { accounts, data, .. } => {
+                        accounts.len() * std::mem::size_of::<ProposalAccountMeta>() + data.len()
+                    }
+                    ProposalType::SetGuardians { guardians, .. } => {
+                        guardians.len() * std::mem::size_of::<Pubkey>()
+                    }
+                    ProposalType::SetRagequitTokens { mints } => mints.len() * std::mem::size_of::<Pubkey>(),
+                    _ => 0,
+                }
+        })
+        .sum()
+}
+
+// ===== ACCOUNT CONTEXTS =====
+
+#[derive(Accounts)]
+pub struct CreateDAO<'info> {
+    #[account(
+        init,
+        payer = authority,
+        // Extra space for name string, a full guardian council and ragequit token list
+        space = 8
+            + std::mem::size_of::<DAO>()
+            + 200
+            + (MAX_GUARDIANS + MAX_RAGEQUIT_TOKENS) * std::mem::size_of::<Pubkey>()
+    )]
+    pub dao: Account<'info, DAO>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Treasury PDA holding the DAO's SOL and token accounts
+    #[account(
+        seeds = [b"treasury", dao.key().as_ref()],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Makes this a token DAO, with voting power from escrowed tokens of this mint
+    pub governance_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<DAOStats>(),
+        seeds = [b"stats", dao.key().as_ref()],
+        bump
+    )]
+    pub dao_stats: Account<'info, DAOStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(title: String, description: String, actions: Vec<ProposalType>, kind: ProposalKind)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub dao: Account<'info, DAO>,
+
+    #[account(
+        init,
+        payer = proposer,
+        // Extra space for strings, actions and option tallies
+        space = 8
+            + std::mem::size_of::<Proposal>()
+            + 500
+            + actions_space(&actions)
+            + tally_slots(&kind, actions.len()) * std::mem::size_of::<u64>()
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        seeds = [b"member", dao.key().as_ref(), proposer.key().as_ref()],
+        bump
+    )]
+    pub member: Account<'info, Member>,
+
+    #[account(
+        mut,
+        seeds = [b"member_stats", dao.key().as_ref(), proposer.key().as_ref()],
+        bump
+    )]
+    pub member_stats: Account<'info, MemberStats>,
+
+    /// CHECK: Deposit escrow PDA; holds SOL deposits and owns token deposits
+    #[account(
+        mut,
+        seeds = [b"deposit", proposal.key().as_ref()],
+        bump
+    )]
+    pub deposit_escrow: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(title: String, description: String, actions: Vec<ProposalType>)]
+pub struct UpdateDraft<'info> {
+    pub dao: Account<'info, DAO>,
+
+    #[account(
+        mut,
+        has_one = dao,
+        realloc = 8
+            + std::mem::size_of::<Proposal>()
+            + 500
+            + actions_space(&actions)
+            + tally_slots(&proposal.kind, actions.len()) * std::mem::size_of::<u64>(),
+        realloc::payer = proposer,
+        realloc::zero = false
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ActivateProposal<'info> {
+    #[account(mut)]
+    pub dao: Account<'info, DAO>,
+
+    #[account(mut, has_one = dao)]
+    pub proposal: Account<'info, Proposal>,
+
+    pub proposer: Signer<'info>,
+
+    #[account(
+        seeds = [b"member", dao.key().as_ref(), proposer.key().as_ref()],
+        bump
+    )]
+    pub member: Account<'info, Member>,
+
+    #[account(
+        mut,
+        seeds = [b"stats", dao.key().as_ref()],
+        bump
+    )]
+    pub dao_stats: Account<'info, DAOStats>,
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + std::mem::size_of::<Vote>() + MAX_COMMENT_LEN + MAX_RANKED_OPTIONS,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, Vote>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"member", proposal.dao.as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub member: Account<'info, Member>,
+
+    #[account(
+        mut,
+        seeds = [b"stats", proposal.dao.as_ref()],
+        bump
+    )]
+    pub dao_stats: Account<'info, DAOStats>,
+
+    #[account(
+        mut,
+        seeds = [b"member_stats", proposal.dao.as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub member_stats: Account<'info, MemberStats>,
 
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CreateProposal<'info> {
+pub struct ChangeVote<'info> {
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, Vote>,
+
+    pub voter: Signer<'info>,
+
+    #[account(
+        seeds = [b"member", proposal.dao.as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub member: Account<'info, Member>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawVote<'info> {
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        close = voter,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, Vote>,
+
+    #[account(
+        mut,
+        seeds = [b"stats", proposal.dao.as_ref()],
+        bump
+    )]
+    pub dao_stats: Account<'info, DAOStats>,
+
+    #[account(
+        mut,
+        seeds = [b"member_stats", proposal.dao.as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub member_stats: Account<'info, MemberStats>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    #[account(
+        seeds = [b"member", proposal.dao.as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub member: Account<'info, Member>,
+}
+
+#[derive(Accounts)]
+pub struct QueueProposal<'info> {
     #[account(mut)]
     pub dao: Account<'info, DAO>,
 
-    #[account(
-        init,
-        payer = proposer,
-        space = 8 + std::mem::size_of::<Proposal>() + 500 // Extra space for strings
-    )]
+    #[account(mut, has_one = dao)]
     pub proposal: Account<'info, Proposal>,
 
-    #[account(mut)]
-    pub proposer: Signer<'info>,
-
     #[account(
-        seeds = [b"member", dao.key().as_ref(), proposer.key().as_ref()],
+        mut,
+        seeds = [b"stats", dao.key().as_ref()],
         bump
     )]
-    pub member: Account<'info, Member>,
+    pub dao_stats: Account<'info, DAOStats>,
 
-    pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        seeds = [b"member_stats", dao.key().as_ref(), proposal.proposer.as_ref()],
+        bump
+    )]
+    pub proposer_stats: Account<'info, MemberStats>,
 }
 
 #[derive(Accounts)]
-pub struct CastVote<'info> {
+pub struct ExecuteProposal<'info> {
     #[account(mut)]
+    pub dao: Account<'info, DAO>,
+
+    #[account(mut, has_one = dao)]
     pub proposal: Account<'info, Proposal>,
 
+    /// CHECK: DAO treasury PDA, pays out Transfer actions and signs Custom
+    /// action instructions
     #[account(
-        init,
-        payer = voter,
-        space = 8 + std::mem::size_of::<Vote>() + 200, // Extra for comment
-        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        mut,
+        seeds = [b"treasury", dao.key().as_ref()],
         bump
     )]
-    pub vote_record: Account<'info, Vote>,
-
-    #[account(mut)]
-    pub voter: Signer<'info>,
+    pub treasury: UncheckedAccount<'info>,
 
     #[account(
-        seeds = [b"member", proposal.dao.as_ref(), voter.key().as_ref()],
+        mut,
+        seeds = [b"stats", dao.key().as_ref()],
         bump
     )]
-    pub member: Account<'info, Member>,
+    pub dao_stats: Account<'info, DAOStats>,
+
+    /// Pays for new member accounts
+    #[account(mut)]
+    pub executor: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct QueueProposal<'info> {
+pub struct CancelProposal<'info> {
     pub dao: Account<'info, DAO>,
 
-    #[account(mut)]
+    #[account(mut, has_one = dao)]
     pub proposal: Account<'info, Proposal>,
+
+    pub canceller: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct ExecuteProposal<'info> {
-    #[account(mut)]
+pub struct FinalizeProposal<'info> {
     pub dao: Account<'info, DAO>,
 
-    #[account(mut)]
+    #[account(mut, has_one = dao)]
     pub proposal: Account<'info, Proposal>,
 
-    /// CHECK: Treasury account
-    #[account(mut)]
-    pub treasury_account: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"stats", dao.key().as_ref()],
+        bump
+    )]
+    pub dao_stats: Account<'info, DAOStats>,
+
+    #[account(
+        mut,
+        seeds = [b"member_stats", dao.key().as_ref(), proposal.proposer.as_ref()],
+        bump
+    )]
+    pub proposer_stats: Account<'info, MemberStats>,
+}
 
-    /// CHECK: Recipient for transfers
+#[derive(Accounts)]
+pub struct SettleDeposit<'info> {
+    pub dao: Account<'info, DAO>,
+
+    #[account(mut, has_one = dao, has_one = proposer)]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: Proposer wallet, refunded the deposit and escrow rent
     #[account(mut)]
-    pub recipient_account: AccountInfo<'info>,
+    pub proposer: UncheckedAccount<'info>,
 
-    pub system_program: Program<'info, System>,
+    /// CHECK: Treasury PDA, receives forfeited deposits
+    #[account(
+        mut,
+        seeds = [b"treasury", dao.key().as_ref()],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Deposit escrow PDA of the proposal
+    #[account(
+        mut,
+        seeds = [b"deposit", proposal.key().as_ref()],
+        bump
+    )]
+    pub deposit_escrow: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
-pub struct CancelProposal<'info> {
+pub struct VetoProposal<'info> {
     pub dao: Account<'info, DAO>,
 
-    #[account(mut)]
+    #[account(mut, has_one = dao)]
     pub proposal: Account<'info, Proposal>,
 
-    pub canceller: Signer<'info>,
+    #[account(
+        init,
+        payer = guardian,
+        space = 8 + std::mem::size_of::<Veto>()
+            + MAX_GUARDIANS * std::mem::size_of::<Pubkey>()
+            + MAX_VETO_REASON_LEN,
+        seeds = [b"veto", proposal.key().as_ref()],
+        bump
+    )]
+    pub veto: Account<'info, Veto>,
+
+    #[account(mut)]
+    pub guardian: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -480,20 +2428,71 @@ pub struct AddMember<'info> {
     /// CHECK: New member wallet
     pub new_member: AccountInfo<'info>,
 
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<MemberStats>(),
+        seeds = [b"member_stats", dao.key().as_ref(), new_member.key().as_ref()],
+        bump
+    )]
+    pub member_stats: Account<'info, MemberStats>,
+
+    #[account(
+        seeds = [b"stats", dao.key().as_ref()],
+        bump
+    )]
+    pub dao_stats: Account<'info, DAOStats>,
+
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct DelegateVote<'info> {
+pub struct RemoveMember<'info> {
+    #[account(mut)]
     pub dao: Account<'info, DAO>,
 
+    #[account(
+        mut,
+        seeds = [b"member", dao.key().as_ref(), member.wallet.as_ref()],
+        bump
+    )]
+    pub member: Account<'info, Member>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateVotingPower<'info> {
     #[account(mut)]
+    pub dao: Account<'info, DAO>,
+
+    #[account(
+        mut,
+        seeds = [b"member", dao.key().as_ref(), member.wallet.as_ref()],
+        bump
+    )]
+    pub member: Account<'info, Member>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DelegateVote<'info> {
+    pub dao: Account<'info, DAO>,
+
+    #[account(
+        mut,
+        seeds = [b"member", dao.key().as_ref(), delegator.key().as_ref()],
+        bump
+    )]
     pub member: Account<'info, Member>,
 
     #[account(
         init,
         payer = delegator,
-        space = 8 + std::mem::size_of::<VoteDelegation>()
+        space = 8 + std::mem::size_of::<VoteDelegation>(),
+        seeds = [b"delegation", dao.key().as_ref(), delegator.key().as_ref()],
+        bump
     )]
     pub delegation: Account<'info, VoteDelegation>,
 
@@ -504,22 +2503,175 @@ pub struct DelegateVote<'info> {
     pub delegatee_wallet: AccountInfo<'info>,
 
     #[account(
+        mut,
         seeds = [b"member", dao.key().as_ref(), delegatee_wallet.key().as_ref()],
         bump
     )]
     pub delegatee: Account<'info, Member>,
 
+    #[account(
+        mut,
+        seeds = [b"member_stats", dao.key().as_ref(), delegator.key().as_ref()],
+        bump
+    )]
+    pub delegator_stats: Account<'info, MemberStats>,
+
+    #[account(
+        mut,
+        seeds = [b"member_stats", dao.key().as_ref(), delegatee_wallet.key().as_ref()],
+        bump
+    )]
+    pub delegatee_stats: Account<'info, MemberStats>,
+
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct RevokeDelegation<'info> {
-    #[account(mut)]
+    pub dao: Account<'info, DAO>,
+
+    #[account(
+        mut,
+        seeds = [b"member", dao.key().as_ref(), delegator.key().as_ref()],
+        bump
+    )]
     pub member: Account<'info, Member>,
 
+    #[account(
+        mut,
+        close = delegator,
+        seeds = [b"delegation", dao.key().as_ref(), delegator.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, VoteDelegation>,
+
+    #[account(
+        mut,
+        seeds = [b"member", dao.key().as_ref(), delegation.delegatee.as_ref()],
+        bump
+    )]
+    pub delegatee: Account<'info, Member>,
+
+    #[account(mut)]
     pub delegator: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct DepositGovernanceTokens<'info> {
+    #[account(mut)]
+    pub dao: Account<'info, DAO>,
+
+    #[account(
+        mut,
+        seeds = [b"member", dao.key().as_ref(), depositor.key().as_ref()],
+        bump
+    )]
+    pub member: Account<'info, Member>,
+
+    #[account(
+        constraint = dao.governance_mint == Some(governance_mint.key()) @ GovernanceError::InvalidGovernanceMint
+    )]
+    pub governance_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Escrow: the member PDA's associated token account
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        associated_token::mint = governance_mint,
+        associated_token::authority = member,
+        associated_token::token_program = token_program
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = governance_mint,
+        token::authority = depositor,
+        token::token_program = token_program
+    )]
+    pub depositor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawGovernanceTokens<'info> {
+    #[account(mut)]
+    pub dao: Account<'info, DAO>,
+
+    #[account(
+        mut,
+        seeds = [b"member", dao.key().as_ref(), depositor.key().as_ref()],
+        bump
+    )]
+    pub member: Account<'info, Member>,
+
+    #[account(
+        constraint = dao.governance_mint == Some(governance_mint.key()) @ GovernanceError::InvalidGovernanceMint
+    )]
+    pub governance_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = governance_mint,
+        associated_token::authority = member,
+        associated_token::token_program = token_program
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = governance_mint,
+        token::token_program = token_program
+    )]
+    pub depositor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub depositor: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct Ragequit<'info> {
+    #[account(mut)]
+    pub dao: Account<'info, DAO>,
+
+    #[account(has_one = dao)]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"member", dao.key().as_ref(), wallet.key().as_ref()],
+        bump
+    )]
+    pub member: Account<'info, Member>,
+
+    /// CHECK: The member's vote record on the proposal, empty if they didn't vote
+    #[account(
+        seeds = [b"vote", proposal.key().as_ref(), wallet.key().as_ref()],
+        bump
+    )]
+    pub vote_record: UncheckedAccount<'info>,
+
+    /// CHECK: Treasury PDA, pays out the member's share
+    #[account(
+        mut,
+        seeds = [b"treasury", dao.key().as_ref()],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
 // ===== ERRORS =====
 
 #[error_code]
@@ -530,6 +2682,9 @@ pub enum GovernanceError {
     #[msg("Invalid timelock delay")]
     InvalidTimelock,
 
+    #[msg("Invalid grace period")]
+    InvalidGracePeriod,
+
     #[msg("Invalid quorum threshold (must be 0-10000 basis points)")]
     InvalidQuorum,
 
@@ -572,9 +2727,162 @@ pub enum GovernanceError {
     #[msg("Invalid voting power")]
     InvalidVotingPower,
 
+    #[msg("Member joined after voting opened on this proposal")]
+    JoinedAfterSnapshot,
+
     #[msg("Delegatee is not active")]
     DelegateeNotActive,
 
     #[msg("No delegation to revoke")]
     NoDelegationToRevoke,
+
+    #[msg("Voting power is delegated; revoke the delegation to vote directly")]
+    VotingPowerDelegated,
+
+    #[msg("Member has an active delegation")]
+    ActiveDelegation,
+
+    #[msg("Cannot delegate to yourself")]
+    InvalidDelegatee,
+
+    #[msg("Delegated power cannot be delegated again")]
+    DelegationChain,
+
+    #[msg("Voting power is committed to proposals still in their voting period")]
+    VotesStillActive,
+
+    #[msg("Member proposals need the member account")]
+    MissingMemberAccount,
+
+    #[msg("Member account does not match the proposal")]
+    InvalidMemberAccount,
+
+    #[msg("Member is already active")]
+    MemberAlreadyActive,
+
+    #[msg("Removing a delegated member needs their delegation, delegatee and wallet")]
+    MissingDelegationAccounts,
+
+    #[msg("Delegation accounts do not match the member")]
+    InvalidDelegationAccounts,
+
+    #[msg("Custom instruction has too many accounts or too much data")]
+    InstructionTooLarge,
+
+    #[msg("Transfer proposals need the recipient, or the mint, token accounts and token program")]
+    MissingTransferAccounts,
+
+    #[msg("Transfer accounts do not match the proposal")]
+    InvalidTransferAccounts,
+
+    #[msg("Proposals need between 1 and 8 actions")]
+    InvalidActionCount,
+
+    #[msg("Custom actions need their instruction's accounts and program")]
+    MissingCustomAccounts,
+
+    #[msg("Custom action accounts do not match its instruction")]
+    InvalidCustomAccounts,
+
+    #[msg("More remaining accounts than the proposal's actions use")]
+    UnusedActionAccounts,
+
+    #[msg("Proposal is not a draft")]
+    ProposalNotDraft,
+
+    #[msg("Grace period for execution has ended")]
+    ProposalExpired,
+
+    #[msg("Grace period for execution has not ended")]
+    GracePeriodNotEnded,
+
+    #[msg("Proposal passed; queue it instead")]
+    ProposalPassed,
+
+    #[msg("Proposal has no outcome to finalize")]
+    NothingToFinalize,
+
+    #[msg("Guardian set must be distinct keys with a threshold of 1 to N, or empty")]
+    InvalidGuardianSet,
+
+    #[msg("DAO has no guardians")]
+    NoGuardians,
+
+    #[msg("Signer is not a guardian")]
+    NotGuardian,
+
+    #[msg("Not enough guardians signed the veto")]
+    InsufficientGuardians,
+
+    #[msg("Only succeeded proposals can be vetoed")]
+    CannotVetoProposal,
+
+    #[msg("Guardians cannot veto a change to the guardian council")]
+    CannotVetoGuardianChange,
+
+    #[msg("Veto reason too long")]
+    VetoReasonTooLong,
+
+    #[msg("Deposit needs a governance mint for token deposits and a refund threshold of at most 10000")]
+    InvalidDepositConfig,
+
+    #[msg("Missing accounts for a token deposit")]
+    MissingDepositAccounts,
+
+    #[msg("Deposit accounts don't match the escrow or recipient")]
+    InvalidDepositAccounts,
+
+    #[msg("Proposal has no deposit to settle")]
+    NoDeposit,
+
+    #[msg("Proposal hasn't reached a final status")]
+    ProposalNotSettled,
+
+    #[msg("Vote comment is too long")]
+    CommentTooLong,
+
+    #[msg("Voting power in this DAO comes from deposited governance tokens")]
+    TokenWeightedDAO,
+
+    #[msg("Mint is not this DAO's governance mint")]
+    InvalidGovernanceMint,
+
+    #[msg("Tokens deposited after voting opened cannot vote on this proposal")]
+    DepositAfterSnapshot,
+
+    #[msg("Ragequit token list has too many or duplicate mints")]
+    InvalidRagequitTokens,
+
+    #[msg("Proposal is not a succeeded proposal in its timelock")]
+    NotInTimelock,
+
+    #[msg("Members who voted for or abstained on the proposal cannot ragequit")]
+    VotedForProposal,
+
+    #[msg("Voting power cannot change while a queued proposal can be ragequit")]
+    RagequitWindowOpen,
+
+    #[msg("Missing token accounts for a ragequit mint")]
+    MissingRagequitAccounts,
+
+    #[msg("Ragequit token accounts don't match the mint, treasury or member")]
+    InvalidRagequitAccounts,
+
+    #[msg("Optimistic veto threshold must be at most 10000")]
+    InvalidOptimisticThreshold,
+
+    #[msg("Optimistic proposals are disabled in this DAO")]
+    OptimisticProposalsDisabled,
+
+    #[msg("Optimistic proposals can only contain Transfer actions")]
+    OptimisticTransfersOnly,
+
+    #[msg("Optimistic proposal transfers more than the DAO's optimistic limit")]
+    OptimisticTransferTooLarge,
+
+    #[msg("Plurality proposals need at least 2 options and ranked-choice proposals 2 to 5")]
+    InvalidOptionCount,
+
+    #[msg("Vote doesn't fit the proposal: Yes/No/Abstain for standard and optimistic proposals, a valid option for plurality, a full ranking for ranked choice")]
+    InvalidVoteType,
 }
@@ -8,12 +8,25 @@ export interface DAO {
   authority: PublicKey;
   name: string;
   treasury: PublicKey;
+  governance_mint?: PublicKey | undefined;
   total_members: number;
+  total_voting_power: number;
+  total_quadratic_power: number;
   total_proposals: number;
+  latest_voting_end: number;
+  latest_timelock_end: number;
   voting_period: number;
   timelock_delay: number;
+  grace_period: number;
   quorum_threshold: number;
   approval_threshold: number;
+  optimistic_veto_threshold: number;
+  optimistic_max_transfer: number;
+  voting_strategy: VotingStrategy;
+  deposit: DepositConfig;
+  guardians: Vec;
+  guardian_threshold: number;
+  ragequit_tokens: Vec;
   is_active: boolean;
   created_at: number;
 }
@@ -22,12 +35,25 @@ export const DAOSchema = borsh.struct([
   borsh.publicKey('authority'),
   borsh.string('name'),
   borsh.publicKey('treasury'),
+  borsh.option(borsh.publicKey)('governance_mint'),
   borsh.u64('total_members'),
+  borsh.u64('total_voting_power'),
+  borsh.u64('total_quadratic_power'),
   borsh.u64('total_proposals'),
+  borsh.i64('latest_voting_end'),
+  borsh.i64('latest_timelock_end'),
   borsh.i64('voting_period'),
   borsh.i64('timelock_delay'),
+  borsh.i64('grace_period'),
   borsh.u64('quorum_threshold'),
   borsh.u64('approval_threshold'),
+  borsh.u64('optimistic_veto_threshold'),
+  borsh.u64('optimistic_max_transfer'),
+  borsh.votingstrategy('voting_strategy'),
+  borsh.depositconfig('deposit'),
+  borsh.vec('guardians'),
+  borsh.u8('guardian_threshold'),
+  borsh.vec('ragequit_tokens'),
   borsh.bool('is_active'),
   borsh.i64('created_at'),
 ]);
@@ -39,17 +65,25 @@ export interface Proposal {
   proposer: PublicKey;
   title: string;
   description: string;
-  proposal_type: ProposalType;
+  actions: Vec;
+  kind: ProposalKind;
+  option_votes: Vec;
+  winning_option?: number | undefined;
   yes_votes: number;
   no_votes: number;
   abstain_votes: number;
   total_votes: number;
+  voting_power_snapshot: number;
+  optimistic_veto_threshold: number;
+  voting_strategy: VotingStrategy;
   start_time: number;
   end_time: number;
   queued_at?: number | undefined;
   executed_at?: number | undefined;
   cancelled_at?: number | undefined;
+  cancel_refunds_deposit: boolean;
   status: ProposalStatus;
+  deposit: DepositConfig;
 }
 
 export const ProposalSchema = borsh.struct([
@@ -58,38 +92,54 @@ export const ProposalSchema = borsh.struct([
   borsh.publicKey('proposer'),
   borsh.string('title'),
   borsh.string('description'),
-  borsh.proposaltype('proposal_type'),
+  borsh.vec('actions'),
+  borsh.proposalkind('kind'),
+  borsh.vec('option_votes'),
+  borsh.option(borsh.u8)('winning_option'),
   borsh.u64('yes_votes'),
   borsh.u64('no_votes'),
   borsh.u64('abstain_votes'),
   borsh.u64('total_votes'),
+  borsh.u64('voting_power_snapshot'),
+  borsh.u64('optimistic_veto_threshold'),
+  borsh.votingstrategy('voting_strategy'),
   borsh.i64('start_time'),
   borsh.i64('end_time'),
   borsh.option(borsh.i64)('queued_at'),
   borsh.option(borsh.i64)('executed_at'),
   borsh.option(borsh.i64)('cancelled_at'),
+  borsh.bool('cancel_refunds_deposit'),
   borsh.proposalstatus('status'),
+  borsh.depositconfig('deposit'),
 ]);
 
 
 export type ProposalType =
-  | { kind: 'Transfer'; recipient: PublicKey; amount: number }
-  | { kind: 'ConfigChange'; voting_period: number | undefined; timelock_delay: number | undefined; quorum_threshold: number | undefined; approval_threshold: number | undefined }
+  | { kind: 'Transfer'; recipient: PublicKey; amount: number; mint: PublicKey | undefined }
+  | { kind: 'ConfigChange'; voting_period: number | undefined; timelock_delay: number | undefined; grace_period: number | undefined; quorum_threshold: number | undefined; approval_threshold: number | undefined; optimistic_veto_threshold: number | undefined; optimistic_max_transfer: number | undefined; voting_strategy: VotingStrategy | undefined; deposit: DepositConfig | undefined }
   | { kind: 'AddMember'; member: PublicKey; voting_power: number }
   | { kind: 'RemoveMember'; member: PublicKey }
-  | { kind: 'Custom'; instruction_data: string; target_program: PublicKey }
+  | { kind: 'SetGuardians'; guardians: Vec; threshold: number }
+  | { kind: 'SetRagequitTokens'; mints: Vec }
+  | { kind: 'Custom'; program_id: PublicKey; accounts: Vec; data: Vec }
 ;
 
 export const ProposalTypeSchema = borsh.rustEnum([
   borsh.struct([
     borsh.publicKey('recipient'),
     borsh.u64('amount'),
+    borsh.option(borsh.publicKey)('mint'),
   ], 'Transfer'),
   borsh.struct([
     borsh.option(borsh.i64)('voting_period'),
     borsh.option(borsh.i64)('timelock_delay'),
+    borsh.option(borsh.i64)('grace_period'),
     borsh.option(borsh.u64)('quorum_threshold'),
     borsh.option(borsh.u64)('approval_threshold'),
+    borsh.option(borsh.u64)('optimistic_veto_threshold'),
+    borsh.option(borsh.u64)('optimistic_max_transfer'),
+    borsh.option(borsh.votingstrategy)('voting_strategy'),
+    borsh.option(borsh.depositconfig)('deposit'),
   ], 'ConfigChange'),
   borsh.struct([
     borsh.publicKey('member'),
@@ -99,12 +149,48 @@ export const ProposalTypeSchema = borsh.rustEnum([
     borsh.publicKey('member'),
   ], 'RemoveMember'),
   borsh.struct([
-    borsh.string('instruction_data'),
-    borsh.publicKey('target_program'),
+    borsh.vec('guardians'),
+    borsh.u8('threshold'),
+  ], 'SetGuardians'),
+  borsh.struct([
+    borsh.vec('mints'),
+  ], 'SetRagequitTokens'),
+  borsh.struct([
+    borsh.publicKey('program_id'),
+    borsh.vec('accounts'),
+    borsh.vec('data'),
   ], 'Custom'),
 ]);
 
 
+export type ProposalKind =
+  | { kind: 'Standard' }
+  | { kind: 'Optimistic' }
+  | { kind: 'Plurality' }
+  | { kind: 'RankedChoice' }
+;
+
+export const ProposalKindSchema = borsh.rustEnum([
+  borsh.unit('Standard'),
+  borsh.unit('Optimistic'),
+  borsh.unit('Plurality'),
+  borsh.unit('RankedChoice'),
+]);
+
+
+export interface ProposalAccountMeta {
+  pubkey: PublicKey;
+  is_signer: boolean;
+  is_writable: boolean;
+}
+
+export const ProposalAccountMetaSchema = borsh.struct([
+  borsh.publicKey('pubkey'),
+  borsh.bool('is_signer'),
+  borsh.bool('is_writable'),
+]);
+
+
 export type ProposalStatus =
   | { kind: 'Draft' }
   | { kind: 'Active' }
@@ -114,6 +200,7 @@ export type ProposalStatus =
   | { kind: 'Executed' }
   | { kind: 'Cancelled' }
   | { kind: 'Expired' }
+  | { kind: 'Vetoed' }
 ;
 
 export const ProposalStatusSchema = borsh.rustEnum([
@@ -125,6 +212,7 @@ export const ProposalStatusSchema = borsh.rustEnum([
   borsh.unit('Executed'),
   borsh.unit('Cancelled'),
   borsh.unit('Expired'),
+  borsh.unit('Vetoed'),
 ]);
 
 
@@ -133,6 +221,7 @@ export interface Vote {
   voter: PublicKey;
   vote_type: VoteType;
   voting_power: number;
+  weight: number;
   comment: string;
   voted_at: number;
 }
@@ -142,21 +231,84 @@ export const VoteSchema = borsh.struct([
   borsh.publicKey('voter'),
   borsh.votetype('vote_type'),
   borsh.u64('voting_power'),
+  borsh.u64('weight'),
   borsh.string('comment'),
   borsh.i64('voted_at'),
 ]);
 
 
+export interface Veto {
+  dao: PublicKey;
+  proposal: PublicKey;
+  guardians: Vec;
+  reason: string;
+  vetoed_at: number;
+}
+
+export const VetoSchema = borsh.struct([
+  borsh.publicKey('dao'),
+  borsh.publicKey('proposal'),
+  borsh.vec('guardians'),
+  borsh.string('reason'),
+  borsh.i64('vetoed_at'),
+]);
+
+
 export type VoteType =
   | { kind: 'Yes' }
   | { kind: 'No' }
   | { kind: 'Abstain' }
+  | { kind: 'Choice'; option: number }
+  | { kind: 'Ranking'; options: Vec }
 ;
 
 export const VoteTypeSchema = borsh.rustEnum([
   borsh.unit('Yes'),
   borsh.unit('No'),
   borsh.unit('Abstain'),
+  borsh.struct([
+    borsh.u8('option'),
+  ], 'Choice'),
+  borsh.struct([
+    borsh.vec('options'),
+  ], 'Ranking'),
+]);
+
+
+export type VotingStrategy =
+  | { kind: 'Linear' }
+  | { kind: 'Quadratic' }
+  | { kind: 'Conviction' }
+;
+
+export const VotingStrategySchema = borsh.rustEnum([
+  borsh.unit('Linear'),
+  borsh.unit('Quadratic'),
+  borsh.unit('Conviction'),
+]);
+
+
+export interface DepositConfig {
+  amount: number;
+  asset: DepositAsset;
+  refund_threshold: number;
+}
+
+export const DepositConfigSchema = borsh.struct([
+  borsh.u64('amount'),
+  borsh.depositasset('asset'),
+  borsh.u64('refund_threshold'),
+]);
+
+
+export type DepositAsset =
+  | { kind: 'Sol' }
+  | { kind: 'GovernanceToken' }
+;
+
+export const DepositAssetSchema = borsh.rustEnum([
+  borsh.unit('Sol'),
+  borsh.unit('GovernanceToken'),
 ]);
 
 
@@ -165,6 +317,11 @@ export interface Member {
   wallet: PublicKey;
   voting_power: number;
   delegate?: PublicKey | undefined;
+  delegated_power: number;
+  delegated_quadratic_power: number;
+  voting_locked_until: number;
+  last_deposit_at: number;
+  delegation_locked_until: number;
   proposals_created: number;
   votes_cast: number;
   joined_at: number;
@@ -176,6 +333,11 @@ export const MemberSchema = borsh.struct([
   borsh.publicKey('wallet'),
   borsh.u64('voting_power'),
   borsh.option(borsh.publicKey)('delegate'),
+  borsh.u64('delegated_power'),
+  borsh.u64('delegated_quadratic_power'),
+  borsh.i64('voting_locked_until'),
+  borsh.i64('last_deposit_at'),
+  borsh.i64('delegation_locked_until'),
   borsh.u64('proposals_created'),
   borsh.u64('votes_cast'),
   borsh.i64('joined_at'),
@@ -269,6 +431,8 @@ export interface DAOStats {
   total_proposals_failed: number;
   total_treasury_spent: number;
   average_participation_rate: number;
+  total_turnout: number;
+  total_proposals_activated: number;
   last_activity_at: number;
 }
 
@@ -279,6 +443,8 @@ export const DAOStatsSchema = borsh.struct([
   borsh.u64('total_proposals_failed'),
   borsh.u64('total_treasury_spent'),
   borsh.u64('average_participation_rate'),
+  borsh.u64('total_turnout'),
+  borsh.u64('total_proposals_activated'),
   borsh.i64('last_activity_at'),
 ]);
 
@@ -293,6 +459,7 @@ export interface MemberStats {
   voting_power_received: number;
   voting_power_delegated: number;
   participation_rate: number;
+  proposals_at_join: number;
 }
 
 export const MemberStatsSchema = borsh.struct([
@@ -305,4 +472,5 @@ export const MemberStatsSchema = borsh.struct([
   borsh.u64('voting_power_received'),
   borsh.u64('voting_power_delegated'),
   borsh.u64('participation_rate'),
+  borsh.u64('proposals_at_join'),
 ]);
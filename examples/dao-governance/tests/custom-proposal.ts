@@ -0,0 +1,163 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { expect } from "chai";
+import { DaoGovernance } from "../target/types/dao_governance";
+import { NftMarketplace } from "../../nft-marketplace/target/types/nft_marketplace";
+import { GovernanceClient } from "../app/src/governance-client";
+import { airdrop, expectError, waitUntil } from "./helpers";
+
+// nft-marketplace is loaded into the test validator by Anchor.toml's
+// [[test.genesis]], so it has to be built first
+const marketplaceIdl = require("../../nft-marketplace/target/idl/nft_marketplace.json");
+
+describe("custom proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.daoGovernance as Program<DaoGovernance>;
+  const marketplace = new Program<NftMarketplace>(marketplaceIdl, provider);
+  const connection = provider.connection;
+  const client = new GovernanceClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const member = Keypair.generate();
+  const config = Keypair.generate();
+
+  let dao: PublicKey;
+  let treasury: PublicKey;
+
+  // Propose an instruction for the treasury to sign, pass it and execute it
+  async function govern(instruction: TransactionInstruction, signers: Keypair[] = []) {
+    const proposal = Keypair.generate();
+    const [memberAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), dao.toBuffer(), member.publicKey.toBuffer()],
+      program.programId
+    );
+    const [voteRecord] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vote"), proposal.publicKey.toBuffer(), member.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .createProposal("Govern nft-marketplace", "", [client.customProposalType(instruction)], { standard: {} })
+      .accounts({
+        dao,
+        proposal: proposal.publicKey,
+        proposer: member.publicKey,
+        member: memberAccount,
+        memberStats: client.getMemberStatsAddress(dao, member.publicKey),
+        depositEscrow: client.getDepositEscrowAddress(proposal.publicKey),
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([proposal, member])
+      .rpc();
+    await client.activateProposal({ dao, proposal: proposal.publicKey, proposer: member });
+
+    await program.methods
+      .castVote({ yes: {} } as any, "")
+      .accounts({
+        proposal: proposal.publicKey,
+        voteRecord,
+        voter: member.publicKey,
+        member: memberAccount,
+        daoStats: client.getDAOStatsAddress(dao),
+        memberStats: client.getMemberStatsAddress(dao, member.publicKey),
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([member])
+      .rpc();
+
+    await waitUntil((await client.getProposal(proposal.publicKey)).endTime);
+    await client.queueProposal({ dao, proposal: proposal.publicKey });
+    await client.executeProposal({ dao, proposal: proposal.publicKey, signers });
+
+    expect((await client.getProposal(proposal.publicKey)).status).to.have.property("executed");
+  }
+
+  before(async () => {
+    await airdrop(authority.publicKey, 2 * LAMPORTS_PER_SOL);
+    await airdrop(member.publicKey, 2 * LAMPORTS_PER_SOL);
+
+    dao = await client.createDAO({
+      authority,
+      name: "Marketplace DAO",
+      votingPeriod: 2,
+      timelockDelay: 0,
+      quorumThreshold: 5000,
+      approvalThreshold: 5100,
+    });
+    await client.addMember({ dao, authority, newMember: member.publicKey, votingPower: 100 });
+    // Members only vote on proposals opened after they joined
+    await waitUntil((await client.getMember(dao, member.publicKey)).joinedAt);
+
+    // The treasury pays for the marketplace config it will own
+    treasury = client.getTreasuryAddress(dao);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: authority.publicKey,
+          toPubkey: treasury,
+          lamports: LAMPORTS_PER_SOL,
+        })
+      ),
+      [authority]
+    );
+  });
+
+  it("creates a marketplace owned by the DAO treasury", async () => {
+    const instruction = await marketplace.methods
+      .initializeMarketplace(250)
+      .accounts({
+        config: config.publicKey,
+        authority: treasury,
+        treasury,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .instruction();
+
+    // The new config account signs its own creation alongside the treasury
+    await govern(instruction, [config]);
+
+    const data = await marketplace.account.marketplaceConfig.fetch(config.publicKey);
+    expect(data.authority.toBase58()).to.equal(treasury.toBase58());
+    expect(data.isPaused).to.be.false;
+  });
+
+  it("only lets the DAO pause it", async () => {
+    await expectError(
+      marketplace.methods
+        .setPause(true)
+        .accounts({ config: config.publicKey, authority: member.publicKey } as any)
+        .signers([member])
+        .rpc(),
+      "ConstraintHasOne"
+    );
+
+    const instruction = await marketplace.methods
+      .setPause(true)
+      .accounts({ config: config.publicKey, authority: treasury } as any)
+      .instruction();
+    await govern(instruction);
+
+    const data = await marketplace.account.marketplaceConfig.fetch(config.publicKey);
+    expect(data.isPaused).to.be.true;
+  });
+
+  it("rejects instructions over the size limit", async () => {
+    const instruction = new TransactionInstruction({
+      programId: marketplace.programId,
+      keys: [],
+      data: Buffer.alloc(513),
+    });
+
+    await expectError(govern(instruction), "InstructionTooLarge");
+  });
+});
@@ -0,0 +1,233 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { DaoGovernance } from "../target/types/dao_governance";
+import { GovernanceClient } from "../app/src/governance-client";
+import { airdrop, configChange, expectError, waitUntil } from "./helpers";
+
+describe("vote delegation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.daoGovernance as Program<DaoGovernance>;
+  const connection = provider.connection;
+  const client = new GovernanceClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const alice = Keypair.generate();
+  const bob = Keypair.generate();
+  const carol = Keypair.generate();
+  const votingPeriod = 4;
+
+  let dao: PublicKey;
+
+  function memberAddress(wallet: PublicKey): PublicKey {
+    const [member] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), dao.toBuffer(), wallet.toBuffer()],
+      program.programId
+    );
+    return member;
+  }
+
+  // A no-op config change, enough to open a vote
+  async function propose(proposer: Keypair): Promise<PublicKey> {
+    const proposal = Keypair.generate();
+    await program.methods
+      .createProposal("Test", "Delegation test proposal", [configChange()], { standard: {} })
+      .accounts({
+        dao,
+        proposal: proposal.publicKey,
+        proposer: proposer.publicKey,
+        member: memberAddress(proposer.publicKey),
+        memberStats: client.getMemberStatsAddress(dao, proposer.publicKey),
+        depositEscrow: client.getDepositEscrowAddress(proposal.publicKey),
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([proposal, proposer])
+      .rpc();
+    await client.activateProposal({ dao, proposal: proposal.publicKey, proposer });
+    return proposal.publicKey;
+  }
+
+  async function vote(proposal: PublicKey, voter: Keypair, voteType: object) {
+    const [voteRecord] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vote"), proposal.toBuffer(), voter.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .castVote(voteType as any, "")
+      .accounts({
+        proposal,
+        voteRecord,
+        voter: voter.publicKey,
+        member: memberAddress(voter.publicKey),
+        daoStats: client.getDAOStatsAddress(dao),
+        memberStats: client.getMemberStatsAddress(dao, voter.publicKey),
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([voter])
+      .rpc();
+  }
+
+  // Power counted on a proposal can never exceed what existed when it opened
+  async function expectNoDoubleCounting(proposal: PublicKey) {
+    const data = await client.getProposal(proposal);
+    expect(data.yesVotes + data.noVotes + data.abstainVotes).to.equal(data.totalVotes);
+    expect(data.totalVotes).to.be.at.most(data.votingPowerSnapshot);
+  }
+
+  before(async () => {
+    for (const wallet of [authority, alice, bob, carol]) {
+      await airdrop(wallet.publicKey, 2 * LAMPORTS_PER_SOL);
+    }
+
+    dao = await client.createDAO({
+      authority,
+      name: "Delegation DAO",
+      votingPeriod,
+      timelockDelay: 0,
+      quorumThreshold: 5000,
+      approvalThreshold: 5100,
+    });
+
+    await client.addMember({ dao, authority, newMember: alice.publicKey, votingPower: 100 });
+    await client.addMember({ dao, authority, newMember: bob.publicKey, votingPower: 200 });
+    await client.addMember({ dao, authority, newMember: carol.publicKey, votingPower: 300 });
+    // Members only vote on proposals opened after they joined
+    await waitUntil((await client.getMember(dao, carol.publicKey)).joinedAt);
+  });
+
+  it("moves the delegator's power to the delegatee", async () => {
+    await client.delegateVote({ dao, delegator: alice, delegatee: bob.publicKey });
+
+    const delegator = await client.getMember(dao, alice.publicKey);
+    const delegatee = await client.getMember(dao, bob.publicKey);
+    expect(delegator.delegate.toBase58()).to.equal(bob.publicKey.toBase58());
+    expect(delegatee.delegatedPower).to.equal(100);
+  });
+
+  it("counts delegated power once, through the delegatee", async () => {
+    const proposal = await propose(carol);
+
+    await expectError(vote(proposal, alice, { yes: {} }), "VotingPowerDelegated");
+
+    await vote(proposal, bob, { yes: {} });
+    await vote(proposal, carol, { no: {} });
+
+    const data = await client.getProposal(proposal);
+    expect(data.yesVotes).to.equal(300);
+    expect(data.noVotes).to.equal(300);
+    expect(data.totalVotes).to.equal(600);
+    await expectNoDoubleCounting(proposal);
+
+    // Revoking now would let alice vote again with power bob already used
+    await expectError(client.revokeDelegation({ dao, delegator: alice }), "VotesStillActive");
+
+    await waitUntil(data.endTime);
+    await client.revokeDelegation({ dao, delegator: alice });
+
+    const delegator = await client.getMember(dao, alice.publicKey);
+    const delegatee = await client.getMember(dao, bob.publicKey);
+    expect(delegator.delegate).to.be.undefined;
+    expect(delegatee.delegatedPower).to.equal(0);
+    expect(await connection.getAccountInfo(client.getDelegationAddress(dao, alice.publicKey))).to
+      .be.null;
+  });
+
+  it("won't let power that already voted be delegated to another voter", async () => {
+    const proposal = await propose(carol);
+
+    await vote(proposal, alice, { yes: {} });
+    await expectError(
+      client.delegateVote({ dao, delegator: alice, delegatee: bob.publicKey }),
+      "VotesStillActive"
+    );
+    await vote(proposal, bob, { no: {} });
+
+    const data = await client.getProposal(proposal);
+    expect(data.yesVotes).to.equal(100);
+    expect(data.noVotes).to.equal(200);
+    await expectNoDoubleCounting(proposal);
+
+    await waitUntil(data.endTime);
+  });
+
+  it("rejects delegation chains", async () => {
+    await client.delegateVote({ dao, delegator: alice, delegatee: bob.publicKey });
+
+    // bob holds alice's power and alice has delegated, so neither can pass power on
+    await expectError(
+      client.delegateVote({ dao, delegator: bob, delegatee: carol.publicKey }),
+      "DelegationChain"
+    );
+    await expectError(
+      client.delegateVote({ dao, delegator: carol, delegatee: alice.publicKey }),
+      "DelegationChain"
+    );
+
+    const proposal = await propose(carol);
+    await vote(proposal, bob, { yes: {} });
+    await vote(proposal, carol, { yes: {} });
+
+    const data = await client.getProposal(proposal);
+    expect(data.totalVotes).to.equal(600);
+    await expectNoDoubleCounting(proposal);
+  });
+
+  it("moves delegated power with changed and withdrawn votes", async () => {
+    const proposal = await propose(carol);
+
+    await vote(proposal, bob, { yes: {} });
+    await client.changeVote({ proposal, voter: bob, voteType: { no: {} } as any, comment: "" });
+
+    let data = await client.getProposal(proposal);
+    expect(data.yesVotes).to.equal(0);
+    expect(data.noVotes).to.equal(300);
+    expect(data.totalVotes).to.equal(300);
+
+    await client.withdrawVote({ proposal, voter: bob });
+    data = await client.getProposal(proposal);
+    expect(data.noVotes).to.equal(0);
+    expect(data.totalVotes).to.equal(0);
+
+    // Withdrawing keeps alice's power with bob until voting ends
+    await expectError(client.revokeDelegation({ dao, delegator: alice }), "VotesStillActive");
+
+    await vote(proposal, bob, { abstain: {} });
+    data = await client.getProposal(proposal);
+    expect(data.abstainVotes).to.equal(300);
+    await expectNoDoubleCounting(proposal);
+
+    await waitUntil(data.endTime);
+    await expectError(client.withdrawVote({ proposal, voter: bob }), "VotingPeriodEnded");
+  });
+
+  it("keeps delegated members' power fixed while delegated", async () => {
+    await expectError(
+      client.updateVotingPower({ dao, authority, member: alice.publicKey, votingPower: 500 }),
+      "ActiveDelegation"
+    );
+  });
+
+  it("keeps members who joined or gained power mid-vote out of its tally", async () => {
+    const proposal = await propose(carol);
+
+    const dave = Keypair.generate();
+    await airdrop(dave.publicKey, LAMPORTS_PER_SOL);
+    await client.addMember({ dao, authority, newMember: dave.publicKey, votingPower: 1000 });
+    await expectError(vote(proposal, dave, { yes: {} }), "JoinedAfterSnapshot");
+
+    // Raising carol's power now would let her vote more than the snapshot holds
+    await expectError(
+      client.updateVotingPower({ dao, authority, member: carol.publicKey, votingPower: 1000 }),
+      "VotesStillActive"
+    );
+    await vote(proposal, carol, { yes: {} });
+    await expectNoDoubleCounting(proposal);
+
+    await waitUntil((await client.getProposal(proposal)).endTime);
+    await client.updateVotingPower({ dao, authority, member: carol.publicKey, votingPower: 1000 });
+    expect((await client.getMember(dao, carol.publicKey)).votingPower).to.equal(1000);
+  });
+});
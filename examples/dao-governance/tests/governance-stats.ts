@@ -0,0 +1,166 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { DaoGovernance } from "../target/types/dao_governance";
+import { GovernanceClient } from "../app/src/governance-client";
+import { airdrop, expectError, waitUntil } from "./helpers";
+
+describe("governance stats", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.daoGovernance as Program<DaoGovernance>;
+  const connection = provider.connection;
+  const client = new GovernanceClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const alice = Keypair.generate();
+  const bob = Keypair.generate();
+  const carol = Keypair.generate();
+  const payment = LAMPORTS_PER_SOL / 10;
+
+  let dao: PublicKey;
+
+  // Alice proposes a payment to carol and the given members vote
+  async function decide(votes: [Keypair, object][]): Promise<PublicKey> {
+    const proposal = await client.createProposal({
+      dao,
+      proposer: alice,
+      title: "Pay carol",
+      description: "",
+      actions: [{ transfer: { recipient: carol.publicKey, amount: new BN(payment), mint: null } }] as any,
+    });
+    await client.activateProposal({ dao, proposal, proposer: alice });
+    for (const [voter, voteType] of votes) {
+      await client.castVote({ proposal, voter, voteType: voteType as any, comment: "" });
+    }
+    await waitUntil((await client.getProposal(proposal)).endTime);
+    return proposal;
+  }
+
+  before(async () => {
+    for (const wallet of [authority, alice, bob, carol]) {
+      await airdrop(wallet.publicKey, 2 * LAMPORTS_PER_SOL);
+    }
+
+    dao = await client.createDAO({
+      authority,
+      name: "Stats DAO",
+      votingPeriod: 2,
+      timelockDelay: 0,
+      quorumThreshold: 5000,
+      approvalThreshold: 5100,
+    });
+    await client.addMember({ dao, authority, newMember: alice.publicKey, votingPower: 100 });
+    await client.addMember({ dao, authority, newMember: bob.publicKey, votingPower: 100 });
+    await client.addMember({ dao, authority, newMember: carol.publicKey, votingPower: 200 });
+    // Members only vote on proposals opened after they joined
+    await waitUntil((await client.getMember(dao, carol.publicKey)).joinedAt);
+    await airdrop(client.getTreasuryAddress(dao), LAMPORTS_PER_SOL);
+  });
+
+  it("starts empty", async () => {
+    const stats = await client.getDAOStats(dao);
+    expect(stats.totalVotesCast).to.equal(0);
+    expect(stats.averageParticipationRate).to.equal(0);
+    expect((await client.getMemberStats(dao, alice.publicKey))!.proposalsAtJoin).to.equal(0);
+  });
+
+  it("tracks outcomes, spending and turnout", async () => {
+    // 300 of 400 voted: 75% turnout
+    const passed = await decide([
+      [alice, { yes: {} }],
+      [carol, { yes: {} }],
+    ]);
+    await client.queueProposal({ dao, proposal: passed });
+    await client.executeProposal({ dao, proposal: passed });
+
+    // 100 of 400 voted: 25% turnout, short of quorum
+    const failed = await decide([[bob, { no: {} }]]);
+    await client.finalizeProposal({ dao, proposal: failed });
+
+    const stats = await client.getDAOStats(dao);
+    expect(stats.totalVotesCast).to.equal(3);
+    expect(stats.totalProposalsActivated).to.equal(2);
+    expect(stats.totalProposalsPassed).to.equal(1);
+    expect(stats.totalProposalsFailed).to.equal(1);
+    expect(stats.totalTreasurySpent).to.equal(payment);
+    expect(stats.averageParticipationRate).to.equal(5000);
+
+    const alices = await client.getMemberStats(dao, alice.publicKey);
+    expect(alices!.totalProposalsCreated).to.equal(2);
+    expect(alices!.proposalsPassed).to.equal(1);
+    expect(alices!.proposalsFailed).to.equal(1);
+    expect(alices!.totalVotesCast).to.equal(1);
+    // Alice voted on the first proposal only, so her participation fell when the second opened
+    expect(alices!.participationRate).to.equal(5000);
+
+    // Bob voted on one of the two proposals opened since he joined
+    expect((await client.getMemberStats(dao, bob.publicKey))!.participationRate).to.equal(5000);
+  });
+
+  it("tracks delegated power", async () => {
+    await client.delegateVote({ dao, delegator: bob, delegatee: carol.publicKey });
+
+    expect((await client.getMemberStats(dao, bob.publicKey))!.votingPowerDelegated).to.equal(100);
+    expect((await client.getMemberStats(dao, carol.publicKey))!.votingPowerReceived).to.equal(100);
+  });
+
+  it("counts a new member's participation from when they joined", async () => {
+    const dave = Keypair.generate();
+    await client.addMember({ dao, authority, newMember: dave.publicKey, votingPower: 100 });
+
+    const stats = await client.getMemberStats(dao, dave.publicKey);
+    expect(stats!.proposalsAtJoin).to.equal(2);
+    expect(stats!.participationRate).to.equal(0);
+  });
+
+  it("counts a withdrawn and recast vote once", async () => {
+    const proposal = await client.createProposal({
+      dao,
+      proposer: alice,
+      title: "Pay carol again",
+      description: "",
+      actions: [{ transfer: { recipient: carol.publicKey, amount: new BN(payment), mint: null } }] as any,
+    });
+    await client.activateProposal({ dao, proposal, proposer: alice });
+    const before = await client.getDAOStats(dao);
+    const carols = await client.getMemberStats(dao, carol.publicKey);
+
+    await client.castVote({ proposal, voter: carol, voteType: { yes: {} } as any, comment: "" });
+    await client.withdrawVote({ proposal, voter: carol });
+    expect((await client.getDAOStats(dao)).totalVotesCast).to.equal(before.totalVotesCast);
+    await client.castVote({ proposal, voter: carol, voteType: { no: {} } as any, comment: "" });
+
+    expect((await client.getDAOStats(dao)).totalVotesCast).to.equal(before.totalVotesCast + 1);
+    const after = await client.getMemberStats(dao, carol.publicKey);
+    expect(after!.totalVotesCast).to.equal(carols!.totalVotesCast + 1);
+    // Carol has now voted on two of the three proposals
+    expect(after!.participationRate).to.equal(6666);
+  });
+
+  it("keeps a removed member's vote counted", async () => {
+    const eve = Keypair.generate();
+    await airdrop(eve.publicKey, LAMPORTS_PER_SOL);
+    await client.addMember({ dao, authority, newMember: eve.publicKey, votingPower: 100 });
+    await waitUntil((await client.getMember(dao, eve.publicKey)).joinedAt);
+
+    const proposal = await client.createProposal({
+      dao,
+      proposer: alice,
+      title: "Pay carol once more",
+      description: "",
+      actions: [{ transfer: { recipient: carol.publicKey, amount: new BN(payment), mint: null } }] as any,
+    });
+    await client.activateProposal({ dao, proposal, proposer: alice });
+    await client.castVote({ proposal, voter: eve, voteType: { yes: {} } as any, comment: "" });
+    const before = await client.getDAOStats(dao);
+
+    await client.removeMember({ dao, authority, member: eve.publicKey });
+    await expectError(client.withdrawVote({ proposal, voter: eve }), "MemberNotActive");
+
+    expect((await client.getProposal(proposal)).yesVotes).to.equal(100);
+    expect((await client.getDAOStats(dao)).totalVotesCast).to.equal(before.totalVotesCast);
+  });
+});
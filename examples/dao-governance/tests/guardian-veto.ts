@@ -0,0 +1,152 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { DaoGovernance } from "../target/types/dao_governance";
+import { GovernanceClient } from "../app/src/governance-client";
+import { airdrop, expectError, waitUntil } from "./helpers";
+
+describe("guardian veto council", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.daoGovernance as Program<DaoGovernance>;
+  const connection = provider.connection;
+  const client = new GovernanceClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const member = Keypair.generate();
+  const guardians = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const outsider = Keypair.generate();
+  const timelockDelay = 3;
+
+  let dao: PublicKey;
+
+  function setGuardians(keys: PublicKey[], threshold: number) {
+    return { setGuardians: { guardians: keys, threshold } };
+  }
+
+  function pay(lamports: number) {
+    return { transfer: { recipient: outsider.publicKey, amount: new anchor.BN(lamports), mint: null } };
+  }
+
+  async function propose(actions: object[]): Promise<PublicKey> {
+    const proposal = await client.createProposal({
+      dao,
+      proposer: member,
+      title: "Guardians",
+      description: "",
+      actions: actions as any,
+    });
+    await client.activateProposal({ dao, proposal, proposer: member });
+    return proposal;
+  }
+
+  // Propose the actions, vote them through and queue them
+  async function pass(actions: object[]): Promise<PublicKey> {
+    const proposal = await propose(actions);
+    await client.castVote({ proposal, voter: member, voteType: { yes: {} } as any, comment: "" });
+    await waitUntil((await client.getProposal(proposal)).endTime);
+    await client.queueProposal({ dao, proposal });
+    return proposal;
+  }
+
+  before(async () => {
+    for (const wallet of [authority, member, guardians[0], outsider]) {
+      await airdrop(wallet.publicKey, 2 * LAMPORTS_PER_SOL);
+    }
+
+    dao = await client.createDAO({
+      authority,
+      name: "Guarded DAO",
+      votingPeriod: 2,
+      timelockDelay,
+      quorumThreshold: 5000,
+      approvalThreshold: 5100,
+    });
+    await client.addMember({ dao, authority, newMember: member.publicKey, votingPower: 100 });
+    // Members only vote on proposals opened after they joined
+    await waitUntil((await client.getMember(dao, member.publicKey)).joinedAt);
+  });
+
+  it("only changes the guardian set through proposals", async () => {
+    expect((await client.getDAO(dao)).guardianThreshold).to.equal(0);
+
+    await expectError(
+      propose([setGuardians(guardians.map((g) => g.publicKey), 4)]),
+      "InvalidGuardianSet"
+    );
+    await expectError(
+      propose([setGuardians([guardians[0].publicKey, guardians[0].publicKey], 1)]),
+      "InvalidGuardianSet"
+    );
+
+    const proposal = await pass([setGuardians(guardians.map((g) => g.publicKey), 2)]);
+    await waitUntil((await client.getProposal(proposal)).queuedAt! + timelockDelay);
+    await client.executeProposal({ dao, proposal });
+
+    const data = await client.getDAO(dao);
+    expect(data.guardians.map((g: PublicKey) => g.toBase58())).to.deep.equal(
+      guardians.map((g) => g.publicKey.toBase58())
+    );
+    expect(data.guardianThreshold).to.equal(2);
+  });
+
+  it("needs the threshold of guardians to veto", async () => {
+    const proposal = await pass([pay(LAMPORTS_PER_SOL)]);
+
+    await expectError(
+      client.vetoProposal({ dao, proposal, guardians: [guardians[0]], reason: "Alone" }),
+      "InsufficientGuardians"
+    );
+    await expectError(
+      client.vetoProposal({ dao, proposal, guardians: [guardians[0], outsider], reason: "Impostor" }),
+      "NotGuardian"
+    );
+    await expectError(
+      client.vetoProposal({ dao, proposal, guardians: [guardians[0], guardians[0]], reason: "Twice" }),
+      "InsufficientGuardians"
+    );
+
+    await client.vetoProposal({
+      dao,
+      proposal,
+      guardians: [guardians[0], guardians[2]],
+      reason: "Recipient is not a contributor",
+    });
+
+    expect((await client.getProposal(proposal)).status).to.have.property("vetoed");
+    const veto = await client.getVeto(proposal);
+    expect(veto!.reason).to.equal("Recipient is not a contributor");
+    expect(veto!.guardians.map((g: PublicKey) => g.toBase58())).to.deep.equal([
+      guardians[0].publicKey.toBase58(),
+      guardians[2].publicKey.toBase58(),
+    ]);
+
+    await waitUntil((await client.getProposal(proposal)).queuedAt! + timelockDelay);
+    await expectError(client.executeProposal({ dao, proposal }), "ProposalNotQueued");
+  });
+
+  it("can't veto a proposal that replaces the council", async () => {
+    // Mixed with other actions or not, the guardians can't keep themselves in place
+    for (const actions of [
+      [setGuardians([outsider.publicKey], 1)],
+      [pay(LAMPORTS_PER_SOL), setGuardians([outsider.publicKey], 1)],
+    ]) {
+      const proposal = await pass(actions);
+      await expectError(
+        client.vetoProposal({ dao, proposal, guardians: guardians.slice(0, 2), reason: "Entrench" }),
+        "CannotVetoGuardianChange"
+      );
+      expect((await client.getProposal(proposal)).status).to.have.property("succeeded");
+    }
+  });
+
+  it("only vetoes succeeded proposals", async () => {
+    const proposal = await propose([pay(LAMPORTS_PER_SOL)]);
+    await expectError(
+      client.vetoProposal({ dao, proposal, guardians: guardians.slice(0, 2), reason: "Early" }),
+      "CannotVetoProposal"
+    );
+  });
+});
@@ -0,0 +1,44 @@
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+
+export async function airdrop(to: PublicKey, lamports: number) {
+  const connection = anchor.getProvider().connection;
+  const signature = await connection.requestAirdrop(to, lamports);
+  await connection.confirmTransaction(signature, "confirmed");
+}
+
+export async function expectError(promise: Promise<unknown>, error: string) {
+  try {
+    await promise;
+    expect.fail(`expected ${error}`);
+  } catch (e) {
+    expect(String(e)).to.include(error);
+  }
+}
+
+// Waits for the cluster clock, not the local one, to pass `timestamp`
+export async function waitUntil(timestamp: number) {
+  const connection = anchor.getProvider().connection;
+  while ((await connection.getBlockTime(await connection.getSlot("confirmed"))) <= timestamp) {
+    await new Promise((resolve) => setTimeout(resolve, 500));
+  }
+}
+
+// A config change action touching only the given fields; with none, a no-op enough to open a vote
+export function configChange(changes: object = {}) {
+  return {
+    configChange: {
+      votingPeriod: null,
+      timelockDelay: null,
+      gracePeriod: null,
+      quorumThreshold: null,
+      approvalThreshold: null,
+      optimisticVetoThreshold: null,
+      optimisticMaxTransfer: null,
+      votingStrategy: null,
+      deposit: null,
+      ...changes,
+    },
+  };
+}
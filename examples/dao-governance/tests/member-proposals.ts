@@ -0,0 +1,159 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { DaoGovernance } from "../target/types/dao_governance";
+import { GovernanceClient } from "../app/src/governance-client";
+import { airdrop, expectError, waitUntil } from "./helpers";
+
+describe("member proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.daoGovernance as Program<DaoGovernance>;
+  const connection = provider.connection;
+  const client = new GovernanceClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const alice = Keypair.generate();
+  const bob = Keypair.generate();
+  const carol = Keypair.generate();
+  const dave = Keypair.generate();
+  const erin = Keypair.generate();
+
+  let dao: PublicKey;
+
+  function memberAddress(wallet: PublicKey): PublicKey {
+    const [member] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), dao.toBuffer(), wallet.toBuffer()],
+      program.programId
+    );
+    return member;
+  }
+
+  const addMember = (wallet: PublicKey, votingPower: number) => ({
+    addMember: { member: wallet, votingPower: new BN(votingPower) },
+  });
+  const removeMember = (wallet: PublicKey) => ({ removeMember: { member: wallet } });
+
+  // Pass a proposal with the authority's majority and queue it, ready to execute
+  async function pass(action: object): Promise<PublicKey> {
+    const proposal = await client.createProposal({
+      dao,
+      proposer: authority,
+      title: "Membership",
+      description: "",
+      actions: [action] as any,
+    });
+    await client.activateProposal({ dao, proposal, proposer: authority });
+    await client.castVote({ proposal, voter: authority, voteType: { yes: {} } as any, comment: "" });
+    await waitUntil((await client.getProposal(proposal)).endTime);
+    await client.queueProposal({ dao, proposal });
+    return proposal;
+  }
+
+  // Expect `wallet` removed with its delegation to bob closed and the power taken back
+  async function expectDelegationReclaimed(wallet: PublicKey, bobDelegated: number) {
+    const member = await client.getMember(dao, wallet);
+    expect(member.isActive).to.be.false;
+    expect(member.delegate).to.be.undefined;
+    expect((await client.getMember(dao, bob.publicKey)).delegatedPower).to.equal(bobDelegated);
+    expect(await connection.getAccountInfo(client.getDelegationAddress(dao, wallet))).to.be.null;
+  }
+
+  before(async () => {
+    for (const wallet of [authority, alice, bob, carol]) {
+      await airdrop(wallet.publicKey, 2 * LAMPORTS_PER_SOL);
+    }
+
+    dao = await client.createDAO({
+      authority,
+      name: "Members DAO",
+      votingPeriod: 2,
+      timelockDelay: 0,
+      quorumThreshold: 5000,
+      approvalThreshold: 5100,
+    });
+    await client.addMember({ dao, authority, newMember: authority.publicKey, votingPower: 600 });
+    await client.addMember({ dao, authority, newMember: alice.publicKey, votingPower: 100 });
+    await client.addMember({ dao, authority, newMember: bob.publicKey, votingPower: 100 });
+    await client.addMember({ dao, authority, newMember: carol.publicKey, votingPower: 200 });
+    // Members only vote on proposals opened after they joined
+    await waitUntil((await client.getMember(dao, carol.publicKey)).joinedAt);
+  });
+
+  it("admits a new member and readmits a removed one with their history", async () => {
+    const before = await client.getDAO(dao);
+    await client.executeProposal({ dao, proposal: await pass(addMember(dave.publicKey, 50)) });
+
+    const admitted = await client.getMember(dao, dave.publicKey);
+    expect(admitted.isActive).to.be.true;
+    expect(admitted.votingPower).to.equal(50);
+    expect((await client.getDAO(dao)).totalMembers).to.equal(before.totalMembers + 1);
+    expect((await client.getDAO(dao)).totalVotingPower).to.equal(before.totalVotingPower + 50);
+
+    // Admitting an active member fails on execution
+    const again = await pass(addMember(dave.publicKey, 50));
+    await expectError(client.executeProposal({ dao, proposal: again }), "MemberAlreadyActive");
+
+    await client.removeMember({ dao, authority, member: dave.publicKey });
+    await client.executeProposal({ dao, proposal: await pass(addMember(dave.publicKey, 80)) });
+
+    const readmitted = await client.getMember(dao, dave.publicKey);
+    expect(readmitted.isActive).to.be.true;
+    expect(readmitted.votingPower).to.equal(80);
+    // Open proposals' snapshots don't hold their power, so they count as joining again
+    expect(readmitted.joinedAt).to.be.greaterThan(admitted.joinedAt);
+    expect((await client.getDAO(dao)).totalVotingPower).to.equal(before.totalVotingPower + 80);
+  });
+
+  it("rejects a member account that isn't the proposed member's PDA", async () => {
+    const proposal = await pass(addMember(erin.publicKey, 50));
+
+    // Alice's member PDA in place of erin's
+    await expectError(
+      program.methods
+        .executeProposal()
+        .accounts({
+          dao,
+          proposal,
+          treasury: client.getTreasuryAddress(dao),
+          daoStats: client.getDAOStatsAddress(dao),
+          executor: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .remainingAccounts([
+          { pubkey: memberAddress(alice.publicKey), isSigner: false, isWritable: true },
+          { pubkey: client.getMemberStatsAddress(dao, erin.publicKey), isSigner: false, isWritable: true },
+        ])
+        .rpc(),
+      "InvalidMemberAccount"
+    );
+
+    await client.executeProposal({ dao, proposal });
+    expect((await client.getMember(dao, erin.publicKey)).isActive).to.be.true;
+    expect((await client.getMember(dao, alice.publicKey)).votingPower).to.equal(100);
+  });
+
+  it("takes back a removed member's delegated power when removed by proposal", async () => {
+    await client.delegateVote({ dao, delegator: alice, delegatee: bob.publicKey });
+    expect((await client.getMember(dao, bob.publicKey)).delegatedPower).to.equal(100);
+    const before = await client.getDAO(dao);
+
+    await client.executeProposal({ dao, proposal: await pass(removeMember(alice.publicKey)) });
+
+    await expectDelegationReclaimed(alice.publicKey, 0);
+    expect((await client.getDAO(dao)).totalVotingPower).to.equal(before.totalVotingPower - 100);
+  });
+
+  it("takes back delegated power the same way when the authority removes a member", async () => {
+    await client.delegateVote({ dao, delegator: carol, delegatee: bob.publicKey });
+    expect((await client.getMember(dao, bob.publicKey)).delegatedPower).to.equal(200);
+    const before = await client.getDAO(dao);
+
+    await client.removeMember({ dao, authority, member: carol.publicKey });
+
+    await expectDelegationReclaimed(carol.publicKey, 0);
+    expect((await client.getDAO(dao)).totalVotingPower).to.equal(before.totalVotingPower - 200);
+  });
+});
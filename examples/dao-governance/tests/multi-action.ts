@@ -0,0 +1,115 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { expect } from "chai";
+import { DaoGovernance } from "../target/types/dao_governance";
+import { GovernanceClient } from "../app/src/governance-client";
+import { airdrop, configChange, expectError, waitUntil } from "./helpers";
+
+describe("multi-action proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.daoGovernance as Program<DaoGovernance>;
+  const connection = provider.connection;
+  const client = new GovernanceClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const member = Keypair.generate();
+  const contributor = Keypair.generate();
+
+  let dao: PublicKey;
+
+  function pay(lamports: number) {
+    return { transfer: { recipient: contributor.publicKey, amount: new BN(lamports), mint: null } };
+  }
+
+  // Propose the actions and vote them through
+  async function pass(actions: object[]): Promise<PublicKey> {
+    const proposal = await client.createProposal({
+      dao,
+      proposer: member,
+      title: "Multi-action",
+      description: "",
+      actions: actions as any,
+    });
+    await client.activateProposal({ dao, proposal, proposer: member });
+    await client.castVote({ proposal, voter: member, voteType: { yes: {} } as any, comment: "" });
+
+    await waitUntil((await client.getProposal(proposal)).endTime);
+    await client.queueProposal({ dao, proposal });
+    return proposal;
+  }
+
+  before(async () => {
+    await airdrop(authority.publicKey, 5 * LAMPORTS_PER_SOL);
+    await airdrop(member.publicKey, 2 * LAMPORTS_PER_SOL);
+
+    dao = await client.createDAO({
+      authority,
+      name: "Multi-action DAO",
+      votingPeriod: 2,
+      timelockDelay: 0,
+      quorumThreshold: 5000,
+      approvalThreshold: 5100,
+    });
+    await client.addMember({ dao, authority, newMember: member.publicKey, votingPower: 100 });
+    // Members only vote on proposals opened after they joined
+    await waitUntil((await client.getMember(dao, member.publicKey)).joinedAt);
+
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: authority.publicKey,
+          toPubkey: client.getTreasuryAddress(dao),
+          lamports: 2 * LAMPORTS_PER_SOL,
+        })
+      ),
+      [authority]
+    );
+  });
+
+  it("runs every action in order", async () => {
+    const proposal = await pass([configChange({ votingPeriod: new BN(3) }), pay(LAMPORTS_PER_SOL)]);
+    await client.executeProposal({ dao, proposal });
+
+    expect((await client.getDAO(dao)).votingPeriod).to.equal(3);
+    expect(await connection.getBalance(contributor.publicKey)).to.equal(LAMPORTS_PER_SOL);
+    expect((await client.getProposal(proposal)).status).to.have.property("executed");
+  });
+
+  it("runs none of them if one fails", async () => {
+    // The treasury can cover the first payment but not the second
+    const proposal = await pass([
+      configChange({ votingPeriod: new BN(2) }),
+      pay(LAMPORTS_PER_SOL / 2),
+      pay(100 * LAMPORTS_PER_SOL),
+    ]);
+
+    try {
+      await client.executeProposal({ dao, proposal });
+      expect.fail("execution should fail");
+    } catch (e) {
+      expect(String(e)).to.not.include("execution should fail");
+    }
+
+    expect((await client.getDAO(dao)).votingPeriod).to.equal(3);
+    expect(await connection.getBalance(contributor.publicKey)).to.equal(LAMPORTS_PER_SOL);
+    expect((await client.getProposal(proposal)).status).to.have.property("succeeded");
+  });
+
+  it("limits the number of actions", async () => {
+    for (const actions of [[], Array(9).fill(configChange({ votingPeriod: new BN(3) }))]) {
+      await expectError(
+        client.createProposal({
+          dao,
+          proposer: member,
+          title: "Too many",
+          description: "",
+          actions: actions as any,
+        }),
+        "InvalidActionCount"
+      );
+    }
+  });
+});
@@ -0,0 +1,137 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { DaoGovernance } from "../target/types/dao_governance";
+import { GovernanceClient } from "../app/src/governance-client";
+import { airdrop, expectError, waitUntil } from "./helpers";
+
+describe("multi-option proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.daoGovernance as Program<DaoGovernance>;
+  const connection = provider.connection;
+  const client = new GovernanceClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const alice = Keypair.generate();
+  const bob = Keypair.generate();
+  const carol = Keypair.generate();
+  // Grant recipients, one per option
+  const recipients = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const grant = LAMPORTS_PER_SOL / 10;
+
+  let dao: PublicKey;
+
+  function grants(count: number) {
+    return Array.from({ length: count }, (_, i) => ({
+      transfer: { recipient: recipients[i % recipients.length].publicKey, amount: new BN(grant), mint: null },
+    }));
+  }
+
+  async function open(kind: "Plurality" | "RankedChoice", options = 3): Promise<PublicKey> {
+    const proposal = await client.createProposal({
+      dao,
+      proposer: alice,
+      title: "Choose a grantee",
+      description: "",
+      actions: grants(options) as any,
+      kind: { kind },
+    });
+    await client.activateProposal({ dao, proposal, proposer: alice });
+    return proposal;
+  }
+
+  // Cast the votes, queue the proposal and execute it, returning what each recipient received
+  async function decide(proposal: PublicKey, votes: [Keypair, object][]): Promise<number[]> {
+    for (const [voter, voteType] of votes) {
+      await client.castVote({ proposal, voter, voteType: voteType as any, comment: "" });
+    }
+    await waitUntil((await client.getProposal(proposal)).endTime);
+    await client.queueProposal({ dao, proposal });
+
+    const before = await Promise.all(recipients.map((r) => connection.getBalance(r.publicKey)));
+    await client.executeProposal({ dao, proposal });
+    const after = await Promise.all(recipients.map((r) => connection.getBalance(r.publicKey)));
+    return after.map((balance, i) => balance - before[i]);
+  }
+
+  before(async () => {
+    for (const wallet of [authority, alice, bob, carol]) {
+      await airdrop(wallet.publicKey, 2 * LAMPORTS_PER_SOL);
+    }
+
+    dao = await client.createDAO({
+      authority,
+      name: "Grants DAO",
+      votingPeriod: 2,
+      timelockDelay: 0,
+      quorumThreshold: 5000,
+      approvalThreshold: 5100,
+    });
+    await client.addMember({ dao, authority, newMember: alice.publicKey, votingPower: 400 });
+    await client.addMember({ dao, authority, newMember: bob.publicKey, votingPower: 350 });
+    await client.addMember({ dao, authority, newMember: carol.publicKey, votingPower: 250 });
+    // Members only vote on proposals opened after they joined
+    await waitUntil((await client.getMember(dao, carol.publicKey)).joinedAt);
+    await airdrop(client.getTreasuryAddress(dao), LAMPORTS_PER_SOL);
+  });
+
+  it("rejects option counts and votes that don't fit the proposal", async () => {
+    await expectError(open("Plurality", 1), "InvalidOptionCount");
+    await expectError(open("RankedChoice", 6), "InvalidOptionCount");
+
+    const plurality = await open("Plurality");
+    for (const voteType of [{ yes: {} }, { choice: { option: 3 } }]) {
+      await expectError(
+        client.castVote({ proposal: plurality, voter: bob, voteType: voteType as any, comment: "" }),
+        "InvalidVoteType"
+      );
+    }
+
+    // Ballots must rank every option exactly once
+    const ranked = await open("RankedChoice");
+    for (const options of [[0, 1], [0, 0, 1], [0, 1, 3]]) {
+      await expectError(
+        client.castVote({
+          proposal: ranked,
+          voter: bob,
+          voteType: { ranking: { options: Buffer.from(options) } } as any,
+          comment: "",
+        }),
+        "InvalidVoteType"
+      );
+    }
+  });
+
+  it("executes only the plurality winner", async () => {
+    const proposal = await open("Plurality");
+    const received = await decide(proposal, [
+      [alice, { choice: { option: 0 } }],
+      [bob, { choice: { option: 1 } }],
+      [carol, { choice: { option: 2 } }],
+    ]);
+
+    const data = await client.getProposal(proposal);
+    expect(data.optionVotes).to.deep.equal([400, 350, 250]);
+    expect(data.winningOption).to.equal(0);
+    expect(received).to.deep.equal([grant, 0, 0]);
+  });
+
+  it("transfers eliminated first choices in a ranked-choice runoff", async () => {
+    // First choices 400/350/250: option 2 is eliminated and carol's ballot
+    // moves to option 1, which then holds 600 of 1000
+    const proposal = await open("RankedChoice");
+    const received = await decide(proposal, [
+      [alice, { ranking: { options: Buffer.from([0, 1, 2]) } }],
+      [bob, { ranking: { options: Buffer.from([1, 0, 2]) } }],
+      [carol, { ranking: { options: Buffer.from([2, 1, 0]) } }],
+    ]);
+
+    const data = await client.getProposal(proposal);
+    expect(data.optionVotes).to.have.length(6);
+    expect(data.winningOption).to.equal(1);
+    expect(received).to.deep.equal([0, grant, 0]);
+  });
+});
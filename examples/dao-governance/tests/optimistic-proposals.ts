@@ -0,0 +1,136 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { DaoGovernance } from "../target/types/dao_governance";
+import { GovernanceClient } from "../app/src/governance-client";
+import { airdrop, configChange, expectError, waitUntil } from "./helpers";
+
+describe("optimistic proposals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.daoGovernance as Program<DaoGovernance>;
+  const connection = provider.connection;
+  const client = new GovernanceClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const proposer = Keypair.generate();
+  const critic = Keypair.generate();
+  const grantee = Keypair.generate();
+  const grant = LAMPORTS_PER_SOL / 10;
+
+  let dao: PublicKey;
+
+  const payGrantee = { transfer: { recipient: grantee.publicKey, amount: new BN(grant), mint: null } };
+
+  async function propose(actions: object[], kind: "Standard" | "Optimistic", daoAddress = dao): Promise<PublicKey> {
+    return client.createProposal({
+      dao: daoAddress,
+      proposer,
+      title: "Small grant",
+      description: "",
+      actions: actions as any,
+      kind: { kind },
+    });
+  }
+
+  // Open the proposal, cast the given votes and wait for voting to end
+  async function decide(kind: "Standard" | "Optimistic", votes: [Keypair, object][]): Promise<PublicKey> {
+    const proposal = await propose([payGrantee], kind);
+    await client.activateProposal({ dao, proposal, proposer });
+    for (const [voter, voteType] of votes) {
+      await client.castVote({ proposal, voter, voteType: voteType as any, comment: "" });
+    }
+    await waitUntil((await client.getProposal(proposal)).endTime);
+    return proposal;
+  }
+
+  before(async () => {
+    for (const wallet of [authority, proposer, critic]) {
+      await airdrop(wallet.publicKey, 2 * LAMPORTS_PER_SOL);
+    }
+
+    dao = await client.createDAO({
+      authority,
+      name: "Grants DAO",
+      votingPeriod: 2,
+      timelockDelay: 0,
+      quorumThreshold: 5000,
+      approvalThreshold: 5100,
+      // Vetoed once 30% of the snapshot votes No
+      optimisticVetoThreshold: 3000,
+      optimisticMaxTransfer: 2 * grant,
+    });
+    await client.addMember({ dao, authority, newMember: proposer.publicKey, votingPower: 100 });
+    await client.addMember({ dao, authority, newMember: critic.publicKey, votingPower: 300 });
+    await client.addMember({ dao, authority, newMember: authority.publicKey, votingPower: 600 });
+    // Members only vote on proposals opened after they joined
+    await waitUntil((await client.getMember(dao, authority.publicKey)).joinedAt);
+    await airdrop(client.getTreasuryAddress(dao), LAMPORTS_PER_SOL);
+  });
+
+  it("only allows transfers, and only when enabled", async () => {
+    await expectError(propose([payGrantee, configChange()], "Optimistic"), "OptimisticTransfersOnly");
+
+    const pessimistic = await client.createDAO({
+      authority,
+      name: "Pessimistic DAO",
+      votingPeriod: 2,
+      timelockDelay: 0,
+      quorumThreshold: 5000,
+      approvalThreshold: 5100,
+    });
+    await client.addMember({ dao: pessimistic, authority, newMember: proposer.publicKey, votingPower: 100 });
+    await expectError(propose([payGrantee], "Optimistic", pessimistic), "OptimisticProposalsDisabled");
+  });
+
+  it("caps what an optimistic proposal transfers", async () => {
+    const payGrantee3x = { transfer: { ...payGrantee.transfer, amount: new BN(3 * grant) } };
+    await expectError(propose([payGrantee3x], "Optimistic"), "OptimisticTransferTooLarge");
+    // The limit is on the proposal's total, not each transfer
+    await expectError(
+      propose([payGrantee, payGrantee, payGrantee], "Optimistic"),
+      "OptimisticTransferTooLarge"
+    );
+
+    await propose([payGrantee, payGrantee], "Optimistic");
+    await propose([payGrantee3x], "Standard");
+  });
+
+  it("passes without votes and executes through the timelock", async () => {
+    const standard = await decide("Standard", []);
+    await expectError(client.queueProposal({ dao, proposal: standard }), "QuorumNotReached");
+
+    const proposal = await decide("Optimistic", []);
+    await client.queueProposal({ dao, proposal });
+    expect((await client.getProposal(proposal)).status).to.have.property("succeeded");
+
+    const before = await connection.getBalance(grantee.publicKey);
+    await client.executeProposal({ dao, proposal });
+    expect((await connection.getBalance(grantee.publicKey)) - before).to.equal(grant);
+  });
+
+  it("is defeated once No votes reach the veto threshold it opened with", async () => {
+    // Raise the threshold to 50% while the optimistic vote is open
+    const raise = await propose([configChange({ optimisticVetoThreshold: new BN(5000) })], "Standard");
+    await client.activateProposal({ dao, proposal: raise, proposer });
+    await client.castVote({ proposal: raise, voter: authority, voteType: { yes: {} } as any, comment: "" });
+
+    // 300 of 1000 voting No is exactly the 30% threshold, despite more Yes votes
+    const proposal = await decide("Optimistic", [
+      [critic, { no: {} }],
+      [authority, { yes: {} }],
+    ]);
+
+    await waitUntil((await client.getProposal(raise)).endTime);
+    await client.queueProposal({ dao, proposal: raise });
+    await client.executeProposal({ dao, proposal: raise });
+    expect((await client.getDAO(dao)).optimisticVetoThreshold).to.equal(5000);
+
+    await client.finalizeProposal({ dao, proposal });
+    const data = await client.getProposal(proposal);
+    expect(data.optimisticVetoThreshold).to.equal(3000);
+    expect(data.status).to.have.property("defeated");
+  });
+});
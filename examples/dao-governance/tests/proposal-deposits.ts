@@ -0,0 +1,139 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { DaoGovernance } from "../target/types/dao_governance";
+import { GovernanceClient } from "../app/src/governance-client";
+import { airdrop, expectError, waitUntil } from "./helpers";
+
+describe("proposal deposits", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.daoGovernance as Program<DaoGovernance>;
+  const connection = provider.connection;
+  const client = new GovernanceClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const proposer = Keypair.generate();
+  const voter = Keypair.generate();
+  const deposit = LAMPORTS_PER_SOL / 2;
+
+  let dao: PublicKey;
+  let treasury: PublicKey;
+  let escrowRent: number;
+
+  async function draft(): Promise<PublicKey> {
+    return client.createProposal({
+      dao,
+      proposer,
+      title: "Deposit",
+      description: "",
+      actions: [{ transfer: { recipient: voter.publicKey, amount: new anchor.BN(1), mint: null } }] as any,
+    });
+  }
+
+  // Open a draft, cast the given votes and wait for voting to end
+  async function decide(votes: [Keypair, object][]): Promise<PublicKey> {
+    const proposal = await draft();
+    await client.activateProposal({ dao, proposal, proposer });
+    for (const [wallet, voteType] of votes) {
+      await client.castVote({ proposal, voter: wallet, voteType: voteType as any, comment: "" });
+    }
+    await waitUntil((await client.getProposal(proposal)).endTime);
+    return proposal;
+  }
+
+  // Settle the deposit, returning how much the proposer and treasury gained
+  async function settle(proposal: PublicKey): Promise<[number, number]> {
+    const before = await Promise.all([proposer.publicKey, treasury].map((k) => connection.getBalance(k)));
+    await client.settleDeposit({ dao, proposal });
+    const after = await Promise.all([proposer.publicKey, treasury].map((k) => connection.getBalance(k)));
+
+    expect(await connection.getAccountInfo(client.getDepositEscrowAddress(proposal))).to.be.null;
+    expect((await client.getProposal(proposal)).deposit.amount).to.equal(0);
+    return [after[0] - before[0], after[1] - before[1]];
+  }
+
+  before(async () => {
+    for (const wallet of [authority, proposer, voter]) {
+      await airdrop(wallet.publicKey, 5 * LAMPORTS_PER_SOL);
+    }
+
+    dao = await client.createDAO({
+      authority,
+      name: "Deposit DAO",
+      votingPeriod: 2,
+      timelockDelay: 0,
+      quorumThreshold: 5000,
+      approvalThreshold: 5100,
+      // Refunded with at least 20% of the snapshot voting yes
+      deposit: { amount: deposit, asset: { kind: "Sol" }, refund_threshold: 2000 },
+    });
+    treasury = client.getTreasuryAddress(dao);
+    escrowRent = await connection.getMinimumBalanceForRentExemption(0);
+
+    await client.addMember({ dao, authority, newMember: proposer.publicKey, votingPower: 300 });
+    await client.addMember({ dao, authority, newMember: voter.publicKey, votingPower: 700 });
+    // Members only vote on proposals opened after they joined
+    await waitUntil((await client.getMember(dao, voter.publicKey)).joinedAt);
+    await airdrop(treasury, LAMPORTS_PER_SOL);
+  });
+
+  it("escrows the deposit at creation", async () => {
+    const proposal = await draft();
+
+    const escrow = client.getDepositEscrowAddress(proposal);
+    expect(await connection.getBalance(escrow)).to.equal(deposit + escrowRent);
+    expect((await client.getProposal(proposal)).deposit.amount).to.equal(deposit);
+
+    await expectError(client.settleDeposit({ dao, proposal }), "ProposalNotSettled");
+  });
+
+  it("refunds executed proposals", async () => {
+    const proposal = await decide([[voter, { yes: {} }]]);
+    await client.queueProposal({ dao, proposal });
+    await client.executeProposal({ dao, proposal });
+
+    expect(await settle(proposal)).to.deep.equal([deposit + escrowRent, 0]);
+    await expectError(client.settleDeposit({ dao, proposal }), "NoDeposit");
+  });
+
+  it("refunds defeated proposals with enough support", async () => {
+    // 300 of 1000 voted yes: defeated, but above the 20% refund threshold
+    const proposal = await decide([[proposer, { yes: {} }], [voter, { no: {} }]]);
+    await client.finalizeProposal({ dao, proposal });
+
+    expect(await settle(proposal)).to.deep.equal([deposit + escrowRent, 0]);
+  });
+
+  it("forfeits deposits of proposals with almost no support", async () => {
+    const proposal = await decide([[voter, { no: {} }]]);
+    await client.finalizeProposal({ dao, proposal });
+
+    expect(await settle(proposal)).to.deep.equal([escrowRent, deposit]);
+  });
+
+  it("refunds drafts their proposer withdraws", async () => {
+    const proposal = await draft();
+    await client.cancelProposal({ dao, proposal, canceller: proposer });
+
+    expect(await settle(proposal)).to.deep.equal([deposit + escrowRent, 0]);
+  });
+
+  it("refunds passed proposals the authority cancels", async () => {
+    const proposal = await decide([[voter, { yes: {} }]]);
+    await client.queueProposal({ dao, proposal });
+    await client.cancelProposal({ dao, proposal, canceller: authority });
+
+    expect(await settle(proposal)).to.deep.equal([deposit + escrowRent, 0]);
+  });
+
+  it("forfeits deposits of proposals cancelled mid-vote", async () => {
+    const proposal = await draft();
+    await client.activateProposal({ dao, proposal, proposer });
+    await client.cancelProposal({ dao, proposal, canceller: proposer });
+
+    expect(await settle(proposal)).to.deep.equal([escrowRent, deposit]);
+  });
+});
@@ -0,0 +1,148 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { DaoGovernance } from "../target/types/dao_governance";
+import { GovernanceClient } from "../app/src/governance-client";
+import { airdrop, configChange, expectError, waitUntil } from "./helpers";
+
+describe("proposal lifecycle", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.daoGovernance as Program<DaoGovernance>;
+  const connection = provider.connection;
+  const client = new GovernanceClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const proposer = Keypair.generate();
+  const voter = Keypair.generate();
+  const gracePeriod = 2;
+
+  let dao: PublicKey;
+
+  async function draft(actions: object[] = [configChange()]): Promise<PublicKey> {
+    return client.createProposal({
+      dao,
+      proposer,
+      title: "Lifecycle",
+      description: "",
+      actions: actions as any,
+    });
+  }
+
+  // Open a draft and let the voters settle it
+  async function decide(voteType: object): Promise<PublicKey> {
+    const proposal = await draft();
+    await client.activateProposal({ dao, proposal, proposer });
+    await client.castVote({ proposal, voter, voteType: voteType as any, comment: "" });
+    await waitUntil((await client.getProposal(proposal)).endTime);
+    return proposal;
+  }
+
+  before(async () => {
+    for (const wallet of [authority, proposer, voter]) {
+      await airdrop(wallet.publicKey, 2 * LAMPORTS_PER_SOL);
+    }
+
+    dao = await client.createDAO({
+      authority,
+      name: "Lifecycle DAO",
+      votingPeriod: 2,
+      timelockDelay: 0,
+      gracePeriod,
+      quorumThreshold: 5000,
+      approvalThreshold: 5100,
+    });
+    await client.addMember({ dao, authority, newMember: proposer.publicKey, votingPower: 100 });
+    await client.addMember({ dao, authority, newMember: voter.publicKey, votingPower: 900 });
+    // Members only vote on proposals opened after they joined
+    await waitUntil((await client.getMember(dao, voter.publicKey)).joinedAt);
+  });
+
+  it("lets the proposer edit a draft until it is activated", async () => {
+    const proposal = await draft();
+    expect((await client.getProposal(proposal)).status).to.have.property("draft");
+    await expectError(
+      client.castVote({ proposal, voter, voteType: { yes: {} } as any, comment: "" }),
+      "ProposalNotActive"
+    );
+
+    const actions = [
+      configChange({ votingPeriod: new BN(3) }),
+      configChange({ votingPeriod: new BN(4) }),
+      configChange(),
+    ];
+    await expectError(
+      client.updateDraft({ dao, proposal, proposer: voter, title: "Hijacked", description: "", actions: actions as any }),
+      "Unauthorized"
+    );
+    await client.updateDraft({ dao, proposal, proposer, title: "Edited", description: "Longer", actions: actions as any });
+
+    const edited = await client.getProposal(proposal);
+    expect(edited.title).to.equal("Edited");
+    expect(edited.actions).to.have.length(3);
+    expect(edited.startTime).to.equal(0);
+
+    await expectError(client.activateProposal({ dao, proposal, proposer: voter }), "Unauthorized");
+    await client.activateProposal({ dao, proposal, proposer });
+
+    const active = await client.getProposal(proposal);
+    expect(active.status).to.have.property("active");
+    expect(active.votingPowerSnapshot).to.equal(1000);
+    expect(active.endTime).to.equal(active.startTime + 2);
+
+    await expectError(
+      client.updateDraft({ dao, proposal, proposer, title: "Too late", description: "", actions: actions as any }),
+      "ProposalNotDraft"
+    );
+  });
+
+  it("rejects config changes create_dao would reject", async () => {
+    const invalid: [object, string][] = [
+      [{ votingPeriod: new BN(0) }, "InvalidVotingPeriod"],
+      [{ timelockDelay: new BN(-1) }, "InvalidTimelock"],
+      [{ gracePeriod: new BN(0) }, "InvalidGracePeriod"],
+      [{ quorumThreshold: new BN(0) }, "InvalidQuorum"],
+      [{ approvalThreshold: new BN(10001) }, "InvalidThreshold"],
+    ];
+    for (const [changes, error] of invalid) {
+      await expectError(draft([configChange(changes)]), error);
+    }
+  });
+
+  it("lets drafts be cancelled", async () => {
+    const proposal = await draft();
+    await client.cancelProposal({ dao, proposal, canceller: proposer });
+    expect((await client.getProposal(proposal)).status).to.have.property("cancelled");
+  });
+
+  it("defeats lost votes without queueing", async () => {
+    const proposal = await decide({ no: {} });
+    await client.finalizeProposal({ dao, proposal });
+    expect((await client.getProposal(proposal)).status).to.have.property("defeated");
+
+    await expectError(client.finalizeProposal({ dao, proposal }), "NothingToFinalize");
+  });
+
+  it("sends passing votes through the queue", async () => {
+    const proposal = await decide({ yes: {} });
+    await expectError(client.finalizeProposal({ dao, proposal }), "ProposalPassed");
+
+    await client.queueProposal({ dao, proposal });
+    await expectError(client.finalizeProposal({ dao, proposal }), "GracePeriodNotEnded");
+    await client.executeProposal({ dao, proposal });
+    expect((await client.getProposal(proposal)).status).to.have.property("executed");
+  });
+
+  it("expires proposals left unexecuted past the grace period", async () => {
+    const proposal = await decide({ yes: {} });
+    await client.queueProposal({ dao, proposal });
+
+    await waitUntil((await client.getProposal(proposal)).queuedAt! + gracePeriod);
+    await expectError(client.executeProposal({ dao, proposal }), "ProposalExpired");
+
+    await client.finalizeProposal({ dao, proposal });
+    expect((await client.getProposal(proposal)).status).to.have.property("expired");
+  });
+});
@@ -0,0 +1,158 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { createMint, getAccount, getOrCreateAssociatedTokenAccount, mintTo } from "@solana/spl-token";
+import { expect } from "chai";
+import { DaoGovernance } from "../target/types/dao_governance";
+import { GovernanceClient } from "../app/src/governance-client";
+import { airdrop, expectError, waitUntil } from "./helpers";
+
+describe("ragequit", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.daoGovernance as Program<DaoGovernance>;
+  const connection = provider.connection;
+  const client = new GovernanceClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const alice = Keypair.generate();
+  const bob = Keypair.generate();
+  const carol = Keypair.generate();
+  const timelockDelay = 10;
+
+  let dao: PublicKey;
+  let treasury: PublicKey;
+  let mint: PublicKey;
+  let treasuryTokens: PublicKey;
+
+  async function propose(actions: object[]): Promise<PublicKey> {
+    return client.createProposal({
+      dao,
+      proposer: alice,
+      title: "Ragequit",
+      description: "",
+      actions: actions as any,
+    });
+  }
+
+  // Alice proposes the actions, the given members vote and it is queued
+  async function pass(actions: object[], votes: [Keypair, object][]): Promise<PublicKey> {
+    const proposal = await propose(actions);
+    await client.activateProposal({ dao, proposal, proposer: alice });
+    for (const [voter, voteType] of votes) {
+      await client.castVote({ proposal, voter, voteType: voteType as any, comment: "" });
+    }
+    await waitUntil((await client.getProposal(proposal)).endTime);
+    await client.queueProposal({ dao, proposal });
+    return proposal;
+  }
+
+  // Ragequit, returning the lamports and tokens the treasury paid out
+  async function ragequit(proposal: PublicKey, member: Keypair): Promise<[number, number]> {
+    const before = [await connection.getBalance(treasury), (await getAccount(connection, treasuryTokens)).amount];
+    await client.ragequit({ dao, proposal, member });
+    const after = [await connection.getBalance(treasury), (await getAccount(connection, treasuryTokens)).amount];
+    return [Number(before[0]) - Number(after[0]), Number(before[1]) - Number(after[1])];
+  }
+
+  before(async () => {
+    for (const wallet of [authority, alice, bob, carol]) {
+      await airdrop(wallet.publicKey, 2 * LAMPORTS_PER_SOL);
+    }
+
+    dao = await client.createDAO({
+      authority,
+      name: "Ragequit DAO",
+      votingPeriod: 2,
+      timelockDelay,
+      quorumThreshold: 5000,
+      approvalThreshold: 5100,
+    });
+    treasury = client.getTreasuryAddress(dao);
+
+    await client.addMember({ dao, authority, newMember: alice.publicKey, votingPower: 200 });
+    await client.addMember({ dao, authority, newMember: bob.publicKey, votingPower: 100 });
+    await client.addMember({ dao, authority, newMember: carol.publicKey, votingPower: 100 });
+    // Members only vote on proposals opened after they joined
+    await waitUntil((await client.getMember(dao, carol.publicKey)).joinedAt);
+
+    await airdrop(treasury, LAMPORTS_PER_SOL);
+    mint = await createMint(connection, authority, authority.publicKey, null, 0);
+    treasuryTokens = (await getOrCreateAssociatedTokenAccount(connection, authority, mint, treasury, true)).address;
+    await mintTo(connection, authority, mint, treasuryTokens, authority, 1000);
+  });
+
+  it("only changes the ragequit token list through proposals", async () => {
+    await expectError(propose([{ setRagequitTokens: { mints: [mint, mint] } }]), "InvalidRagequitTokens");
+
+    const proposal = await pass([{ setRagequitTokens: { mints: [mint] } }], [[alice, { yes: {} }]]);
+    await waitUntil((await client.getProposal(proposal)).queuedAt! + timelockDelay);
+    await client.executeProposal({ dao, proposal });
+
+    const tokens = (await client.getDAO(dao)).ragequitTokens;
+    expect(tokens.map((t: PublicKey) => t.toBase58())).to.deep.equal([mint.toBase58()]);
+  });
+
+  it("pays out members who voted no or didn't vote during the timelock", async () => {
+    const payment = { transfer: { recipient: alice.publicKey, amount: new BN(LAMPORTS_PER_SOL / 10), mint: null } };
+    const proposal = await pass([payment], [[alice, { yes: {} }], [bob, { no: {} }]]);
+
+    await expectError(client.ragequit({ dao, proposal, member: alice }), "VotedForProposal");
+
+    // Bob holds 100 of 400 voting power
+    const rent = await connection.getMinimumBalanceForRentExemption(0);
+    let available = (await connection.getBalance(treasury)) - rent;
+    expect(await ragequit(proposal, bob)).to.deep.equal([Math.floor(available / 4), 250]);
+
+    // Carol, who didn't vote, holds 100 of the remaining 300
+    available = (await connection.getBalance(treasury)) - rent;
+    expect(await ragequit(proposal, carol)).to.deep.equal([Math.floor(available / 3), 250]);
+
+    const data = await client.getDAO(dao);
+    expect(data.totalVotingPower).to.equal(200);
+    expect(data.totalMembers).to.equal(1);
+    const member = await client.getMember(dao, carol.publicKey);
+    expect(member!.isActive).to.be.false;
+    expect(member!.votingPower).to.equal(0);
+
+    await expectError(client.ragequit({ dao, proposal, member: bob }), "MemberNotActive");
+
+    // Once the timelock is over the proposal executes and ragequit closes
+    await waitUntil((await client.getProposal(proposal)).queuedAt! + timelockDelay);
+    await expectError(client.ragequit({ dao, proposal, member: alice }), "NotInTimelock");
+    await client.executeProposal({ dao, proposal });
+  });
+
+  it("keeps members the authority adds or re-powers from taking a share", async () => {
+    const payment = { transfer: { recipient: alice.publicKey, amount: new BN(LAMPORTS_PER_SOL / 10), mint: null } };
+    const proposal = await propose([payment]);
+    await client.activateProposal({ dao, proposal, proposer: alice });
+
+    // Dave joins once voting has opened, so the proposal isn't his to leave over
+    const dave = Keypair.generate();
+    await airdrop(dave.publicKey, LAMPORTS_PER_SOL);
+    await client.addMember({ dao, authority, newMember: dave.publicKey, votingPower: 1000 });
+    await client.castVote({ proposal, voter: alice, voteType: { yes: {} } as any, comment: "" });
+    await waitUntil((await client.getProposal(proposal)).endTime);
+    await client.queueProposal({ dao, proposal });
+
+    await expectError(client.ragequit({ dao, proposal, member: dave }), "JoinedAfterSnapshot");
+
+    // Nor can the authority move shares while the proposal can be ragequit
+    const eve = Keypair.generate();
+    await expectError(
+      client.addMember({ dao, authority, newMember: eve.publicKey, votingPower: 1000 }),
+      "RagequitWindowOpen"
+    );
+    await expectError(
+      client.updateVotingPower({ dao, authority, member: dave.publicKey, votingPower: 5000 }),
+      "RagequitWindowOpen"
+    );
+    expect((await client.getDAO(dao)).totalVotingPower).to.equal(1200);
+
+    await waitUntil((await client.getProposal(proposal)).queuedAt! + timelockDelay);
+    await client.updateVotingPower({ dao, authority, member: dave.publicKey, votingPower: 500 });
+    expect((await client.getDAO(dao)).totalVotingPower).to.equal(700);
+  });
+});
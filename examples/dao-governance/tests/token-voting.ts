@@ -0,0 +1,237 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  transfer,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { DaoGovernance } from "../target/types/dao_governance";
+import { GovernanceClient } from "../app/src/governance-client";
+import { airdrop, configChange, expectError, waitUntil } from "./helpers";
+
+describe("token-weighted voting", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.daoGovernance as Program<DaoGovernance>;
+  const connection = provider.connection;
+  const client = new GovernanceClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const alice = Keypair.generate();
+  const bob = Keypair.generate();
+  const votingPeriod = 4;
+
+  let dao: PublicKey;
+  let mint: PublicKey;
+
+  function memberAddress(wallet: PublicKey): PublicKey {
+    const [member] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), dao.toBuffer(), wallet.toBuffer()],
+      program.programId
+    );
+    return member;
+  }
+
+  // A no-op config change, enough to open a vote
+  async function propose(proposer: Keypair): Promise<PublicKey> {
+    const proposal = Keypair.generate();
+    await program.methods
+      .createProposal("Test", "Token voting test proposal", [configChange()], { standard: {} })
+      .accounts({
+        dao,
+        proposal: proposal.publicKey,
+        proposer: proposer.publicKey,
+        member: memberAddress(proposer.publicKey),
+        memberStats: client.getMemberStatsAddress(dao, proposer.publicKey),
+        depositEscrow: client.getDepositEscrowAddress(proposal.publicKey),
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([proposal, proposer])
+      .rpc();
+    await client.activateProposal({ dao, proposal: proposal.publicKey, proposer });
+    return proposal.publicKey;
+  }
+
+  async function vote(proposal: PublicKey, voter: Keypair, voteType: object) {
+    const [voteRecord] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vote"), proposal.toBuffer(), voter.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .castVote(voteType as any, "")
+      .accounts({
+        proposal,
+        voteRecord,
+        voter: voter.publicKey,
+        member: memberAddress(voter.publicKey),
+        daoStats: client.getDAOStatsAddress(dao),
+        memberStats: client.getMemberStatsAddress(dao, voter.publicKey),
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([voter])
+      .rpc();
+  }
+
+  async function tokenBalance(owner: PublicKey): Promise<number> {
+    const account = await getOrCreateAssociatedTokenAccount(connection, authority, mint, owner);
+    return Number(account.amount);
+  }
+
+  before(async () => {
+    for (const wallet of [authority, alice, bob]) {
+      await airdrop(wallet.publicKey, 2 * LAMPORTS_PER_SOL);
+    }
+
+    mint = await createMint(connection, authority, authority.publicKey, null, 0);
+    for (const wallet of [alice, bob]) {
+      const account = await getOrCreateAssociatedTokenAccount(
+        connection,
+        authority,
+        mint,
+        wallet.publicKey
+      );
+      await mintTo(connection, authority, mint, account.address, authority, 1000);
+    }
+
+    dao = await client.createDAO({
+      authority,
+      name: "Token DAO",
+      votingPeriod,
+      timelockDelay: 0,
+      quorumThreshold: 5000,
+      approvalThreshold: 5100,
+      governanceMint: mint,
+    });
+
+    // Members join with no power and earn it by depositing
+    await expectError(
+      client.addMember({ dao, authority, newMember: alice.publicKey, votingPower: 100 }),
+      "TokenWeightedDAO"
+    );
+    await client.addMember({ dao, authority, newMember: alice.publicKey, votingPower: 0 });
+    await client.addMember({ dao, authority, newMember: bob.publicKey, votingPower: 0 });
+  });
+
+  it("turns deposits into voting power", async () => {
+    await client.depositGovernanceTokens({ dao, depositor: alice, amount: 600 });
+    await client.depositGovernanceTokens({ dao, depositor: bob, amount: 400 });
+
+    const member = await client.getMember(dao, alice.publicKey);
+    expect(member.votingPower).to.equal(600);
+    expect((await client.getDAO(dao)).totalVotingPower).to.equal(1000);
+
+    const escrow = await getAccount(
+      connection,
+      client.getEscrowAddress(dao, alice.publicKey, mint)
+    );
+    expect(Number(escrow.amount)).to.equal(600);
+    expect(await tokenBalance(alice.publicKey)).to.equal(400);
+
+    await expectError(
+      client.updateVotingPower({ dao, authority, member: alice.publicKey, votingPower: 5000 }),
+      "TokenWeightedDAO"
+    );
+  });
+
+  it("locks tokens that voted until voting ends", async () => {
+    await waitUntil((await client.getMember(dao, alice.publicKey)).lastDepositAt);
+    const proposal = await propose(alice);
+    await vote(proposal, alice, { yes: {} });
+
+    // Moving the tokens to bob would let them vote a second time
+    await expectError(
+      client.withdrawGovernanceTokens({ dao, depositor: alice, amount: 600 }),
+      "VotesStillActive"
+    );
+
+    // Tokens bob deposits now weren't in the snapshot
+    await client.depositGovernanceTokens({ dao, depositor: bob, amount: 100 });
+    await expectError(vote(proposal, bob, { no: {} }), "DepositAfterSnapshot");
+
+    const data = await client.getProposal(proposal);
+    expect(data.votingPowerSnapshot).to.equal(1000);
+    expect(data.yesVotes).to.equal(600);
+    expect(data.totalVotes).to.be.at.most(data.votingPowerSnapshot);
+
+    await waitUntil(data.endTime);
+    await client.withdrawGovernanceTokens({ dao, depositor: alice, amount: 600 });
+
+    expect(await tokenBalance(alice.publicKey)).to.equal(1000);
+    expect((await client.getMember(dao, alice.publicKey)).votingPower).to.equal(0);
+    expect((await client.getDAO(dao)).totalVotingPower).to.equal(500);
+  });
+
+  it("doesn't let the same tokens vote twice by moving wallets", async () => {
+    await client.depositGovernanceTokens({ dao, depositor: alice, amount: 500 });
+    await waitUntil((await client.getMember(dao, alice.publicKey)).lastDepositAt);
+    const proposal = await propose(bob);
+    await vote(proposal, alice, { yes: {} });
+
+    // The only way out of the escrow is withdrawal, which stays locked
+    await expectError(
+      client.withdrawGovernanceTokens({ dao, depositor: alice, amount: 500 }),
+      "VotesStillActive"
+    );
+
+    // Fresh tokens sent to bob and deposited still can't vote on this proposal
+    const bobAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      authority,
+      mint,
+      bob.publicKey
+    );
+    const aliceAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      authority,
+      mint,
+      alice.publicKey
+    );
+    await transfer(connection, alice, aliceAccount.address, bobAccount.address, alice, 500);
+    await client.depositGovernanceTokens({ dao, depositor: bob, amount: 500 });
+    await expectError(vote(proposal, bob, { no: {} }), "DepositAfterSnapshot");
+
+    const data = await client.getProposal(proposal);
+    expect(data.totalVotes).to.equal(500);
+    expect(data.totalVotes).to.be.at.most(data.votingPowerSnapshot);
+  });
+
+  it("keeps late deposits out of delegations without blocking the delegatee", async () => {
+    const carol = Keypair.generate();
+    await airdrop(carol.publicKey, LAMPORTS_PER_SOL);
+    const carolAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      authority,
+      mint,
+      carol.publicKey
+    );
+    await mintTo(connection, authority, mint, carolAccount.address, authority, 300);
+    await client.addMember({ dao, authority, newMember: carol.publicKey, votingPower: 0 });
+
+    await waitUntil((await client.getMember(dao, bob.publicKey)).lastDepositAt);
+    const proposal = await propose(bob);
+
+    // Delegating tokens deposited after the snapshot would let bob vote them,
+    // and used to stop bob voting at all
+    await client.depositGovernanceTokens({ dao, depositor: carol, amount: 300 });
+    await expectError(
+      client.delegateVote({ dao, delegator: carol, delegatee: bob.publicKey }),
+      "DepositAfterSnapshot"
+    );
+
+    const bobPower = (await client.getMember(dao, bob.publicKey)).votingPower;
+    await vote(proposal, bob, { yes: {} });
+    const data = await client.getProposal(proposal);
+    expect(data.yesVotes).to.equal(bobPower);
+    expect(data.totalVotes).to.be.at.most(data.votingPowerSnapshot);
+
+    // Once every proposal open at the deposit has closed, the tokens can be delegated
+    await waitUntil((await client.getMember(dao, carol.publicKey)).delegationLockedUntil);
+    await client.delegateVote({ dao, delegator: carol, delegatee: bob.publicKey });
+    expect((await client.getMember(dao, bob.publicKey)).delegatedPower).to.equal(300);
+  });
+});
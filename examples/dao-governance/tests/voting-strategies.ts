@@ -0,0 +1,182 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { DaoGovernance } from "../target/types/dao_governance";
+import { GovernanceClient } from "../app/src/governance-client";
+import { airdrop, configChange, waitUntil } from "./helpers";
+
+describe("voting strategies", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.daoGovernance as Program<DaoGovernance>;
+  const connection = provider.connection;
+  const client = new GovernanceClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const whale = Keypair.generate();
+  const minnows = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const votingPeriod = 4;
+
+  function memberAddress(dao: PublicKey, wallet: PublicKey): PublicKey {
+    const [member] = PublicKey.findProgramAddressSync(
+      [Buffer.from("member"), dao.toBuffer(), wallet.toBuffer()],
+      program.programId
+    );
+    return member;
+  }
+
+  async function propose(dao: PublicKey, proposer: Keypair, votingStrategy: object | null = null) {
+    const proposal = Keypair.generate();
+    await program.methods
+      .createProposal("Test", "Voting strategy test proposal", [configChange({ votingStrategy })], { standard: {} })
+      .accounts({
+        dao,
+        proposal: proposal.publicKey,
+        proposer: proposer.publicKey,
+        member: memberAddress(dao, proposer.publicKey),
+        memberStats: client.getMemberStatsAddress(dao, proposer.publicKey),
+        depositEscrow: client.getDepositEscrowAddress(proposal.publicKey),
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([proposal, proposer])
+      .rpc();
+    await client.activateProposal({ dao, proposal: proposal.publicKey, proposer });
+    return proposal.publicKey;
+  }
+
+  async function vote(dao: PublicKey, proposal: PublicKey, voter: Keypair, voteType: object) {
+    const [voteRecord] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vote"), proposal.toBuffer(), voter.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .castVote(voteType as any, "")
+      .accounts({
+        proposal,
+        voteRecord,
+        voter: voter.publicKey,
+        member: memberAddress(dao, voter.publicKey),
+        daoStats: client.getDAOStatsAddress(dao),
+        memberStats: client.getMemberStatsAddress(dao, voter.publicKey),
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([voter])
+      .rpc();
+  }
+
+  async function createDAO(kind: "Linear" | "Quadratic" | "Conviction"): Promise<PublicKey> {
+    const dao = await client.createDAO({
+      authority,
+      name: `${kind} DAO`,
+      votingPeriod,
+      timelockDelay: 0,
+      quorumThreshold: 5000,
+      approvalThreshold: 5100,
+      votingStrategy: { kind },
+    });
+    await client.addMember({ dao, authority, newMember: whale.publicKey, votingPower: 900 });
+    for (const minnow of minnows) {
+      await client.addMember({ dao, authority, newMember: minnow.publicKey, votingPower: 100 });
+    }
+    // Members only vote on proposals opened after they joined
+    await waitUntil((await client.getMember(dao, minnows[minnows.length - 1].publicKey)).joinedAt);
+    return dao;
+  }
+
+  before(async () => {
+    for (const wallet of [authority, whale, ...minnows]) {
+      await airdrop(wallet.publicKey, 2 * LAMPORTS_PER_SOL);
+    }
+  });
+
+  it("lets many small holders outvote a whale under quadratic voting", async () => {
+    const dao = await createDAO("Quadratic");
+    expect((await client.getDAO(dao)).totalQuadraticPower).to.equal(30 + 3 * 10);
+
+    const proposal = await propose(dao, whale);
+    await vote(dao, proposal, whale, { no: {} });
+    for (const minnow of minnows) {
+      await vote(dao, proposal, minnow, { yes: {} });
+    }
+
+    const data = await client.getProposal(proposal);
+    expect(data.votingStrategy).to.have.property("quadratic");
+    expect(data.votingPowerSnapshot).to.equal(60);
+    expect(data.noVotes).to.equal(30);
+    expect(data.yesVotes).to.equal(30);
+    expect(data.totalVotes).to.equal(60);
+
+    // 30 of 60 is short of the 51% approval threshold
+    await waitUntil(data.endTime);
+    await client.queueProposal({ dao, proposal });
+    expect((await client.getProposal(proposal)).status).to.have.property("defeated");
+  });
+
+  it("weights conviction votes by how long they stay committed", async () => {
+    const dao = await createDAO("Conviction");
+    const proposal = await propose(dao, whale);
+
+    await vote(dao, proposal, minnows[0], { yes: {} });
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    await vote(dao, proposal, minnows[1], { yes: {} });
+
+    const [early, late] = await Promise.all(
+      minnows.slice(0, 2).map((minnow) => {
+        const [voteRecord] = PublicKey.findProgramAddressSync(
+          [Buffer.from("vote"), proposal.toBuffer(), minnow.publicKey.toBuffer()],
+          program.programId
+        );
+        return program.account.vote.fetch(voteRecord);
+      })
+    );
+    expect(early.votingPower.toNumber()).to.equal(100);
+    expect(early.weight.toNumber()).to.be.at.most(100);
+    expect(late.weight.toNumber()).to.be.lessThan(early.weight.toNumber());
+
+    const data = await client.getProposal(proposal);
+    expect(data.yesVotes).to.equal(early.weight.toNumber() + late.weight.toNumber());
+    expect(data.totalVotes).to.be.at.most(data.votingPowerSnapshot);
+  });
+
+  it("square-roots each delegator's power separately under quadratic voting", async () => {
+    const dao = await createDAO("Quadratic");
+    const [delegatee, ...delegators] = minnows;
+    for (const delegator of delegators) {
+      await client.delegateVote({ dao, delegator, delegatee: delegatee.publicKey });
+    }
+    expect((await client.getMember(dao, delegatee.publicKey)).delegatedQuadraticPower).to.equal(20);
+
+    // √100 three times, not √300, so the minnows still reach the 50% quorum of 60 together
+    const proposal = await propose(dao, whale);
+    await vote(dao, proposal, delegatee, { yes: {} });
+
+    const data = await client.getProposal(proposal);
+    expect(data.votingPowerSnapshot).to.equal(60);
+    expect(data.yesVotes).to.equal(30);
+
+    await waitUntil(data.endTime);
+    await client.queueProposal({ dao, proposal });
+    expect((await client.getProposal(proposal)).status).to.have.property("succeeded");
+  });
+
+  it("keeps open proposals on the strategy they were created with", async () => {
+    const dao = await createDAO("Linear");
+
+    // Switch the DAO to quadratic voting through governance
+    const change = await propose(dao, whale, { quadratic: {} });
+    await vote(dao, change, whale, { yes: {} });
+    const open = await propose(dao, minnows[0]);
+
+    const data = await client.getProposal(change);
+    await waitUntil(data.endTime);
+    await client.queueProposal({ dao, proposal: change });
+    await client.executeProposal({ dao, proposal: change });
+    expect((await client.getDAO(dao)).votingStrategy).to.have.property("quadratic");
+
+    expect((await client.getProposal(open)).votingStrategy).to.have.property("linear");
+    expect((await client.getProposal(open)).votingPowerSnapshot).to.equal(1200);
+    expect((await client.getProposal(await propose(dao, whale))).votingPowerSnapshot).to.equal(60);
+  });
+});
@@ -8,6 +8,9 @@ export interface StakingPool {
   authority: PublicKey;
   token_mint: PublicKey;
   vault: PublicKey;
+  reward_vault: PublicKey;
+  mode: PoolMode;
+  receipt_mint?: PublicKey | undefined;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
    * For Solana lamports or large values, ensure they stay within safe range.
@@ -26,12 +29,58 @@ export interface StakingPool {
    * Values exceeding this limit will lose precision during serialization.
    */
   reward_rate: number;
+  emission_schedule: EmissionSchedule;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  acc_reward_per_share: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  rewards_owed: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  last_accrual_at: number;
+  reward_stream_count: number;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
    * For Solana lamports or large values, ensure they stay within safe range.
    * Values exceeding this limit will lose precision during serialization.
    */
   min_stake_amount: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  max_total_staked?: number | undefined;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  max_per_user?: number | undefined;
+  allowlist_root?: number[] | undefined;
+  boost_collection?: PublicKey | undefined;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  boost_multiplier: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  total_boost_weight: number;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
    * For Solana lamports or large values, ensure they stay within safe range.
@@ -51,20 +100,240 @@ export interface StakingPool {
    * Values exceeding this limit will lose precision during serialization.
    */
   created_at: number;
+  emission_epochs: EmissionEpoch[];
 }
 
 export const StakingPoolSchema = borsh.struct([
   borsh.publicKey('authority'),
   borsh.publicKey('token_mint'),
   borsh.publicKey('vault'),
+  borsh.publicKey('reward_vault'),
+  PoolModeSchema('mode'),
+  borsh.option(borsh.publicKey)('receipt_mint'),
   borsh.u64('total_staked'),
   borsh.u64('total_stakers'),
   borsh.u64('reward_rate'),
+  EmissionScheduleSchema('emission_schedule'),
+  borsh.u128('acc_reward_per_share'),
+  borsh.u64('rewards_owed'),
+  borsh.i64('last_accrual_at'),
+  borsh.u8('reward_stream_count'),
   borsh.u64('min_stake_amount'),
+  borsh.option(borsh.u64)('max_total_staked'),
+  borsh.option(borsh.u64)('max_per_user'),
+  borsh.option(borsh.array(borsh.u8, 32))('allowlist_root'),
+  borsh.option(borsh.publicKey)('boost_collection'),
+  borsh.u64('boost_multiplier'),
+  borsh.u64('total_boost_weight'),
   borsh.i64('min_lock_duration'),
   borsh.i64('cooldown_period'),
   borsh.bool('is_active'),
   borsh.i64('created_at'),
+  borsh.vec(EmissionEpochSchema)('emission_epochs'),
+]);
+
+
+export type PoolMode =
+  | { kind: 'Standard' }
+  | { kind: 'Liquid' }
+  | { kind: 'NativeStake' }
+;
+
+export const PoolModeSchema = borsh.rustEnum([
+  borsh.unit('Standard'),
+  borsh.unit('Liquid'),
+  borsh.unit('NativeStake'),
+]);
+
+
+/**
+ * WARNING: Some variants contain TypeScript 'number' types with precision limit of 2^53-1.
+ * Large values (e.g., Solana lamports) may lose precision during serialization.
+ */
+export type EmissionSchedule =
+  | { kind: 'Fixed' }
+  | { kind: 'Epochs' }
+  | { kind: 'Halving'; start_time: number; interval: number; initial_rate: number }
+;
+
+export const EmissionScheduleSchema = borsh.rustEnum([
+  borsh.unit('Fixed'),
+  borsh.unit('Epochs'),
+  borsh.struct([
+    borsh.i64('start_time'),
+    borsh.i64('interval'),
+    borsh.u64('initial_rate'),
+  ], 'Halving'),
+]);
+
+
+export interface EmissionEpoch {
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  start_time: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  end_time: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  reward_rate: number;
+}
+
+export const EmissionEpochSchema = borsh.struct([
+  borsh.i64('start_time'),
+  borsh.i64('end_time'),
+  borsh.u64('reward_rate'),
+]);
+
+
+export interface ValidatorList {
+  pool: PublicKey;
+  validators: ValidatorStake[];
+}
+
+export const ValidatorListSchema = borsh.struct([
+  borsh.publicKey('pool'),
+  borsh.vec(ValidatorStakeSchema)('validators'),
+]);
+
+
+export interface ValidatorStake {
+  vote_account: PublicKey;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  total_delegated: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  total_rewards: number;
+}
+
+export const ValidatorStakeSchema = borsh.struct([
+  borsh.publicKey('vote_account'),
+  borsh.u64('total_delegated'),
+  borsh.u64('total_rewards'),
+]);
+
+
+export interface RewardVault {
+  pool: PublicKey;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  balance: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  total_funded: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  total_distributed: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  total_withdrawn: number;
+}
+
+export const RewardVaultSchema = borsh.struct([
+  borsh.publicKey('pool'),
+  borsh.u64('balance'),
+  borsh.u64('total_funded'),
+  borsh.u64('total_distributed'),
+  borsh.u64('total_withdrawn'),
+]);
+
+
+export interface RewardStream {
+  pool: PublicKey;
+  index: number;
+  reward_mint: PublicKey;
+  reward_vault: PublicKey;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  emission_rate: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  start_time: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  end_time: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  acc_reward_per_share: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  last_update_at: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  total_funded: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  total_distributed: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  total_unallocated: number;
+}
+
+export const RewardStreamSchema = borsh.struct([
+  borsh.publicKey('pool'),
+  borsh.u8('index'),
+  borsh.publicKey('reward_mint'),
+  borsh.publicKey('reward_vault'),
+  borsh.u64('emission_rate'),
+  borsh.i64('start_time'),
+  borsh.i64('end_time'),
+  borsh.u128('acc_reward_per_share'),
+  borsh.i64('last_update_at'),
+  borsh.u64('total_funded'),
+  borsh.u64('total_distributed'),
+  borsh.u64('total_unallocated'),
 ]);
 
 
@@ -101,6 +370,25 @@ export interface StakeAccount {
    * Values exceeding this limit will lose precision during serialization.
    */
   total_claimed: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  reward_per_share_paid: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  pending_rewards: number;
+  boost_mint?: PublicKey | undefined;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  boost_multiplier: number;
   status: StakingStatus;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
@@ -108,6 +396,7 @@ export interface StakeAccount {
    * Values exceeding this limit will lose precision during serialization.
    */
   unstake_requested_at?: number | undefined;
+  stream_checkpoints: StreamCheckpoint[];
 }
 
 export const StakeAccountSchema = borsh.struct([
@@ -118,8 +407,34 @@ export const StakeAccountSchema = borsh.struct([
   borsh.i64('last_claim_at'),
   borsh.i64('unlock_at'),
   borsh.u64('total_claimed'),
+  borsh.u128('reward_per_share_paid'),
+  borsh.u64('pending_rewards'),
+  borsh.option(borsh.publicKey)('boost_mint'),
+  borsh.u64('boost_multiplier'),
   StakingStatusSchema('status'),
   borsh.option(borsh.i64)('unstake_requested_at'),
+  borsh.vec(StreamCheckpointSchema)('stream_checkpoints'),
+]);
+
+
+export interface StreamCheckpoint {
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  reward_per_share_paid: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  pending: number;
+}
+
+export const StreamCheckpointSchema = borsh.struct([
+  borsh.u128('reward_per_share_paid'),
+  borsh.u64('pending'),
 ]);
 
 
@@ -337,6 +652,18 @@ export interface PoolStats {
    * Values exceeding this limit will lose precision during serialization.
    */
   current_apy: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  total_deposited: number;
+  /**
+   * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
+   * For Solana lamports or large values, ensure they stay within safe range.
+   * Values exceeding this limit will lose precision during serialization.
+   */
+  stake_seconds: number;
   /**
    * WARNING: TypeScript 'number' has precision limit of 2^53-1 (9,007,199,254,740,991).
    * For Solana lamports or large values, ensure they stay within safe range.
@@ -351,5 +678,7 @@ export const PoolStatsSchema = borsh.struct([
   borsh.i64('average_stake_duration'),
   borsh.u64('highest_stake'),
   borsh.u64('current_apy'),
+  borsh.u64('total_deposited'),
+  borsh.u128('stake_seconds'),
   borsh.i64('last_updated'),
 ]);
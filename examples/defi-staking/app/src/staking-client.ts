@@ -10,12 +10,29 @@ import {
   Transaction,
   Keypair,
   LAMPORTS_PER_SOL,
+  StakeProgram,
+  STAKE_CONFIG_ID,
+  SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_STAKE_HISTORY_PUBKEY,
 } from '@solana/web3.js';
 import { AnchorProvider, Program, Idl, BN } from '@coral-xyz/anchor';
+import { createHash } from 'crypto';
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from '@solana/spl-token';
 
 // Import LUMOS-generated types
 import {
   StakingPool,
+  PoolMode,
+  EmissionSchedule,
+  EmissionEpoch,
+  ValidatorList,
+  RewardVault,
+  RewardStream,
   StakeAccount,
   StakingStatus,
   RewardConfig,
@@ -27,6 +44,9 @@ import {
 // Program ID (matches lib.rs declare_id!)
 const PROGRAM_ID = new PublicKey('stk1111111111111111111111111111111111111111');
 
+// Metaplex Token Metadata program, used to verify boost NFT collections
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
+
 /**
  * Client for interacting with the DeFi staking program
  */
@@ -50,6 +70,7 @@ export class StakingClient {
     cooldownPeriod: number;
   }): Promise<PublicKey> {
     const poolKeypair = Keypair.generate();
+    const rewardVault = this.getRewardVaultAddress(poolKeypair.publicKey);
 
     await this.program.methods
       .initializePool(
@@ -63,6 +84,9 @@ export class StakingClient {
         authority: params.authority.publicKey,
         tokenMint: params.tokenMint,
         vault: params.vault,
+        rewardVault,
+        rewardConfig: this.getRewardConfigAddress(poolKeypair.publicKey),
+        poolStats: this.getPoolStatsAddress(poolKeypair.publicKey),
         systemProgram: SystemProgram.programId,
       })
       .signers([poolKeypair, params.authority])
@@ -79,6 +103,7 @@ export class StakingClient {
     pool: PublicKey;
     user: Keypair;
     amount: number;
+    proof?: Buffer[]; // required when the pool has an allow-list
   }): Promise<void> {
     const [stakeAccount] = PublicKey.findProgramAddressSync(
       [Buffer.from('stake'), params.pool.toBuffer(), params.user.publicKey.toBuffer()],
@@ -88,14 +113,18 @@ export class StakingClient {
     const poolData = await this.getPool(params.pool);
 
     await this.program.methods
-      .stake(new BN(params.amount))
+      .stake(new BN(params.amount), (params.proof ?? []).map((node) => Array.from(node)))
       .accounts({
         pool: params.pool,
         stakeAccount,
         user: params.user.publicKey,
         vaultAccount: poolData.vault,
+        userStats: this.getUserStatsAddress(params.user.publicKey),
+        poolStats: this.getPoolStatsAddress(params.pool),
+        rewardConfig: this.getRewardConfigAddress(params.pool),
         systemProgram: SystemProgram.programId,
       })
+      .remainingAccounts(this.getRewardStreamMetas(params.pool, poolData.rewardStreamCount))
       .signers([params.user])
       .rpc();
 
@@ -140,6 +169,7 @@ export class StakingClient {
     );
 
     const poolData = await this.getPool(params.pool);
+    const boostAccounts = await this.getBoostReturnAccounts(params.pool, params.user.publicKey);
 
     await this.program.methods
       .unstake()
@@ -148,8 +178,13 @@ export class StakingClient {
         stakeAccount,
         user: params.user.publicKey,
         vaultAccount: poolData.vault,
+        userStats: this.getUserStatsAddress(params.user.publicKey),
+        poolStats: this.getPoolStatsAddress(params.pool),
+        rewardConfig: this.getRewardConfigAddress(params.pool),
+        ...boostAccounts,
         systemProgram: SystemProgram.programId,
       })
+      .remainingAccounts(this.getRewardStreamMetas(params.pool, poolData.rewardStreamCount))
       .signers([params.user])
       .rpc();
 
@@ -168,7 +203,17 @@ export class StakingClient {
       PROGRAM_ID
     );
 
-    const poolData = await this.getPool(params.pool);
+    // Each stream pays out from its vault into the user's token account for its mint
+    const streams = await this.getRewardStreams(params.pool);
+    const streamAccounts = streams.flatMap(({ address, stream }) => [
+      { pubkey: address, isSigner: false, isWritable: true },
+      { pubkey: stream.rewardVault, isSigner: false, isWritable: true },
+      {
+        pubkey: getAssociatedTokenAddressSync(stream.rewardMint, params.user.publicKey),
+        isSigner: false,
+        isWritable: true,
+      },
+    ]);
 
     await this.program.methods
       .claimRewards()
@@ -176,9 +221,15 @@ export class StakingClient {
         pool: params.pool,
         stakeAccount,
         user: params.user.publicKey,
-        vaultAccount: poolData.vault,
+        rewardVault: this.getRewardVaultAddress(params.pool),
+        poolAuthority: this.getPoolAuthorityAddress(params.pool),
+        userStats: this.getUserStatsAddress(params.user.publicKey),
+        poolStats: this.getPoolStatsAddress(params.pool),
+        rewardConfig: this.getRewardConfigAddress(params.pool),
+        tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
+      .remainingAccounts(streamAccounts)
       .signers([params.user])
       .rpc();
 
@@ -198,6 +249,7 @@ export class StakingClient {
     );
 
     const poolData = await this.getPool(params.pool);
+    const boostAccounts = await this.getBoostReturnAccounts(params.pool, params.user.publicKey);
 
     await this.program.methods
       .emergencyWithdraw()
@@ -206,8 +258,13 @@ export class StakingClient {
         stakeAccount,
         user: params.user.publicKey,
         vaultAccount: poolData.vault,
+        userStats: this.getUserStatsAddress(params.user.publicKey),
+        poolStats: this.getPoolStatsAddress(params.pool),
+        rewardConfig: this.getRewardConfigAddress(params.pool),
+        ...boostAccounts,
         systemProgram: SystemProgram.programId,
       })
+      .remainingAccounts(this.getRewardStreamMetas(params.pool, poolData.rewardStreamCount))
       .signers([params.user])
       .rpc();
 
@@ -230,6 +287,8 @@ export class StakingClient {
       )
       .accounts({
         pool: params.pool,
+        rewardConfig: this.getRewardConfigAddress(params.pool),
+        poolStats: this.getPoolStatsAddress(params.pool),
         authority: params.authority.publicKey,
       })
       .signers([params.authority])
@@ -238,164 +297,1173 @@ export class StakingClient {
     console.log('Pool updated');
   }
 
-  // ===== VIEW FUNCTIONS =====
-
   /**
-   * Fetch pool data with type safety from LUMOS-generated types
+   * Name the NFT collection that boosts rewards and its multiplier in basis
+   * points, e.g. 15000 = 1.5x (admin only)
    */
-  async getPool(poolAddress: PublicKey): Promise<StakingPool> {
-    const pool = await this.program.account.stakingPool.fetch(poolAddress);
+  async setBoostCollection(params: {
+    pool: PublicKey;
+    authority: Keypair;
+    collection: PublicKey | null;
+    multiplier: number;
+  }): Promise<void> {
+    await this.program.methods
+      .setBoostCollection(params.collection, new BN(params.multiplier))
+      .accounts({
+        pool: params.pool,
+        authority: params.authority.publicKey,
+      })
+      .signers([params.authority])
+      .rpc();
 
-    // TypeScript knows the exact structure thanks to LUMOS!
-    return {
-      authority: pool.authority,
-      tokenMint: pool.tokenMint,
-      vault: pool.vault,
-      totalStaked: pool.totalStaked.toNumber(),
-      totalStakers: pool.totalStakers.toNumber(),
-      rewardRate: pool.rewardRate.toNumber(),
-      minStakeAmount: pool.minStakeAmount.toNumber(),
-      minLockDuration: pool.minLockDuration.toNumber(),
-      cooldownPeriod: pool.cooldownPeriod.toNumber(),
-      isActive: pool.isActive,
-      createdAt: pool.createdAt.toNumber(),
-    };
+    console.log(`Boost collection set to ${params.collection?.toBase58() ?? 'none'}`);
   }
 
   /**
-   * Fetch user stake account
+   * Lock an NFT from the pool's boost collection into the user's position
    */
-  async getStakeAccount(
-    pool: PublicKey,
-    user: PublicKey
-  ): Promise<StakeAccount | null> {
+  async lockBoostNft(params: {
+    pool: PublicKey;
+    user: Keypair;
+    nftMint: PublicKey;
+  }): Promise<void> {
     const [stakeAccount] = PublicKey.findProgramAddressSync(
-      [Buffer.from('stake'), pool.toBuffer(), user.toBuffer()],
+      [Buffer.from('stake'), params.pool.toBuffer(), params.user.publicKey.toBuffer()],
       PROGRAM_ID
     );
 
-    try {
-      const account = await this.program.account.stakeAccount.fetch(stakeAccount);
+    await this.program.methods
+      .lockBoostNft()
+      .accounts({
+        pool: params.pool,
+        stakeAccount,
+        poolAuthority: this.getPoolAuthorityAddress(params.pool),
+        nftMint: params.nftMint,
+        nftMetadata: this.getMetadataAddress(params.nftMint),
+        userNftAccount: getAssociatedTokenAddressSync(params.nftMint, params.user.publicKey),
+        nftEscrow: this.getNftEscrowAddress(stakeAccount),
+        user: params.user.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([params.user])
+      .rpc();
 
-      return {
-        owner: account.owner,
-        pool: account.pool,
-        amount: account.amount.toNumber(),
-        stakedAt: account.stakedAt.toNumber(),
-        lastClaimAt: account.lastClaimAt.toNumber(),
-        unlockAt: account.unlockAt.toNumber(),
-        totalClaimed: account.totalClaimed.toNumber(),
-        status: account.status as StakingStatus,
-        unstakeRequestedAt: account.unstakeRequestedAt?.toNumber() || undefined,
-      };
-    } catch (e) {
-      return null;
-    }
+    console.log(`Boost NFT ${params.nftMint.toBase58()} locked`);
   }
 
   /**
-   * Calculate current rewards for a user
+   * Replace a pool's emission schedule (admin only). Rewards already earned
+   * under the old schedule are kept.
    */
-  async calculateRewards(pool: PublicKey, user: PublicKey): Promise<number> {
-    const poolData = await this.getPool(pool);
-    const stakeData = await this.getStakeAccount(pool, user);
+  async setEmissionSchedule(params: {
+    pool: PublicKey;
+    authority: Keypair;
+    schedule: EmissionSchedule;
+    epochs?: EmissionEpoch[]; // only for { kind: 'Epochs' }
+  }): Promise<void> {
+    await this.program.methods
+      .setEmissionSchedule(
+        toAnchorEmissionSchedule(params.schedule),
+        (params.epochs ?? []).map((epoch) => ({
+          startTime: new BN(epoch.start_time),
+          endTime: new BN(epoch.end_time),
+          rewardRate: new BN(epoch.reward_rate),
+        }))
+      )
+      .accounts({
+        pool: params.pool,
+        rewardConfig: this.getRewardConfigAddress(params.pool),
+        poolStats: this.getPoolStatsAddress(params.pool),
+        authority: params.authority.publicKey,
+      })
+      .signers([params.authority])
+      .rpc();
 
-    if (!stakeData || stakeData.amount === 0) {
-      return 0;
-    }
+    console.log(`Emission schedule set to ${params.schedule.kind}`);
+  }
 
-    const currentTime = Math.floor(Date.now() / 1000);
-    const timeStaked = currentTime - stakeData.lastClaimAt;
+  /**
+   * Set or clear a pool's capacity caps and allow-list root (admin only).
+   * Omitted values remove the corresponding limit.
+   */
+  async setStakeLimits(params: {
+    pool: PublicKey;
+    authority: Keypair;
+    maxTotalStaked?: number;
+    maxPerUser?: number;
+    allowlistRoot?: Buffer;
+  }): Promise<void> {
+    await this.program.methods
+      .setStakeLimits(
+        params.maxTotalStaked !== undefined ? new BN(params.maxTotalStaked) : null,
+        params.maxPerUser !== undefined ? new BN(params.maxPerUser) : null,
+        params.allowlistRoot ? Array.from(params.allowlistRoot) : null
+      )
+      .accounts({
+        pool: params.pool,
+        authority: params.authority.publicKey,
+      })
+      .signers([params.authority])
+      .rpc();
 
-    // APY calculation: (amount * rate * time) / (100 * 365 * 86400)
-    const reward =
-      (stakeData.amount * poolData.rewardRate * timeStaked) /
-      (100 * 365 * 86400);
+    console.log('Stake limits updated');
+  }
+
+  /**
+   * Replace the reward config that drives the pool's advertised APY (admin only)
+   */
+  async setRewardConfig(params: {
+    pool: PublicKey;
+    authority: Keypair;
+    calculationType: RewardCalculationType;
+    baseApy: number;
+    bonusMultiplier: number;
+    maxApy: number;
+    compoundFrequency: number;
+  }): Promise<void> {
+    await this.program.methods
+      .setRewardConfig(
+        toAnchorCalculationType(params.calculationType),
+        new BN(params.baseApy),
+        new BN(params.bonusMultiplier),
+        new BN(params.maxApy),
+        new BN(params.compoundFrequency)
+      )
+      .accounts({
+        pool: params.pool,
+        rewardConfig: this.getRewardConfigAddress(params.pool),
+        poolStats: this.getPoolStatsAddress(params.pool),
+        authority: params.authority.publicKey,
+      })
+      .signers([params.authority])
+      .rpc();
 
-    return reward;
+    console.log('Reward config updated');
   }
 
   /**
-   * Calculate APY for a pool
+   * Deposit lamports into the pool's reward vault
    */
-  calculateAPY(rewardRate: number): number {
-    return rewardRate / 100;
+  async fundRewards(params: {
+    pool: PublicKey;
+    funder: Keypair;
+    amount: number;
+  }): Promise<void> {
+    await this.program.methods
+      .fundRewards(new BN(params.amount))
+      .accounts({
+        pool: params.pool,
+        rewardVault: this.getRewardVaultAddress(params.pool),
+        funder: params.funder.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([params.funder])
+      .rpc();
+
+    console.log(`Funded ${params.amount} lamports of rewards`);
   }
 
   /**
-   * Check if user can unstake
+   * Withdraw rewards not owed to any staker (admin only)
    */
-  async canUnstake(pool: PublicKey, user: PublicKey): Promise<boolean> {
-    const stakeData = await this.getStakeAccount(pool, user);
-    if (!stakeData) return false;
+  async withdrawUnallocatedRewards(params: {
+    pool: PublicKey;
+    authority: Keypair;
+    amount: number;
+  }): Promise<void> {
+    await this.program.methods
+      .withdrawUnallocatedRewards(new BN(params.amount))
+      .accounts({
+        pool: params.pool,
+        rewardVault: this.getRewardVaultAddress(params.pool),
+        authority: params.authority.publicKey,
+      })
+      .signers([params.authority])
+      .rpc();
 
-    const currentTime = Math.floor(Date.now() / 1000);
+    console.log(`Withdrew ${params.amount} unallocated reward lamports`);
+  }
 
-    // Check if unlocked
-    const isUnlocked = currentTime >= stakeData.unlockAt;
+  /**
+   * Add an incentive token stream to a pool (admin only)
+   */
+  async addRewardStream(params: {
+    pool: PublicKey;
+    authority: Keypair;
+    rewardMint: PublicKey;
+    emissionRate: number;
+    startTime: number;
+    endTime: number;
+  }): Promise<PublicKey> {
+    const poolData = await this.getPool(params.pool);
+    const rewardStream = this.getRewardStreamAddress(params.pool, poolData.rewardStreamCount);
 
-    // Check if in correct status
-    const correctStatus =
-      stakeData.status === 'Active' || stakeData.status === 'Locked';
+    await this.program.methods
+      .addRewardStream(
+        new BN(params.emissionRate),
+        new BN(params.startTime),
+        new BN(params.endTime)
+      )
+      .accounts({
+        pool: params.pool,
+        poolAuthority: this.getPoolAuthorityAddress(params.pool),
+        rewardStream,
+        rewardMint: params.rewardMint,
+        streamVault: this.getStreamVaultAddress(rewardStream),
+        authority: params.authority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([params.authority])
+      .rpc();
 
-    return isUnlocked && correctStatus;
+    console.log(`Reward stream ${poolData.rewardStreamCount} added for ${params.rewardMint.toBase58()}`);
+    return rewardStream;
   }
 
   /**
-   * Check if user can complete unstake (after cooldown)
+   * Deposit incentive tokens into a reward stream
    */
-  async canCompleteUnstake(pool: PublicKey, user: PublicKey): Promise<boolean> {
-    const poolData = await this.getPool(pool);
-    const stakeData = await this.getStakeAccount(pool, user);
-
-    if (!stakeData || stakeData.status !== 'UnstakeRequested') {
-      return false;
-    }
+  async fundRewardStream(params: {
+    rewardStream: PublicKey;
+    funder: Keypair;
+    amount: number;
+  }): Promise<void> {
+    const stream = await this.program.account.rewardStream.fetch(params.rewardStream);
 
-    const requestedAt = stakeData.unstakeRequestedAt || 0;
-    const currentTime = Math.floor(Date.now() / 1000);
-    const cooldownEnd = requestedAt + poolData.cooldownPeriod;
+    await this.program.methods
+      .fundRewardStream(new BN(params.amount))
+      .accounts({
+        rewardStream: params.rewardStream,
+        streamVault: stream.rewardVault,
+        funderTokenAccount: getAssociatedTokenAddressSync(stream.rewardMint, params.funder.publicKey),
+        funder: params.funder.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([params.funder])
+      .rpc();
 
-    return currentTime >= cooldownEnd;
+    console.log(`Funded reward stream with ${params.amount} tokens`);
   }
-}
 
-// ===== USAGE EXAMPLE =====
+  /**
+   * Switch an empty pool to liquid mode (admin only)
+   */
+  async enableLiquidStaking(params: {
+    pool: PublicKey;
+    authority: Keypair;
+  }): Promise<PublicKey> {
+    const receiptMint = this.getReceiptMintAddress(params.pool);
 
-async function example() {
-  const connection = new Connection('https://api.devnet.solana.com');
-  const wallet = Keypair.generate();
+    await this.program.methods
+      .enableLiquidStaking()
+      .accounts({
+        pool: params.pool,
+        poolAuthority: this.getPoolAuthorityAddress(params.pool),
+        receiptMint,
+        authority: params.authority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([params.authority])
+      .rpc();
 
-  // Initialize client
-  const client = new StakingClient(connection, {} as Program, {} as AnchorProvider);
+    console.log(`Liquid staking enabled, receipt mint: ${receiptMint.toBase58()}`);
+    return receiptMint;
+  }
 
-  // Create a pool
-  const pool = await client.initializePool({
-    authority: wallet,
-    tokenMint: PublicKey.default,
-    vault: PublicKey.default,
-    rewardRate: 1000, // 10% APY (1000 basis points)
-    minStakeAmount: 0.1 * LAMPORTS_PER_SOL,
-    minLockDuration: 7 * 24 * 60 * 60, // 7 days
-    cooldownPeriod: 24 * 60 * 60, // 1 day
-  });
+  /**
+   * Stake into a liquid pool and receive receipt tokens
+   */
+  async liquidStake(params: {
+    pool: PublicKey;
+    user: Keypair;
+    amount: number;
+    proof?: Buffer[]; // required when the pool has an allow-list
+  }): Promise<void> {
+    const poolData = await this.getPool(params.pool);
+    const receiptMint = this.getReceiptMintAddress(params.pool);
 
-  // Stake tokens
-  await client.stake({
-    pool,
-    user: wallet,
-    amount: 10 * LAMPORTS_PER_SOL,
-  });
+    await this.program.methods
+      .liquidStake(new BN(params.amount), (params.proof ?? []).map((node) => Array.from(node)))
+      .accounts({
+        pool: params.pool,
+        poolAuthority: this.getPoolAuthorityAddress(params.pool),
+        receiptMint,
+        receiptAccount: getAssociatedTokenAddressSync(receiptMint, params.user.publicKey),
+        rewardVault: this.getRewardVaultAddress(params.pool),
+        user: params.user.publicKey,
+        vaultAccount: poolData.vault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([params.user])
+      .rpc();
 
-  // Check rewards
-  const rewards = await client.calculateRewards(pool, wallet.publicKey);
-  console.log(`Current rewards: ${rewards / LAMPORTS_PER_SOL} SOL`);
+    console.log(`Liquid staked ${params.amount} lamports`);
+  }
 
-  // Claim rewards
-  await client.claimRewards({
-    pool,
-    user: wallet,
-  });
+  /**
+   * Burn receipt tokens for principal plus accrued rewards
+   */
+  async redeemReceipt(params: {
+    pool: PublicKey;
+    user: Keypair;
+    receipts: number;
+  }): Promise<void> {
+    const poolData = await this.getPool(params.pool);
+    const receiptMint = this.getReceiptMintAddress(params.pool);
+
+    await this.program.methods
+      .redeemReceipt(new BN(params.receipts))
+      .accounts({
+        pool: params.pool,
+        receiptMint,
+        receiptAccount: getAssociatedTokenAddressSync(receiptMint, params.user.publicKey),
+        rewardVault: this.getRewardVaultAddress(params.pool),
+        user: params.user.publicKey,
+        vaultAccount: poolData.vault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([params.user])
+      .rpc();
+
+    console.log(`Redeemed ${params.receipts} receipt tokens`);
+  }
+
+  /**
+   * Switch an empty pool to native staking across a set of validator vote
+   * accounts (admin only)
+   */
+  async enableNativeStaking(params: {
+    pool: PublicKey;
+    authority: Keypair;
+    validators: PublicKey[];
+  }): Promise<void> {
+    await this.program.methods
+      .enableNativeStaking(params.validators)
+      .accounts({
+        pool: params.pool,
+        validatorList: this.getValidatorListAddress(params.pool),
+        rewardConfig: this.getRewardConfigAddress(params.pool),
+        poolStats: this.getPoolStatsAddress(params.pool),
+        authority: params.authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([params.authority])
+      .rpc();
+
+    console.log(`Native staking enabled across ${params.validators.length} validators`);
+  }
+
+  /**
+   * Stake SOL into a native pool; it is delegated to the pool's
+   * least-delegated validator
+   */
+  async nativeStake(params: {
+    pool: PublicKey;
+    user: Keypair;
+    amount: number;
+    proof?: Buffer[]; // required when the pool has an allow-list
+  }): Promise<void> {
+    const [stakeAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from('stake'), params.pool.toBuffer(), params.user.publicKey.toBuffer()],
+      PROGRAM_ID
+    );
+
+    // Same choice as the program: least delegated, earliest on ties
+    const { validators } = await this.getValidatorList(params.pool);
+    const validator = validators.reduce((least, candidate) =>
+      candidate.totalDelegated < least.totalDelegated ? candidate : least
+    );
+
+    await this.program.methods
+      .nativeStake(new BN(params.amount), (params.proof ?? []).map((node) => Array.from(node)))
+      .accounts({
+        pool: params.pool,
+        stakeAccount,
+        validatorList: this.getValidatorListAddress(params.pool),
+        nativeStake: this.getNativeStakeAddress(stakeAccount),
+        voteAccount: validator.voteAccount,
+        poolAuthority: this.getPoolAuthorityAddress(params.pool),
+        user: params.user.publicKey,
+        userStats: this.getUserStatsAddress(params.user.publicKey),
+        poolStats: this.getPoolStatsAddress(params.pool),
+        rewardConfig: this.getRewardConfigAddress(params.pool),
+        clock: SYSVAR_CLOCK_PUBKEY,
+        rent: SYSVAR_RENT_PUBKEY,
+        stakeHistory: SYSVAR_STAKE_HISTORY_PUBKEY,
+        stakeConfig: STAKE_CONFIG_ID,
+        stakeProgram: StakeProgram.programId,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([params.user])
+      .rpc();
+
+    console.log(`Delegated ${params.amount} lamports to ${validator.voteAccount.toBase58()}`);
+  }
+
+  /**
+   * Request unstaking from a native pool (deactivates the stake)
+   */
+  async requestNativeUnstake(params: {
+    pool: PublicKey;
+    user: Keypair;
+  }): Promise<void> {
+    const [stakeAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from('stake'), params.pool.toBuffer(), params.user.publicKey.toBuffer()],
+      PROGRAM_ID
+    );
+
+    await this.program.methods
+      .requestNativeUnstake()
+      .accounts({
+        pool: params.pool,
+        stakeAccount,
+        nativeStake: this.getNativeStakeAddress(stakeAccount),
+        poolAuthority: this.getPoolAuthorityAddress(params.pool),
+        user: params.user.publicKey,
+        clock: SYSVAR_CLOCK_PUBKEY,
+        stakeProgram: StakeProgram.programId,
+      })
+      .signers([params.user])
+      .rpc();
+
+    console.log('Unstake requested, stake deactivating');
+  }
+
+  /**
+   * Withdraw principal plus epoch rewards from a native pool once the stake
+   * has cooled down
+   */
+  async nativeUnstake(params: {
+    pool: PublicKey;
+    user: Keypair;
+  }): Promise<void> {
+    const [stakeAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from('stake'), params.pool.toBuffer(), params.user.publicKey.toBuffer()],
+      PROGRAM_ID
+    );
+
+    await this.program.methods
+      .nativeUnstake()
+      .accounts({
+        pool: params.pool,
+        stakeAccount,
+        validatorList: this.getValidatorListAddress(params.pool),
+        nativeStake: this.getNativeStakeAddress(stakeAccount),
+        poolAuthority: this.getPoolAuthorityAddress(params.pool),
+        user: params.user.publicKey,
+        userStats: this.getUserStatsAddress(params.user.publicKey),
+        poolStats: this.getPoolStatsAddress(params.pool),
+        rewardConfig: this.getRewardConfigAddress(params.pool),
+        clock: SYSVAR_CLOCK_PUBKEY,
+        rent: SYSVAR_RENT_PUBKEY,
+        stakeHistory: SYSVAR_STAKE_HISTORY_PUBKEY,
+        stakeProgram: StakeProgram.programId,
+      })
+      .signers([params.user])
+      .rpc();
+
+    console.log('Native stake withdrawn');
+  }
+
+  // ===== VIEW FUNCTIONS =====
+
+  /**
+   * Derive the reward vault PDA for a pool
+   */
+  getRewardVaultAddress(pool: PublicKey): PublicKey {
+    const [rewardVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from('reward_vault'), pool.toBuffer()],
+      PROGRAM_ID
+    );
+    return rewardVault;
+  }
+
+  /**
+   * Derive the reward config PDA for a pool
+   */
+  getRewardConfigAddress(pool: PublicKey): PublicKey {
+    const [rewardConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from('reward_config'), pool.toBuffer()],
+      PROGRAM_ID
+    );
+    return rewardConfig;
+  }
+
+  /**
+   * Derive the statistics PDA for a pool
+   */
+  getPoolStatsAddress(pool: PublicKey): PublicKey {
+    const [poolStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from('pool_stats'), pool.toBuffer()],
+      PROGRAM_ID
+    );
+    return poolStats;
+  }
+
+  /**
+   * Derive the cross-pool statistics PDA for a user
+   */
+  getUserStatsAddress(user: PublicKey): PublicKey {
+    const [userStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from('user_stats'), user.toBuffer()],
+      PROGRAM_ID
+    );
+    return userStats;
+  }
+
+  /**
+   * Derive the escrow token account PDA holding a position's boost NFT
+   */
+  getNftEscrowAddress(stakeAccount: PublicKey): PublicKey {
+    const [nftEscrow] = PublicKey.findProgramAddressSync(
+      [Buffer.from('nft_escrow'), stakeAccount.toBuffer()],
+      PROGRAM_ID
+    );
+    return nftEscrow;
+  }
+
+  /**
+   * Derive the Metaplex metadata account of a mint
+   */
+  getMetadataAddress(mint: PublicKey): PublicKey {
+    const [metadata] = PublicKey.findProgramAddressSync(
+      [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+    return metadata;
+  }
+
+  /**
+   * Accounts unstake/emergencyWithdraw need to hand back a locked boost NFT
+   * (all null when the position has none)
+   */
+  private async getBoostReturnAccounts(pool: PublicKey, user: PublicKey) {
+    const stakeData = await this.getStakeAccount(pool, user);
+    if (!stakeData?.boostMint) {
+      return { nftEscrow: null, userNftAccount: null, poolAuthority: null, tokenProgram: null };
+    }
+
+    const [stakeAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from('stake'), pool.toBuffer(), user.toBuffer()],
+      PROGRAM_ID
+    );
+
+    return {
+      nftEscrow: this.getNftEscrowAddress(stakeAccount),
+      userNftAccount: getAssociatedTokenAddressSync(stakeData.boostMint, user),
+      poolAuthority: this.getPoolAuthorityAddress(pool),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+  }
+
+  /**
+   * Derive the PDA that signs for a pool's receipt mint
+   */
+  getPoolAuthorityAddress(pool: PublicKey): PublicKey {
+    const [poolAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from('pool_authority'), pool.toBuffer()],
+      PROGRAM_ID
+    );
+    return poolAuthority;
+  }
+
+  /**
+   * Derive the PDA of a pool's reward stream at `index`
+   */
+  getRewardStreamAddress(pool: PublicKey, index: number): PublicKey {
+    const [rewardStream] = PublicKey.findProgramAddressSync(
+      [Buffer.from('reward_stream'), pool.toBuffer(), Buffer.from([index])],
+      PROGRAM_ID
+    );
+    return rewardStream;
+  }
+
+  /**
+   * Derive the token vault PDA of a reward stream
+   */
+  getStreamVaultAddress(rewardStream: PublicKey): PublicKey {
+    const [streamVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from('stream_vault'), rewardStream.toBuffer()],
+      PROGRAM_ID
+    );
+    return streamVault;
+  }
+
+  /**
+   * Writable metas for every reward stream of a pool, in index order, as
+   * expected by stake/unstake/emergencyWithdraw
+   */
+  private getRewardStreamMetas(pool: PublicKey, count: number) {
+    return Array.from({ length: count }, (_, index) => ({
+      pubkey: this.getRewardStreamAddress(pool, index),
+      isSigner: false,
+      isWritable: true,
+    }));
+  }
+
+  /**
+   * Derive the validator list PDA of a native pool
+   */
+  getValidatorListAddress(pool: PublicKey): PublicKey {
+    const [validatorList] = PublicKey.findProgramAddressSync(
+      [Buffer.from('validator_list'), pool.toBuffer()],
+      PROGRAM_ID
+    );
+    return validatorList;
+  }
+
+  /**
+   * Derive the Stake program account PDA backing a native position
+   */
+  getNativeStakeAddress(stakeAccount: PublicKey): PublicKey {
+    const [nativeStake] = PublicKey.findProgramAddressSync(
+      [Buffer.from('native_stake'), stakeAccount.toBuffer()],
+      PROGRAM_ID
+    );
+    return nativeStake;
+  }
+
+  /**
+   * Derive the receipt mint PDA for a liquid pool
+   */
+  getReceiptMintAddress(pool: PublicKey): PublicKey {
+    const [receiptMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from('receipt_mint'), pool.toBuffer()],
+      PROGRAM_ID
+    );
+    return receiptMint;
+  }
+
+  /**
+   * Fetch pool data with type safety from LUMOS-generated types
+   */
+  async getPool(poolAddress: PublicKey): Promise<StakingPool> {
+    const pool = await this.program.account.stakingPool.fetch(poolAddress);
+
+    // TypeScript knows the exact structure thanks to LUMOS!
+    return {
+      authority: pool.authority,
+      tokenMint: pool.tokenMint,
+      vault: pool.vault,
+      rewardVault: pool.rewardVault,
+      mode: pool.mode as PoolMode,
+      receiptMint: pool.receiptMint || undefined,
+      totalStaked: pool.totalStaked.toNumber(),
+      totalStakers: pool.totalStakers.toNumber(),
+      rewardRate: pool.rewardRate.toNumber(),
+      emissionSchedule: fromAnchorEmissionSchedule(pool.emissionSchedule),
+      // u128 fixed-point, can exceed BN.toNumber()'s 53-bit limit
+      accRewardPerShare: Number(pool.accRewardPerShare.toString()),
+      rewardsOwed: pool.rewardsOwed.toNumber(),
+      lastAccrualAt: pool.lastAccrualAt.toNumber(),
+      rewardStreamCount: pool.rewardStreamCount,
+      minStakeAmount: pool.minStakeAmount.toNumber(),
+      maxTotalStaked: pool.maxTotalStaked?.toNumber() ?? undefined,
+      maxPerUser: pool.maxPerUser?.toNumber() ?? undefined,
+      allowlistRoot: pool.allowlistRoot ?? undefined,
+      boostCollection: pool.boostCollection || undefined,
+      boostMultiplier: pool.boostMultiplier.toNumber(),
+      totalBoostWeight: pool.totalBoostWeight.toNumber(),
+      minLockDuration: pool.minLockDuration.toNumber(),
+      cooldownPeriod: pool.cooldownPeriod.toNumber(),
+      isActive: pool.isActive,
+      createdAt: pool.createdAt.toNumber(),
+      emissionEpochs: pool.emissionEpochs.map((epoch: any) => ({
+        startTime: epoch.startTime.toNumber(),
+        endTime: epoch.endTime.toNumber(),
+        rewardRate: epoch.rewardRate.toNumber(),
+      })),
+    };
+  }
+
+  /**
+   * Fetch the pool's reward vault
+   */
+  async getRewardVault(pool: PublicKey): Promise<RewardVault> {
+    const vault = await this.program.account.rewardVault.fetch(
+      this.getRewardVaultAddress(pool)
+    );
+
+    return {
+      pool: vault.pool,
+      balance: vault.balance.toNumber(),
+      totalFunded: vault.totalFunded.toNumber(),
+      totalDistributed: vault.totalDistributed.toNumber(),
+      totalWithdrawn: vault.totalWithdrawn.toNumber(),
+    };
+  }
+
+  /**
+   * Fetch the pool's reward config
+   */
+  async getRewardConfig(pool: PublicKey): Promise<RewardConfig> {
+    const config = await this.program.account.rewardConfig.fetch(
+      this.getRewardConfigAddress(pool)
+    );
+
+    return {
+      pool: config.pool,
+      calculationType: fromAnchorCalculationType(config.calculationType),
+      baseApy: config.baseApy.toNumber(),
+      bonusMultiplier: config.bonusMultiplier.toNumber(),
+      maxApy: config.maxApy.toNumber(),
+      compoundFrequency: config.compoundFrequency.toNumber(),
+    };
+  }
+
+  /**
+   * Fetch pool statistics
+   */
+  async getPoolStats(pool: PublicKey): Promise<PoolStats> {
+    const stats = await this.program.account.poolStats.fetch(this.getPoolStatsAddress(pool));
+
+    return {
+      pool: stats.pool,
+      totalRewardsDistributed: stats.totalRewardsDistributed.toNumber(),
+      averageStakeDuration: stats.averageStakeDuration.toNumber(),
+      highestStake: stats.highestStake.toNumber(),
+      currentApy: stats.currentApy.toNumber(),
+      totalDeposited: stats.totalDeposited.toNumber(),
+      // u128 lamport-seconds, can exceed BN.toNumber()'s 53-bit limit
+      stakeSeconds: Number(stats.stakeSeconds.toString()),
+      lastUpdated: stats.lastUpdated.toNumber(),
+    };
+  }
+
+  /**
+   * Fetch a user's statistics across all pools
+   */
+  async getUserStats(user: PublicKey): Promise<UserStakingStats | null> {
+    try {
+      const stats = await this.program.account.userStakingStats.fetch(
+        this.getUserStatsAddress(user)
+      );
+
+      return {
+        owner: stats.owner,
+        totalStakedLifetime: stats.totalStakedLifetime.toNumber(),
+        totalRewardsClaimed: stats.totalRewardsClaimed.toNumber(),
+        totalPoolsParticipated: stats.totalPoolsParticipated.toNumber(),
+        firstStakeAt: stats.firstStakeAt.toNumber(),
+        lastActivityAt: stats.lastActivityAt.toNumber(),
+      };
+    } catch (e) {
+      return null;
+    }
+  }
+
+  /**
+   * Fetch a native pool's validators and what is delegated to each
+   */
+  async getValidatorList(pool: PublicKey): Promise<ValidatorList> {
+    const list = await this.program.account.validatorList.fetch(
+      this.getValidatorListAddress(pool)
+    );
+
+    return {
+      pool: list.pool,
+      validators: list.validators.map((validator: any) => ({
+        voteAccount: validator.voteAccount,
+        totalDelegated: validator.totalDelegated.toNumber(),
+        totalRewards: validator.totalRewards.toNumber(),
+      })),
+    };
+  }
+
+  /**
+   * Epoch rewards compounded into a user's native position so far; they are
+   * paid out with the principal on unstake
+   */
+  async getNativeStakeRewards(pool: PublicKey, user: PublicKey): Promise<number> {
+    const [stakeAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from('stake'), pool.toBuffer(), user.toBuffer()],
+      PROGRAM_ID
+    );
+
+    const stakeData = await this.getStakeAccount(pool, user);
+    const info = await this.connection.getAccountInfo(this.getNativeStakeAddress(stakeAccount));
+    if (!stakeData || !info) {
+      return 0;
+    }
+
+    const reserve = await this.connection.getMinimumBalanceForRentExemption(info.data.length);
+    return Math.max(info.lamports - reserve - stakeData.amount, 0);
+  }
+
+  /**
+   * Fetch every reward stream of a pool, in index order
+   */
+  async getRewardStreams(
+    pool: PublicKey
+  ): Promise<{ address: PublicKey; stream: RewardStream }[]> {
+    const poolData = await this.getPool(pool);
+    const streams = [];
+
+    for (let index = 0; index < poolData.rewardStreamCount; index++) {
+      const address = this.getRewardStreamAddress(pool, index);
+      const stream = await this.program.account.rewardStream.fetch(address);
+
+      streams.push({
+        address,
+        stream: {
+          pool: stream.pool,
+          index: stream.index,
+          rewardMint: stream.rewardMint,
+          rewardVault: stream.rewardVault,
+          emissionRate: stream.emissionRate.toNumber(),
+          startTime: stream.startTime.toNumber(),
+          endTime: stream.endTime.toNumber(),
+          // u128 fixed-point, can exceed BN.toNumber()'s 53-bit limit
+          accRewardPerShare: Number(stream.accRewardPerShare.toString()),
+          lastUpdateAt: stream.lastUpdateAt.toNumber(),
+          totalFunded: stream.totalFunded.toNumber(),
+          totalDistributed: stream.totalDistributed.toNumber(),
+          totalUnallocated: stream.totalUnallocated.toNumber(),
+        },
+      });
+    }
+
+    return streams;
+  }
+
+  /**
+   * Seconds until the pool's unallocated rewards run out at the current
+   * emission rate (null while nothing is being emitted)
+   */
+  async getSolvencyRunway(pool: PublicKey): Promise<number | null> {
+    const runway = await this.program.methods
+      .getSolvencyRunway()
+      .accounts({
+        pool,
+        rewardVault: this.getRewardVaultAddress(pool),
+      })
+      .view();
+
+    return runway ? runway.toNumber() : null;
+  }
+
+  /**
+   * Lamports redeemable per whole receipt token (1e9 base units) in a liquid
+   * pool, as of the pool's last accrual
+   */
+  async getExchangeRate(pool: PublicKey): Promise<number> {
+    const poolData = await this.getPool(pool);
+    const supply = await this.connection.getTokenSupply(this.getReceiptMintAddress(pool));
+    const receiptSupply = Number(supply.value.amount);
+
+    if (receiptSupply === 0) {
+      return LAMPORTS_PER_SOL;
+    }
+
+    return ((poolData.totalStaked + poolData.rewardsOwed) * LAMPORTS_PER_SOL) / receiptSupply;
+  }
+
+  /**
+   * Fetch user stake account
+   */
+  async getStakeAccount(
+    pool: PublicKey,
+    user: PublicKey
+  ): Promise<StakeAccount | null> {
+    const [stakeAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from('stake'), pool.toBuffer(), user.toBuffer()],
+      PROGRAM_ID
+    );
+
+    try {
+      const account = await this.program.account.stakeAccount.fetch(stakeAccount);
+
+      return {
+        owner: account.owner,
+        pool: account.pool,
+        amount: account.amount.toNumber(),
+        stakedAt: account.stakedAt.toNumber(),
+        lastClaimAt: account.lastClaimAt.toNumber(),
+        unlockAt: account.unlockAt.toNumber(),
+        totalClaimed: account.totalClaimed.toNumber(),
+        rewardPerSharePaid: Number(account.rewardPerSharePaid.toString()),
+        pendingRewards: account.pendingRewards.toNumber(),
+        boostMint: account.boostMint || undefined,
+        boostMultiplier: account.boostMultiplier.toNumber(),
+        status: account.status as StakingStatus,
+        unstakeRequestedAt: account.unstakeRequestedAt?.toNumber() || undefined,
+        streamCheckpoints: account.streamCheckpoints.map((checkpoint: any) => ({
+          rewardPerSharePaid: Number(checkpoint.rewardPerSharePaid.toString()),
+          pending: checkpoint.pending.toNumber(),
+        })),
+      };
+    } catch (e) {
+      return null;
+    }
+  }
+
+  /**
+   * Calculate current rewards for a user
+   */
+  async calculateRewards(pool: PublicKey, user: PublicKey): Promise<number> {
+    const [stakeAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from('stake'), pool.toBuffer(), user.toBuffer()],
+      PROGRAM_ID
+    );
+
+    const stakeData = await this.getStakeAccount(pool, user);
+    if (!stakeData) {
+      return 0;
+    }
+
+    // Evaluated on-chain so epoch and halving boundaries match the program exactly
+    const reward = await this.program.methods
+      .getPendingRewards()
+      .accounts({ pool, stakeAccount })
+      .view();
+
+    return reward.toNumber();
+  }
+
+  /**
+   * Calculate APY for a pool
+   */
+  calculateAPY(rewardRate: number): number {
+    return rewardRate / 100;
+  }
+
+  /**
+   * Check if user can unstake
+   */
+  async canUnstake(pool: PublicKey, user: PublicKey): Promise<boolean> {
+    const stakeData = await this.getStakeAccount(pool, user);
+    if (!stakeData) return false;
+
+    const currentTime = Math.floor(Date.now() / 1000);
+
+    // Check if unlocked
+    const isUnlocked = currentTime >= stakeData.unlockAt;
+
+    // Check if in correct status
+    const correctStatus =
+      stakeData.status === 'Active' || stakeData.status === 'Locked';
+
+    return isUnlocked && correctStatus;
+  }
+
+  /**
+   * Check if user can complete unstake (after cooldown)
+   */
+  async canCompleteUnstake(pool: PublicKey, user: PublicKey): Promise<boolean> {
+    const poolData = await this.getPool(pool);
+    const stakeData = await this.getStakeAccount(pool, user);
+
+    if (!stakeData || stakeData.status !== 'UnstakeRequested') {
+      return false;
+    }
+
+    const requestedAt = stakeData.unstakeRequestedAt || 0;
+    const currentTime = Math.floor(Date.now() / 1000);
+    const cooldownEnd = requestedAt + poolData.cooldownPeriod;
+
+    return currentTime >= cooldownEnd;
+  }
+}
+
+// ===== ALLOW-LIST =====
+
+function sha256(...parts: Buffer[]): Buffer {
+  const hash = createHash('sha256');
+  parts.forEach((part) => hash.update(part));
+  return hash.digest();
+}
+
+/**
+ * Allow-list leaf for a wallet (matches `allowlist_leaf` in lib.rs)
+ */
+export function allowlistLeaf(wallet: PublicKey): Buffer {
+  return sha256(Buffer.from([0]), wallet.toBuffer());
+}
+
+function allowlistNode(a: Buffer, b: Buffer): Buffer {
+  const [left, right] = Buffer.compare(a, b) <= 0 ? [a, b] : [b, a];
+  return sha256(Buffer.from([1]), left, right);
+}
+
+/**
+ * Build a sorted-pair Merkle tree over the allowed wallets.
+ * An odd node out is carried up to the next level unchanged.
+ */
+export function buildAllowlist(wallets: PublicKey[]): {
+  root: Buffer;
+  getProof: (wallet: PublicKey) => Buffer[];
+} {
+  if (wallets.length === 0) {
+    throw new Error('Allow-list needs at least one wallet');
+  }
+
+  const levels: Buffer[][] = [wallets.map(allowlistLeaf)];
+  while (levels[levels.length - 1].length > 1) {
+    const level = levels[levels.length - 1];
+    const next: Buffer[] = [];
+    for (let i = 0; i < level.length; i += 2) {
+      next.push(i + 1 < level.length ? allowlistNode(level[i], level[i + 1]) : level[i]);
+    }
+    levels.push(next);
+  }
+
+  const getProof = (wallet: PublicKey): Buffer[] => {
+    let index = levels[0].findIndex((leaf) => leaf.equals(allowlistLeaf(wallet)));
+    if (index < 0) {
+      throw new Error(`${wallet.toBase58()} is not on the allow-list`);
+    }
+
+    const proof: Buffer[] = [];
+    for (const level of levels.slice(0, -1)) {
+      const sibling = index ^ 1;
+      if (sibling < level.length) {
+        proof.push(level[sibling]);
+      }
+      index = Math.floor(index / 2);
+    }
+    return proof;
+  };
+
+  return { root: levels[levels.length - 1][0], getProof };
+}
+
+/**
+ * Anchor encodes enum arguments as `{ variantName: { ...fields } }`
+ */
+function toAnchorCalculationType(calculationType: RewardCalculationType): any {
+  switch (calculationType.kind) {
+    case 'FixedAPY':
+      return { fixedApy: {} };
+    case 'TieredAPY':
+      return {
+        tieredApy: {
+          tier1Amount: new BN(calculationType.tier1_amount),
+          tier1Apy: new BN(calculationType.tier1_apy),
+          tier2Amount: new BN(calculationType.tier2_amount),
+          tier2Apy: new BN(calculationType.tier2_apy),
+          tier3Apy: new BN(calculationType.tier3_apy),
+        },
+      };
+    case 'DynamicAPY':
+      return {
+        dynamicApy: {
+          minApy: new BN(calculationType.min_apy),
+          maxApy: new BN(calculationType.max_apy),
+          utilizationThreshold: new BN(calculationType.utilization_threshold),
+        },
+      };
+  }
+}
+
+function fromAnchorCalculationType(calculationType: any): RewardCalculationType {
+  if (calculationType.tieredApy) {
+    const tier = calculationType.tieredApy;
+    return {
+      kind: 'TieredAPY',
+      tier1_amount: tier.tier1Amount.toNumber(),
+      tier1_apy: tier.tier1Apy.toNumber(),
+      tier2_amount: tier.tier2Amount.toNumber(),
+      tier2_apy: tier.tier2Apy.toNumber(),
+      tier3_apy: tier.tier3Apy.toNumber(),
+    };
+  }
+  if (calculationType.dynamicApy) {
+    const dynamic = calculationType.dynamicApy;
+    return {
+      kind: 'DynamicAPY',
+      min_apy: dynamic.minApy.toNumber(),
+      max_apy: dynamic.maxApy.toNumber(),
+      utilization_threshold: dynamic.utilizationThreshold.toNumber(),
+    };
+  }
+  return { kind: 'FixedAPY' };
+}
+
+function toAnchorEmissionSchedule(schedule: EmissionSchedule): any {
+  switch (schedule.kind) {
+    case 'Fixed':
+      return { fixed: {} };
+    case 'Epochs':
+      return { epochs: {} };
+    case 'Halving':
+      return {
+        halving: {
+          startTime: new BN(schedule.start_time),
+          interval: new BN(schedule.interval),
+          initialRate: new BN(schedule.initial_rate),
+        },
+      };
+  }
+}
+
+function fromAnchorEmissionSchedule(schedule: any): EmissionSchedule {
+  if (schedule.halving) {
+    return {
+      kind: 'Halving',
+      start_time: schedule.halving.startTime.toNumber(),
+      interval: schedule.halving.interval.toNumber(),
+      initial_rate: schedule.halving.initialRate.toNumber(),
+    };
+  }
+  return schedule.epochs ? { kind: 'Epochs' } : { kind: 'Fixed' };
+}
+
+// ===== USAGE EXAMPLE =====
+
+async function example() {
+  const connection = new Connection('https://api.devnet.solana.com');
+  const wallet = Keypair.generate();
+
+  // Initialize client
+  const client = new StakingClient(connection, {} as Program, {} as AnchorProvider);
+
+  // Create a pool
+  const pool = await client.initializePool({
+    authority: wallet,
+    tokenMint: PublicKey.default,
+    vault: PublicKey.default,
+    rewardRate: 1000, // 10% APY (1000 basis points)
+    minStakeAmount: 0.1 * LAMPORTS_PER_SOL,
+    minLockDuration: 7 * 24 * 60 * 60, // 7 days
+    cooldownPeriod: 24 * 60 * 60, // 1 day
+  });
+
+  // Fund the reward vault (rewards never come out of staked principal)
+  await client.fundRewards({
+    pool,
+    funder: wallet,
+    amount: 1 * LAMPORTS_PER_SOL,
+  });
+
+  const runway = await client.getSolvencyRunway(pool);
+  console.log(`Rewards funded for: ${runway ?? 'unlimited'} seconds`);
+
+  // Stake tokens
+  await client.stake({
+    pool,
+    user: wallet,
+    amount: 10 * LAMPORTS_PER_SOL,
+  });
+
+  // Check rewards
+  const rewards = await client.calculateRewards(pool, wallet.publicKey);
+  console.log(`Current rewards: ${rewards / LAMPORTS_PER_SOL} SOL`);
+
+  // Claim rewards
+  await client.claimRewards({
+    pool,
+    user: wallet,
+  });
+
+  const stats = await client.getPoolStats(pool);
+  console.log(`Current APY: ${stats.currentApy / 100}%`);
+  console.log(`Average stake duration: ${stats.averageStakeDuration / 86400} days`);
 
   // Request unstake
   await client.requestUnstake({
@@ -9,14 +9,92 @@ pub struct StakingPool {
     pub authority: Pubkey,
     pub token_mint: Pubkey,
     pub vault: Pubkey,
+    pub reward_vault: Pubkey,
+    pub mode: PoolMode,
+    pub receipt_mint: Option<Pubkey>,
     pub total_staked: u64,
     pub total_stakers: u64,
     pub reward_rate: u64,
+    pub emission_schedule: EmissionSchedule,
+    pub acc_reward_per_share: u128,
+    pub rewards_owed: u64,
+    pub last_accrual_at: i64,
+    pub reward_stream_count: u8,
     pub min_stake_amount: u64,
+    pub max_total_staked: Option<u64>,
+    pub max_per_user: Option<u64>,
+    pub allowlist_root: Option<[u8; 32]>,
+    pub boost_collection: Option<Pubkey>,
+    pub boost_multiplier: u64,
+    pub total_boost_weight: u64,
     pub min_lock_duration: i64,
     pub cooldown_period: i64,
     pub is_active: bool,
     pub created_at: i64,
+    pub emission_epochs: Vec<EmissionEpoch>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub enum PoolMode {
+    Standard,
+    Liquid,
+    NativeStake,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub enum EmissionSchedule {
+    Fixed,
+    Epochs,
+    Halving {
+        start_time: i64,
+        interval: i64,
+        initial_rate: u64,
+    },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct EmissionEpoch {
+    pub start_time: i64,
+    pub end_time: i64,
+    pub reward_rate: u64,
+}
+
+#[account]
+pub struct ValidatorList {
+    pub pool: Pubkey,
+    pub validators: Vec<ValidatorStake>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct ValidatorStake {
+    pub vote_account: Pubkey,
+    pub total_delegated: u64,
+    pub total_rewards: u64,
+}
+
+#[account]
+pub struct RewardVault {
+    pub pool: Pubkey,
+    pub balance: u64,
+    pub total_funded: u64,
+    pub total_distributed: u64,
+    pub total_withdrawn: u64,
+}
+
+#[account]
+pub struct RewardStream {
+    pub pool: Pubkey,
+    pub index: u8,
+    pub reward_mint: Pubkey,
+    pub reward_vault: Pubkey,
+    pub emission_rate: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub acc_reward_per_share: u128,
+    pub last_update_at: i64,
+    pub total_funded: u64,
+    pub total_distributed: u64,
+    pub total_unallocated: u64,
 }
 
 #[account]
@@ -28,8 +106,19 @@ pub struct StakeAccount {
     pub last_claim_at: i64,
     pub unlock_at: i64,
     pub total_claimed: u64,
+    pub reward_per_share_paid: u128,
+    pub pending_rewards: u64,
+    pub boost_mint: Option<Pubkey>,
+    pub boost_multiplier: u64,
     pub status: StakingStatus,
     pub unstake_requested_at: Option<i64>,
+    pub stream_checkpoints: Vec<StreamCheckpoint>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+pub struct StreamCheckpoint {
+    pub reward_per_share_paid: u128,
+    pub pending: u64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
@@ -110,5 +199,7 @@ pub struct PoolStats {
     pub average_stake_duration: i64,
     pub highest_stake: u64,
     pub current_apy: u64,
+    pub total_deposited: u64,
+    pub stake_seconds: u128,
     pub last_updated: i64,
 }
@@ -1,11 +1,29 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, CreateAccount};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Burn, CloseAccount, Mint, MintTo, Token, TokenAccount, Transfer};
+use solana_sha256_hasher::hashv;
+use solana_stake_interface::instruction as stake_instruction;
+use solana_stake_interface::state::{Authorized, Lockup, StakeStateV2};
 
 // Import LUMOS-generated types
-mod generated;
+pub mod generated;
 use generated::*;
 
+pub mod math;
+use math::*;
+
 declare_id!("stk1111111111111111111111111111111111111111");
 
+/// Maximum NFT reward boost, in basis points (5x)
+pub const MAX_BOOST_MULTIPLIER: u64 = 50_000;
+
+/// Metaplex Token Metadata program, which owns NFT metadata accounts
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Maximum number of validators a native pool delegates to
+pub const MAX_VALIDATORS: usize = 8;
+
 #[program]
 pub mod defi_staking {
     use super::*;
@@ -29,14 +47,53 @@ pub mod defi_staking {
         pool.authority = ctx.accounts.authority.key();
         pool.token_mint = ctx.accounts.token_mint.key();
         pool.vault = ctx.accounts.vault.key();
+        pool.reward_vault = ctx.accounts.reward_vault.key();
+        pool.mode = PoolMode::Standard;
+        pool.receipt_mint = None;
         pool.total_staked = 0;
         pool.total_stakers = 0;
         pool.reward_rate = reward_rate;
+        pool.emission_schedule = EmissionSchedule::Fixed;
+        pool.acc_reward_per_share = 0;
+        pool.rewards_owed = 0;
+        pool.last_accrual_at = clock.unix_timestamp;
+        pool.reward_stream_count = 0;
         pool.min_stake_amount = min_stake_amount;
+        pool.max_total_staked = None;
+        pool.max_per_user = None;
+        pool.allowlist_root = None;
+        pool.boost_collection = None;
+        pool.boost_multiplier = BASIS_POINTS;
+        pool.total_boost_weight = 0;
         pool.min_lock_duration = min_lock_duration;
         pool.cooldown_period = cooldown_period;
         pool.is_active = true;
         pool.created_at = clock.unix_timestamp;
+        pool.emission_epochs = Vec::new();
+
+        let reward_vault = &mut ctx.accounts.reward_vault;
+        reward_vault.pool = pool.key();
+        reward_vault.balance = 0;
+        reward_vault.total_funded = 0;
+        reward_vault.total_distributed = 0;
+        reward_vault.total_withdrawn = 0;
+
+        // Advertised APY starts out as the flat reward rate
+        let reward_config = &mut ctx.accounts.reward_config;
+        reward_config.pool = pool.key();
+        reward_config.calculation_type = RewardCalculationType::FixedAPY;
+        reward_config.compound_frequency = 0;
+        sync_reward_config(reward_config, pool, clock.unix_timestamp);
+
+        let pool_stats = &mut ctx.accounts.pool_stats;
+        pool_stats.pool = pool.key();
+        pool_stats.total_rewards_distributed = 0;
+        pool_stats.average_stake_duration = 0;
+        pool_stats.highest_stake = 0;
+        pool_stats.current_apy = current_apy(reward_config, pool, clock.unix_timestamp);
+        pool_stats.total_deposited = 0;
+        pool_stats.stake_seconds = 0;
+        pool_stats.last_updated = clock.unix_timestamp;
 
         msg!(
             "Pool initialized with {}% APY, min stake: {}, lock: {}s",
@@ -49,17 +106,31 @@ pub mod defi_staking {
     }
 
     /// Stake tokens into the pool
+    ///
+    /// `proof` is the user's Merkle proof when the pool has an allow-list, empty otherwise.
+    /// Remaining accounts: every `RewardStream` of the pool (writable), in index order
     pub fn stake(
         ctx: Context<Stake>,
         amount: u64,
+        proof: Vec<[u8; 32]>,
     ) -> Result<()> {
         let pool = &mut ctx.accounts.pool;
         let stake_account = &mut ctx.accounts.stake_account;
         let clock = Clock::get()?;
 
         // Validations
+        require!(matches!(pool.mode, PoolMode::Standard), StakingError::InvalidPoolMode);
         require!(pool.is_active, StakingError::PoolNotActive);
         require!(amount >= pool.min_stake_amount, StakingError::BelowMinStake);
+        require!(
+            pool.max_total_staked.is_none_or(|cap| pool.total_staked + amount <= cap),
+            StakingError::PoolCapExceeded
+        );
+        require!(
+            pool.max_per_user.is_none_or(|cap| stake_account.amount + amount <= cap),
+            StakingError::UserCapExceeded
+        );
+        verify_allowlist(pool, &ctx.accounts.user.key(), &proof)?;
 
         // Transfer tokens from user to vault (simplified - assumes SOL transfer)
         let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
@@ -76,6 +147,22 @@ pub mod defi_staking {
             ],
         )?;
 
+        // Settle pool-wide reward liabilities before the staked total changes
+        accrue_pool_rewards(pool, clock.unix_timestamp);
+        settle_position_rewards(pool, stake_account);
+        settle_reward_streams(pool, stake_account, ctx.remaining_accounts, clock.unix_timestamp)?;
+        accrue_stake_seconds(&mut ctx.accounts.pool_stats, pool.total_staked, clock.unix_timestamp);
+
+        // First stake by this user anywhere, or first time in this pool
+        let user_stats = &mut ctx.accounts.user_stats;
+        if user_stats.owner == Pubkey::default() {
+            user_stats.owner = ctx.accounts.user.key();
+            user_stats.first_stake_at = clock.unix_timestamp;
+        }
+        if stake_account.pool == Pubkey::default() {
+            user_stats.total_pools_participated += 1;
+        }
+
         // Initialize or update stake account
         let unlock_time = clock.unix_timestamp + pool.min_lock_duration;
 
@@ -86,6 +173,8 @@ pub mod defi_staking {
             stake_account.staked_at = clock.unix_timestamp;
             stake_account.last_claim_at = clock.unix_timestamp;
             stake_account.total_claimed = 0;
+            stake_account.boost_mint = None;
+            stake_account.boost_multiplier = BASIS_POINTS;
             stake_account.status = if pool.min_lock_duration > 0 {
                 StakingStatus::Locked
             } else {
@@ -96,9 +185,19 @@ pub mod defi_staking {
             pool.total_stakers += 1;
         }
 
+        let bonus_before = boost_bonus(stake_account);
         stake_account.amount += amount;
         stake_account.unlock_at = unlock_time;
         pool.total_staked += amount;
+        pool.total_boost_weight = pool.total_boost_weight - bonus_before + boost_bonus(stake_account);
+
+        user_stats.total_staked_lifetime += amount;
+        user_stats.last_activity_at = clock.unix_timestamp;
+
+        let pool_stats = &mut ctx.accounts.pool_stats;
+        pool_stats.total_deposited += amount;
+        pool_stats.highest_stake = pool_stats.highest_stake.max(stake_account.amount);
+        refresh_pool_stats(pool_stats, pool, &ctx.accounts.reward_config, clock.unix_timestamp);
 
         msg!("Staked {} tokens, unlock at: {}", amount, unlock_time);
 
@@ -114,6 +213,7 @@ pub mod defi_staking {
         let clock = Clock::get()?;
 
         // Validations
+        require!(!matches!(pool.mode, PoolMode::NativeStake), StakingError::InvalidPoolMode);
         require!(stake_account.owner == ctx.accounts.user.key(), StakingError::Unauthorized);
         require!(stake_account.amount > 0, StakingError::NoStakedAmount);
         require!(
@@ -137,6 +237,8 @@ pub mod defi_staking {
     }
 
     /// Complete unstaking after cooldown period
+    ///
+    /// Remaining accounts: every `RewardStream` of the pool (writable), in index order
     pub fn unstake(
         ctx: Context<Unstake>,
     ) -> Result<()> {
@@ -145,6 +247,7 @@ pub mod defi_staking {
         let clock = Clock::get()?;
 
         // Validations
+        require!(!matches!(pool.mode, PoolMode::NativeStake), StakingError::InvalidPoolMode);
         require!(stake_account.owner == ctx.accounts.user.key(), StakingError::Unauthorized);
         require!(
             matches!(stake_account.status, StakingStatus::UnstakeRequested),
@@ -160,30 +263,67 @@ pub mod defi_staking {
 
         let amount = stake_account.amount;
 
+        // Rewards left unclaimed at unstake are forfeited back to the reward vaults
+        accrue_pool_rewards(pool, clock.unix_timestamp);
+        release_forfeited_rewards(pool, stake_account);
+        settle_reward_streams(pool, stake_account, ctx.remaining_accounts, clock.unix_timestamp)?;
+        forfeit_stream_rewards(stake_account);
+        accrue_stake_seconds(&mut ctx.accounts.pool_stats, pool.total_staked, clock.unix_timestamp);
+
         // Transfer tokens from vault back to user
         **ctx.accounts.vault_account.try_borrow_mut_lamports()? -= amount;
         **ctx.accounts.user.try_borrow_mut_lamports()? += amount;
 
+        // A locked boost NFT goes back to the owner with the principal
+        if stake_account.boost_mint.is_some() {
+            let pool_key = pool.key();
+            return_boost_nft(
+                stake_account,
+                &pool_key,
+                ctx.bumps.pool_authority,
+                BoostNftReturn {
+                    nft_escrow: ctx.accounts.nft_escrow.as_deref(),
+                    user_nft_account: ctx.accounts.user_nft_account.as_deref(),
+                    pool_authority: ctx.accounts.pool_authority.as_ref(),
+                    token_program: ctx.accounts.token_program.as_ref(),
+                    user: &ctx.accounts.user,
+                },
+            )?;
+        }
+
         // Update state
         pool.total_staked -= amount;
         pool.total_stakers -= 1;
+        pool.total_boost_weight -= boost_bonus(stake_account);
         stake_account.amount = 0;
+        stake_account.boost_mint = None;
+        stake_account.boost_multiplier = BASIS_POINTS;
         stake_account.status = StakingStatus::Unstaked;
 
+        ctx.accounts.user_stats.last_activity_at = clock.unix_timestamp;
+        refresh_pool_stats(&mut ctx.accounts.pool_stats, pool, &ctx.accounts.reward_config, clock.unix_timestamp);
+
         msg!("Unstaked {} tokens", amount);
 
         Ok(())
     }
 
-    /// Claim accumulated staking rewards
-    pub fn claim_rewards(
-        ctx: Context<ClaimRewards>,
+    /// Claim accumulated staking rewards from the pool's reward vault and
+    /// every reward stream
+    ///
+    /// Remaining accounts, per stream in index order: `RewardStream` (writable),
+    /// its stream vault (writable), the user's token account for the stream mint (writable)
+    pub fn claim_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimRewards<'info>>,
     ) -> Result<()> {
-        let pool = &ctx.accounts.pool;
+        let pool = &mut ctx.accounts.pool;
         let stake_account = &mut ctx.accounts.stake_account;
+        let reward_vault = &mut ctx.accounts.reward_vault;
         let clock = Clock::get()?;
 
         // Validations
+        // Native positions are paid their epoch rewards by `native_unstake`
+        require!(!matches!(pool.mode, PoolMode::NativeStake), StakingError::InvalidPoolMode);
         require!(stake_account.owner == ctx.accounts.user.key(), StakingError::Unauthorized);
         require!(stake_account.amount > 0, StakingError::NoStakedAmount);
         require!(
@@ -192,26 +332,91 @@ pub mod defi_staking {
         );
 
         // Calculate rewards
-        let time_staked = clock.unix_timestamp - stake_account.last_claim_at;
-        require!(time_staked > 0, StakingError::NoRewardsToClaim);
+        accrue_pool_rewards(pool, clock.unix_timestamp);
+        settle_position_rewards(pool, stake_account);
+        let reward = stake_account.pending_rewards;
 
-        // Simplified APY calculation: (amount * rate * time) / (100 * 365 * 86400)
-        // rate is in basis points (e.g., 1000 = 10%)
-        let reward = (stake_account.amount as u128
-            * pool.reward_rate as u128
-            * time_staked as u128)
-            / (100u128 * 365u128 * 86400u128);
+        // Rewards are only ever paid from the reward vault, never from principal
+        require!(reward <= reward_vault.balance, StakingError::RewardsExhausted);
 
-        let reward = reward as u64;
-        require!(reward > 0, StakingError::NoRewardsToClaim);
+        // Pay out every reward stream
+        let streams = ctx.remaining_accounts;
+        require!(
+            streams.len() == pool.reward_stream_count as usize * 3,
+            StakingError::InvalidRewardStream
+        );
 
-        // Transfer rewards from vault to user
-        **ctx.accounts.vault_account.try_borrow_mut_lamports()? -= reward;
-        **ctx.accounts.user.try_borrow_mut_lamports()? += reward;
+        let pool_key = pool.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"pool_authority",
+            pool_key.as_ref(),
+            &[ctx.bumps.pool_authority],
+        ]];
+        let mut stream_rewards_paid = false;
+
+        for (index, accounts) in streams.chunks(3).enumerate() {
+            let (stream_info, stream_vault, destination) = (&accounts[0], &accounts[1], &accounts[2]);
+
+            let mut stream = load_reward_stream(stream_info, &pool_key, index as u8)?;
+            require_keys_eq!(stream_vault.key(), stream.reward_vault, StakingError::InvalidRewardStream);
+
+            accrue_reward_stream(&mut stream, pool.total_staked, clock.unix_timestamp);
+            settle_stream_position(&stream, stake_account);
+
+            let payout = stake_account.stream_checkpoints[index].pending;
+            if payout > 0 {
+                let vault_balance = {
+                    let data = stream_vault.try_borrow_data()?;
+                    TokenAccount::try_deserialize(&mut &data[..])?.amount
+                };
+                require!(payout <= vault_balance, StakingError::RewardsExhausted);
+
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: stream_vault.clone(),
+                            to: destination.clone(),
+                            authority: ctx.accounts.pool_authority.to_account_info(),
+                        },
+                        signer_seeds,
+                    ),
+                    payout,
+                )?;
+
+                stake_account.stream_checkpoints[index].pending = 0;
+                stream.total_distributed += payout;
+                stream_rewards_paid = true;
+
+                msg!("Claimed {} of stream {} ({})", payout, index, stream.reward_mint);
+            }
+
+            store_reward_stream(stream_info, &stream)?;
+        }
 
-        // Update state
-        stake_account.last_claim_at = clock.unix_timestamp;
-        stake_account.total_claimed += reward;
+        require!(reward > 0 || stream_rewards_paid, StakingError::NoRewardsToClaim);
+
+        if reward > 0 {
+            // Transfer rewards from reward vault to user
+            **reward_vault.to_account_info().try_borrow_mut_lamports()? -= reward;
+            **ctx.accounts.user.try_borrow_mut_lamports()? += reward;
+
+            reward_vault.balance -= reward;
+            reward_vault.total_distributed += reward;
+            pool.rewards_owed = pool.rewards_owed.saturating_sub(reward);
+            stake_account.pending_rewards = 0;
+            stake_account.last_claim_at = clock.unix_timestamp;
+            stake_account.total_claimed += reward;
+        }
+
+        let user_stats = &mut ctx.accounts.user_stats;
+        user_stats.total_rewards_claimed += reward;
+        user_stats.last_activity_at = clock.unix_timestamp;
+
+        let pool_stats = &mut ctx.accounts.pool_stats;
+        accrue_stake_seconds(pool_stats, pool.total_staked, clock.unix_timestamp);
+        pool_stats.total_rewards_distributed += reward;
+        refresh_pool_stats(pool_stats, pool, &ctx.accounts.reward_config, clock.unix_timestamp);
 
         msg!("Claimed {} tokens in rewards", reward);
 
@@ -219,13 +424,18 @@ pub mod defi_staking {
     }
 
     /// Emergency withdraw with penalty (admin can disable)
+    ///
+    /// Remaining accounts: every `RewardStream` of the pool (writable), in index order
     pub fn emergency_withdraw(
         ctx: Context<EmergencyWithdraw>,
     ) -> Result<()> {
         let pool = &mut ctx.accounts.pool;
         let stake_account = &mut ctx.accounts.stake_account;
+        let clock = Clock::get()?;
 
         // Validations
+        // Delegated stake can't leave before it has cooled down, penalty or not
+        require!(!matches!(pool.mode, PoolMode::NativeStake), StakingError::InvalidPoolMode);
         require!(stake_account.owner == ctx.accounts.user.key(), StakingError::Unauthorized);
         require!(stake_account.amount > 0, StakingError::NoStakedAmount);
 
@@ -233,18 +443,48 @@ pub mod defi_staking {
         let penalty = amount / 10; // 10% penalty
         let withdraw_amount = amount - penalty;
 
+        // Unclaimed rewards are forfeited back to the reward vaults
+        accrue_pool_rewards(pool, clock.unix_timestamp);
+        release_forfeited_rewards(pool, stake_account);
+        settle_reward_streams(pool, stake_account, ctx.remaining_accounts, clock.unix_timestamp)?;
+        forfeit_stream_rewards(stake_account);
+        accrue_stake_seconds(&mut ctx.accounts.pool_stats, pool.total_staked, clock.unix_timestamp);
+
         // Transfer tokens minus penalty
         **ctx.accounts.vault_account.try_borrow_mut_lamports()? -= withdraw_amount;
         **ctx.accounts.user.try_borrow_mut_lamports()? += withdraw_amount;
 
         // Penalty goes to treasury (stays in vault)
 
+        // A locked boost NFT goes back to the owner with the principal
+        if stake_account.boost_mint.is_some() {
+            let pool_key = pool.key();
+            return_boost_nft(
+                stake_account,
+                &pool_key,
+                ctx.bumps.pool_authority,
+                BoostNftReturn {
+                    nft_escrow: ctx.accounts.nft_escrow.as_deref(),
+                    user_nft_account: ctx.accounts.user_nft_account.as_deref(),
+                    pool_authority: ctx.accounts.pool_authority.as_ref(),
+                    token_program: ctx.accounts.token_program.as_ref(),
+                    user: &ctx.accounts.user,
+                },
+            )?;
+        }
+
         // Update state
         pool.total_staked -= amount;
         pool.total_stakers -= 1;
+        pool.total_boost_weight -= boost_bonus(stake_account);
         stake_account.amount = 0;
+        stake_account.boost_mint = None;
+        stake_account.boost_multiplier = BASIS_POINTS;
         stake_account.status = StakingStatus::Unstaked;
 
+        ctx.accounts.user_stats.last_activity_at = clock.unix_timestamp;
+        refresh_pool_stats(&mut ctx.accounts.pool_stats, pool, &ctx.accounts.reward_config, clock.unix_timestamp);
+
         msg!("Emergency withdraw: {} tokens (penalty: {})", withdraw_amount, penalty);
 
         Ok(())
@@ -257,12 +497,23 @@ pub mod defi_staking {
         is_active: Option<bool>,
     ) -> Result<()> {
         let pool = &mut ctx.accounts.pool;
+        let clock = Clock::get()?;
 
         require!(pool.authority == ctx.accounts.authority.key(), StakingError::Unauthorized);
 
         if let Some(rate) = reward_rate {
             require!(rate > 0 && rate <= 1_000_000, StakingError::InvalidRewardRate);
+            require!(
+                matches!(pool.emission_schedule, EmissionSchedule::Fixed),
+                StakingError::InvalidEmissionSchedule
+            );
+            // Time already elapsed keeps the old rate
+            accrue_pool_rewards(pool, clock.unix_timestamp);
             pool.reward_rate = rate;
+
+            let reward_config = &mut ctx.accounts.reward_config;
+            sync_reward_config(reward_config, pool, clock.unix_timestamp);
+            ctx.accounts.pool_stats.current_apy = current_apy(reward_config, pool, clock.unix_timestamp);
             msg!("Updated reward rate to {}%", rate / 100);
         }
 
@@ -273,167 +524,2102 @@ pub mod defi_staking {
 
         Ok(())
     }
-}
 
-// ===== ACCOUNT CONTEXTS =====
+    /// Name the NFT collection whose items boost base rewards, and by how
+    /// much (admin only). Positions keep the multiplier they locked in with.
+    pub fn set_boost_collection(
+        ctx: Context<SetBoostCollection>,
+        collection: Option<Pubkey>,
+        multiplier: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
 
-#[derive(Accounts)]
-pub struct InitializePool<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + std::mem::size_of::<StakingPool>()
-    )]
-    pub pool: Account<'info, StakingPool>,
+        require!(pool.authority == ctx.accounts.authority.key(), StakingError::Unauthorized);
+        require!(matches!(pool.mode, PoolMode::Standard), StakingError::InvalidPoolMode);
+        require!(
+            (BASIS_POINTS..=MAX_BOOST_MULTIPLIER).contains(&multiplier),
+            StakingError::InvalidBoostMultiplier
+        );
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+        pool.boost_collection = collection;
+        pool.boost_multiplier = multiplier;
 
-    /// CHECK: Token mint for the staking pool
-    pub token_mint: AccountInfo<'info>,
+        msg!("Boost collection {:?} at {} bps", collection, multiplier);
 
-    /// CHECK: Vault account to hold staked tokens
-    #[account(mut)]
-    pub vault: AccountInfo<'info>,
+        Ok(())
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    /// Lock an NFT from the pool's boost collection into a position to earn
+    /// boosted base rewards; it is returned on unstake
+    pub fn lock_boost_nft(
+        ctx: Context<LockBoostNft>,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let stake_account = &mut ctx.accounts.stake_account;
+        let clock = Clock::get()?;
 
-#[derive(Accounts)]
-pub struct Stake<'info> {
-    #[account(mut)]
-    pub pool: Account<'info, StakingPool>,
+        // Validations
+        require!(stake_account.owner == ctx.accounts.user.key(), StakingError::Unauthorized);
+        require!(stake_account.amount > 0, StakingError::NoStakedAmount);
+        require!(
+            matches!(stake_account.status, StakingStatus::Active | StakingStatus::Locked),
+            StakingError::InvalidStakingStatus
+        );
+        require!(stake_account.boost_mint.is_none(), StakingError::BoostAlreadyLocked);
 
-    #[account(
-        init_if_needed,
-        payer = user,
-        space = 8 + std::mem::size_of::<StakeAccount>(),
-        seeds = [b"stake", pool.key().as_ref(), user.key().as_ref()],
-        bump
-    )]
-    pub stake_account: Account<'info, StakeAccount>,
+        let collection = pool.boost_collection.ok_or(StakingError::BoostNotEnabled)?;
+        let nft_mint = &ctx.accounts.nft_mint;
+        require!(nft_mint.decimals == 0 && nft_mint.supply == 1, StakingError::InvalidBoostNft);
+        require!(
+            verified_collection(&ctx.accounts.nft_metadata.try_borrow_data()?) == Some(collection),
+            StakingError::InvalidBoostNft
+        );
 
-    #[account(mut)]
-    pub user: Signer<'info>,
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_nft_account.to_account_info(),
+                    to: ctx.accounts.nft_escrow.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            1,
+        )?;
 
-    /// CHECK: Vault account to hold staked tokens
-    #[account(mut)]
-    pub vault_account: AccountInfo<'info>,
+        // Rewards up to now are earned at the unboosted weight
+        accrue_pool_rewards(pool, clock.unix_timestamp);
+        settle_position_rewards(pool, stake_account);
 
-    pub system_program: Program<'info, System>,
-}
+        stake_account.boost_mint = Some(nft_mint.key());
+        stake_account.boost_multiplier = pool.boost_multiplier;
+        pool.total_boost_weight += boost_bonus(stake_account);
 
-#[derive(Accounts)]
-pub struct RequestUnstake<'info> {
-    pub pool: Account<'info, StakingPool>,
+        msg!(
+            "Boost NFT {} locked, reward weight {}",
+            nft_mint.key(),
+            reward_weight(stake_account)
+        );
 
-    #[account(mut)]
-    pub stake_account: Account<'info, StakeAccount>,
+        Ok(())
+    }
 
-    pub user: Signer<'info>,
-}
+    /// Replace the pool's emission schedule (admin only)
+    ///
+    /// Rewards are accrued under the old schedule up to now first, so a new
+    /// schedule only ever affects the future. `epochs` is only used by
+    /// `EmissionSchedule::Epochs` and must be empty otherwise.
+    pub fn set_emission_schedule(
+        ctx: Context<SetEmissionSchedule>,
+        schedule: EmissionSchedule,
+        epochs: Vec<EmissionEpoch>,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let clock = Clock::get()?;
 
-#[derive(Accounts)]
-pub struct Unstake<'info> {
-    #[account(mut)]
-    pub pool: Account<'info, StakingPool>,
+        require!(pool.authority == ctx.accounts.authority.key(), StakingError::Unauthorized);
 
-    #[account(mut)]
-    pub stake_account: Account<'info, StakeAccount>,
+        match schedule {
+            EmissionSchedule::Fixed => {
+                require!(epochs.is_empty(), StakingError::InvalidEmissionSchedule);
+            }
+            EmissionSchedule::Epochs => {
+                require!(
+                    !epochs.is_empty() && epochs.len() <= MAX_EMISSION_EPOCHS,
+                    StakingError::InvalidEmissionSchedule
+                );
+                for epoch in epochs.iter() {
+                    require!(epoch.start_time < epoch.end_time, StakingError::InvalidEmissionSchedule);
+                    require!(epoch.reward_rate <= 1_000_000, StakingError::InvalidRewardRate);
+                }
+                // Sorted and non-overlapping
+                for pair in epochs.windows(2) {
+                    require!(
+                        pair[0].end_time <= pair[1].start_time,
+                        StakingError::InvalidEmissionSchedule
+                    );
+                }
+            }
+            EmissionSchedule::Halving { interval, initial_rate, .. } => {
+                require!(epochs.is_empty() && interval > 0, StakingError::InvalidEmissionSchedule);
+                require!(
+                    initial_rate > 0 && initial_rate <= 1_000_000,
+                    StakingError::InvalidRewardRate
+                );
+            }
+        }
 
-    #[account(mut)]
-    pub user: Signer<'info>,
+        accrue_pool_rewards(pool, clock.unix_timestamp);
 
-    /// CHECK: Vault account holding staked tokens
-    #[account(mut)]
-    pub vault_account: AccountInfo<'info>,
+        pool.emission_schedule = schedule;
+        pool.emission_epochs = epochs;
 
-    pub system_program: Program<'info, System>,
-}
+        let reward_config = &mut ctx.accounts.reward_config;
+        sync_reward_config(reward_config, pool, clock.unix_timestamp);
+        ctx.accounts.pool_stats.current_apy = current_apy(reward_config, pool, clock.unix_timestamp);
 
-#[derive(Accounts)]
-pub struct ClaimRewards<'info> {
-    pub pool: Account<'info, StakingPool>,
+        msg!(
+            "Emission schedule updated, current rate: {}",
+            rate_segment(pool, clock.unix_timestamp).0
+        );
 
-    #[account(mut)]
-    pub stake_account: Account<'info, StakeAccount>,
+        Ok(())
+    }
 
-    #[account(mut)]
-    pub user: Signer<'info>,
+    /// Set or clear the pool's capacity caps and staking allow-list (admin only)
+    pub fn set_stake_limits(
+        ctx: Context<SetStakeLimits>,
+        max_total_staked: Option<u64>,
+        max_per_user: Option<u64>,
+        allowlist_root: Option<[u8; 32]>,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
 
-    /// CHECK: Vault account holding reward tokens
-    #[account(mut)]
-    pub vault_account: AccountInfo<'info>,
+        require!(pool.authority == ctx.accounts.authority.key(), StakingError::Unauthorized);
+        require!(
+            max_total_staked.is_none_or(|cap| cap >= pool.min_stake_amount),
+            StakingError::InvalidStakeLimits
+        );
+        require!(
+            max_per_user.is_none_or(|cap| cap >= pool.min_stake_amount),
+            StakingError::InvalidStakeLimits
+        );
+        if let (Some(total_cap), Some(user_cap)) = (max_total_staked, max_per_user) {
+            require!(user_cap <= total_cap, StakingError::InvalidStakeLimits);
+        }
+        // Receipt tokens are transferable, so there is no per-user position to cap
+        require!(
+            max_per_user.is_none() || matches!(pool.mode, PoolMode::Standard),
+            StakingError::InvalidPoolMode
+        );
 
-    pub system_program: Program<'info, System>,
-}
+        pool.max_total_staked = max_total_staked;
+        pool.max_per_user = max_per_user;
+        pool.allowlist_root = allowlist_root;
 
-#[derive(Accounts)]
-pub struct EmergencyWithdraw<'info> {
-    #[account(mut)]
-    pub pool: Account<'info, StakingPool>,
+        msg!(
+            "Stake limits updated: pool cap {:?}, user cap {:?}, allow-list {}",
+            max_total_staked,
+            max_per_user,
+            if allowlist_root.is_some() { "on" } else { "off" }
+        );
 
-    #[account(mut)]
-    pub stake_account: Account<'info, StakeAccount>,
+        Ok(())
+    }
 
-    #[account(mut)]
-    pub user: Signer<'info>,
+    /// Replace the pool's reward config, which drives the advertised APY (admin only)
+    ///
+    /// A `FixedAPY` config quotes the pool's scheduled reward rate, so its
+    /// `base_apy` and `max_apy` are derived rather than taken from here.
+    pub fn set_reward_config(
+        ctx: Context<SetRewardConfig>,
+        calculation_type: RewardCalculationType,
+        base_apy: u64,
+        bonus_multiplier: u64,
+        max_apy: u64,
+        compound_frequency: i64,
+    ) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        let clock = Clock::get()?;
 
-    /// CHECK: Vault account holding staked tokens
-    #[account(mut)]
-    pub vault_account: AccountInfo<'info>,
+        require!(pool.authority == ctx.accounts.authority.key(), StakingError::Unauthorized);
+        require!(max_apy > 0, StakingError::InvalidRewardConfig);
+        require!(bonus_multiplier >= BASIS_POINTS, StakingError::InvalidRewardConfig);
+        require!(compound_frequency >= 0, StakingError::InvalidRewardConfig);
+        match calculation_type {
+            RewardCalculationType::FixedAPY => {}
+            RewardCalculationType::TieredAPY { tier1_amount, tier2_amount, .. } => {
+                require!(tier1_amount < tier2_amount, StakingError::InvalidRewardConfig);
+            }
+            RewardCalculationType::DynamicAPY { min_apy, max_apy, utilization_threshold } => {
+                require!(
+                    min_apy <= max_apy && utilization_threshold > 0,
+                    StakingError::InvalidRewardConfig
+                );
+            }
+        }
 
-    pub system_program: Program<'info, System>,
-}
+        let reward_config = &mut ctx.accounts.reward_config;
+        reward_config.calculation_type = calculation_type;
+        reward_config.base_apy = base_apy;
+        reward_config.bonus_multiplier = bonus_multiplier;
+        reward_config.max_apy = max_apy;
+        reward_config.compound_frequency = compound_frequency;
+        sync_reward_config(reward_config, pool, clock.unix_timestamp);
 
-#[derive(Accounts)]
-pub struct UpdatePool<'info> {
-    #[account(mut)]
-    pub pool: Account<'info, StakingPool>,
+        let pool_stats = &mut ctx.accounts.pool_stats;
+        pool_stats.current_apy = current_apy(reward_config, pool, clock.unix_timestamp);
 
-    pub authority: Signer<'info>,
-}
+        msg!("Reward config updated, current APY: {} bps", pool_stats.current_apy);
 
-// ===== ERRORS =====
+        Ok(())
+    }
 
-#[error_code]
-pub enum StakingError {
-    #[msg("Invalid reward rate (must be > 0 and <= 1,000,000 basis points)")]
-    InvalidRewardRate,
+    /// Deposit lamports into the pool's reward vault
+    pub fn fund_rewards(
+        ctx: Context<FundRewards>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, StakingError::InvalidAmount);
 
-    #[msg("Invalid minimum stake amount")]
-    InvalidMinStake,
+        let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
+            ctx.accounts.funder.key,
+            &ctx.accounts.reward_vault.key(),
+            amount,
+        );
+        anchor_lang::solana_program::program::invoke(
+            &transfer_ix,
+            &[
+                ctx.accounts.funder.to_account_info(),
+                ctx.accounts.reward_vault.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
 
-    #[msg("Invalid lock duration")]
-    InvalidLockDuration,
+        let reward_vault = &mut ctx.accounts.reward_vault;
+        reward_vault.balance += amount;
+        reward_vault.total_funded += amount;
 
-    #[msg("Invalid cooldown period")]
-    InvalidCooldown,
+        msg!("Funded {} lamports of rewards, vault balance: {}", amount, reward_vault.balance);
 
-    #[msg("Pool is not active")]
-    PoolNotActive,
+        Ok(())
+    }
 
-    #[msg("Amount below minimum stake requirement")]
-    BelowMinStake,
+    /// Withdraw rewards that are not owed to any staker (admin only)
+    pub fn withdraw_unallocated_rewards(
+        ctx: Context<WithdrawUnallocatedRewards>,
+        amount: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let reward_vault = &mut ctx.accounts.reward_vault;
+        let clock = Clock::get()?;
 
-    #[msg("Stake is still locked")]
-    StillLocked,
+        require!(pool.authority == ctx.accounts.authority.key(), StakingError::Unauthorized);
+        require!(amount > 0, StakingError::InvalidAmount);
 
-    #[msg("No staked amount")]
-    NoStakedAmount,
+        accrue_pool_rewards(pool, clock.unix_timestamp);
 
-    #[msg("Invalid staking status for this operation")]
-    InvalidStakingStatus,
+        let unallocated = reward_vault.balance.saturating_sub(pool.rewards_owed);
+        require!(amount <= unallocated, StakingError::InsufficientUnallocatedRewards);
 
-    #[msg("Unstake not requested")]
-    UnstakeNotRequested,
+        **reward_vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.authority.try_borrow_mut_lamports()? += amount;
 
-    #[msg("Cooldown period not complete")]
-    CooldownNotComplete,
+        reward_vault.balance -= amount;
+        reward_vault.total_withdrawn += amount;
 
-    #[msg("No rewards to claim")]
-    NoRewardsToClaim,
+        msg!("Withdrew {} unallocated reward lamports", amount);
 
-    #[msg("Unauthorized")]
-    Unauthorized,
+        Ok(())
+    }
+
+    /// Add an incentive token emitted alongside the pool's base rewards (admin only)
+    pub fn add_reward_stream(
+        ctx: Context<AddRewardStream>,
+        emission_rate: u64,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let stream = &mut ctx.accounts.reward_stream;
+        let clock = Clock::get()?;
+
+        require!(pool.authority == ctx.accounts.authority.key(), StakingError::Unauthorized);
+        require!(matches!(pool.mode, PoolMode::Standard), StakingError::InvalidPoolMode);
+        require!(
+            pool.reward_stream_count < MAX_REWARD_STREAMS,
+            StakingError::TooManyRewardStreams
+        );
+        require!(emission_rate > 0, StakingError::InvalidEmissionRate);
+        require!(end_time > start_time, StakingError::InvalidStreamSchedule);
+
+        stream.pool = pool.key();
+        stream.index = pool.reward_stream_count;
+        stream.reward_mint = ctx.accounts.reward_mint.key();
+        stream.reward_vault = ctx.accounts.stream_vault.key();
+        stream.emission_rate = emission_rate;
+        stream.start_time = start_time;
+        stream.end_time = end_time;
+        stream.acc_reward_per_share = 0;
+        stream.last_update_at = clock.unix_timestamp;
+        stream.total_funded = 0;
+        stream.total_distributed = 0;
+        stream.total_unallocated = 0;
+
+        pool.reward_stream_count += 1;
+
+        msg!(
+            "Reward stream {} added: {} per second of {} from {} to {}",
+            stream.index,
+            emission_rate,
+            stream.reward_mint,
+            start_time,
+            end_time
+        );
+
+        Ok(())
+    }
+
+    /// Deposit incentive tokens into a reward stream's vault
+    pub fn fund_reward_stream(
+        ctx: Context<FundRewardStream>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, StakingError::InvalidAmount);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.funder_token_account.to_account_info(),
+                    to: ctx.accounts.stream_vault.to_account_info(),
+                    authority: ctx.accounts.funder.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let stream = &mut ctx.accounts.reward_stream;
+        stream.total_funded += amount;
+
+        msg!("Funded reward stream {} with {} tokens", stream.index, amount);
+
+        Ok(())
+    }
+
+    /// Switch an empty pool without a lock or cooldown to liquid mode, creating
+    /// its receipt mint (admin only)
+    pub fn enable_liquid_staking(
+        ctx: Context<EnableLiquidStaking>,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+
+        require!(pool.authority == ctx.accounts.authority.key(), StakingError::Unauthorized);
+        require!(matches!(pool.mode, PoolMode::Standard), StakingError::InvalidPoolMode);
+        require!(
+            pool.total_staked == 0 && pool.total_stakers == 0,
+            StakingError::PoolNotEmpty
+        );
+        // Reward streams, per-user caps and NFT boosts work on stake accounts, which liquid pools don't have
+        require!(pool.reward_stream_count == 0, StakingError::InvalidPoolMode);
+        require!(pool.max_per_user.is_none(), StakingError::InvalidPoolMode);
+        require!(pool.boost_collection.is_none(), StakingError::InvalidPoolMode);
+        // Receipts can be redeemed by whoever holds them, so there is no position to lock
+        require!(
+            pool.min_lock_duration == 0 && pool.cooldown_period == 0,
+            StakingError::InvalidPoolMode
+        );
+
+        pool.mode = PoolMode::Liquid;
+        pool.receipt_mint = Some(ctx.accounts.receipt_mint.key());
+
+        msg!("Liquid staking enabled, receipt mint: {}", ctx.accounts.receipt_mint.key());
+
+        Ok(())
+    }
+
+    /// Stake into a liquid pool and receive transferable receipt tokens
+    ///
+    /// `proof` is the user's Merkle proof when the pool has an allow-list, empty otherwise
+    pub fn liquid_stake(
+        ctx: Context<LiquidStake>,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let clock = Clock::get()?;
+
+        require!(matches!(pool.mode, PoolMode::Liquid), StakingError::InvalidPoolMode);
+        require!(pool.is_active, StakingError::PoolNotActive);
+        require!(amount >= pool.min_stake_amount, StakingError::BelowMinStake);
+        require!(
+            pool.max_total_staked.is_none_or(|cap| pool.total_staked + amount <= cap),
+            StakingError::PoolCapExceeded
+        );
+        verify_allowlist(pool, &ctx.accounts.user.key(), &proof)?;
+
+        accrue_liquid_rewards(pool, ctx.accounts.reward_vault.balance, clock.unix_timestamp);
+
+        // Receipts are priced at the current exchange rate so earlier holders keep their rewards
+        let receipts = receipts_for_deposit(pool, ctx.accounts.receipt_mint.supply, amount);
+        require!(receipts > 0, StakingError::InvalidAmount);
+
+        let transfer_ix = anchor_lang::solana_program::system_instruction::transfer(
+            ctx.accounts.user.key,
+            &pool.vault,
+            amount,
+        );
+        anchor_lang::solana_program::program::invoke(
+            &transfer_ix,
+            &[
+                ctx.accounts.user.to_account_info(),
+                ctx.accounts.vault_account.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+
+        let pool_key = pool.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"pool_authority",
+            pool_key.as_ref(),
+            &[ctx.bumps.pool_authority],
+        ]];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    to: ctx.accounts.receipt_account.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            receipts,
+        )?;
+
+        pool.total_staked += amount;
+
+        msg!("Liquid staked {} lamports for {} receipt tokens", amount, receipts);
+
+        Ok(())
+    }
+
+    /// Burn receipt tokens for their share of principal plus accrued rewards
+    pub fn redeem_receipt(
+        ctx: Context<RedeemReceipt>,
+        receipts: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let reward_vault = &mut ctx.accounts.reward_vault;
+        let clock = Clock::get()?;
+
+        // Like `unstake`, redemption stays open on a paused pool; pausing only stops deposits
+        require!(matches!(pool.mode, PoolMode::Liquid), StakingError::InvalidPoolMode);
+        require!(receipts > 0, StakingError::InvalidAmount);
+
+        accrue_liquid_rewards(pool, reward_vault.balance, clock.unix_timestamp);
+
+        let supply = ctx.accounts.receipt_mint.supply;
+        let (principal, reward) = redemption_value(pool, supply, receipts);
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    from: ctx.accounts.receipt_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            receipts,
+        )?;
+
+        // Principal comes from the stake vault, rewards from the reward vault
+        **ctx.accounts.vault_account.try_borrow_mut_lamports()? -= principal;
+        **reward_vault.to_account_info().try_borrow_mut_lamports()? -= reward;
+        **ctx.accounts.user.try_borrow_mut_lamports()? += principal + reward;
+
+        pool.total_staked -= principal;
+        pool.rewards_owed -= reward;
+        reward_vault.balance -= reward;
+        reward_vault.total_distributed += reward;
+
+        msg!(
+            "Redeemed {} receipt tokens for {} principal + {} rewards",
+            receipts,
+            principal,
+            reward
+        );
+
+        Ok(())
+    }
+
+    /// Switch an empty pool to native staking, delegating deposits across
+    /// `validators` (vote accounts) through the Stake program (admin only)
+    pub fn enable_native_staking(
+        ctx: Context<EnableNativeStaking>,
+        validators: Vec<Pubkey>,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+
+        require!(pool.authority == ctx.accounts.authority.key(), StakingError::Unauthorized);
+        require!(matches!(pool.mode, PoolMode::Standard), StakingError::InvalidPoolMode);
+        require!(
+            pool.total_staked == 0 && pool.total_stakers == 0,
+            StakingError::PoolNotEmpty
+        );
+        // Native positions are closed through the Stake program, which reward
+        // streams and boost NFTs don't hook into
+        require!(pool.reward_stream_count == 0, StakingError::InvalidPoolMode);
+        require!(pool.boost_collection.is_none(), StakingError::InvalidPoolMode);
+        require!(
+            !validators.is_empty() && validators.len() <= MAX_VALIDATORS,
+            StakingError::InvalidValidatorSet
+        );
+        for (index, vote_account) in validators.iter().enumerate() {
+            require!(!validators[..index].contains(vote_account), StakingError::InvalidValidatorSet);
+        }
+
+        let clock = Clock::get()?;
+        accrue_pool_rewards(pool, clock.unix_timestamp);
+        pool.mode = PoolMode::NativeStake;
+
+        // Base rewards stop, so the advertised APY drops to zero
+        let reward_config = &mut ctx.accounts.reward_config;
+        sync_reward_config(reward_config, pool, clock.unix_timestamp);
+        ctx.accounts.pool_stats.current_apy = current_apy(reward_config, pool, clock.unix_timestamp);
+
+        let validator_list = &mut ctx.accounts.validator_list;
+        validator_list.pool = pool.key();
+        validator_list.validators = validators
+            .iter()
+            .map(|vote_account| ValidatorStake {
+                vote_account: *vote_account,
+                total_delegated: 0,
+                total_rewards: 0,
+            })
+            .collect();
+
+        msg!("Native staking enabled across {} validators", validators.len());
+
+        Ok(())
+    }
+
+    /// Stake SOL into a native pool. It is delegated to the pool's
+    /// least-delegated validator from a stake account the pool controls.
+    ///
+    /// `proof` is the user's Merkle proof when the pool has an allow-list, empty otherwise
+    pub fn native_stake(
+        ctx: Context<NativeStake>,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let stake_account = &mut ctx.accounts.stake_account;
+        let clock = Clock::get()?;
+
+        // Validations
+        require!(matches!(pool.mode, PoolMode::NativeStake), StakingError::InvalidPoolMode);
+        require!(pool.is_active, StakingError::PoolNotActive);
+        require!(amount >= pool.min_stake_amount, StakingError::BelowMinStake);
+        // Each position is a single delegation, which can't be topped up
+        require!(stake_account.amount == 0, StakingError::PositionAlreadyOpen);
+        require!(
+            pool.max_total_staked.is_none_or(|cap| pool.total_staked + amount <= cap),
+            StakingError::PoolCapExceeded
+        );
+        require!(
+            pool.max_per_user.is_none_or(|cap| amount <= cap),
+            StakingError::UserCapExceeded
+        );
+        verify_allowlist(pool, &ctx.accounts.user.key(), &proof)?;
+
+        let validator_list = &mut ctx.accounts.validator_list;
+        let index = next_validator(validator_list).ok_or(StakingError::InvalidValidatorSet)?;
+        let validator = &mut validator_list.validators[index];
+        require_keys_eq!(
+            ctx.accounts.vote_account.key(),
+            validator.vote_account,
+            StakingError::InvalidValidator
+        );
+
+        // Fund a stake account with the deposit plus its rent-exempt reserve
+        let stake_key = stake_account.key();
+        let native_stake_seeds: &[&[&[u8]]] = &[&[
+            b"native_stake",
+            stake_key.as_ref(),
+            &[ctx.bumps.native_stake],
+        ]];
+        let space = StakeStateV2::size_of();
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: ctx.accounts.native_stake.to_account_info(),
+                },
+                native_stake_seeds,
+            ),
+            amount + ctx.accounts.rent.minimum_balance(space),
+            space as u64,
+            &solana_stake_interface::program::ID,
+        )?;
+
+        // The pool authority stakes and withdraws; there is no lockup
+        let pool_key = pool.key();
+        let authority_key = ctx.accounts.pool_authority.key();
+        let authority_seeds: &[&[&[u8]]] = &[&[
+            b"pool_authority",
+            pool_key.as_ref(),
+            &[ctx.bumps.pool_authority],
+        ]];
+        anchor_lang::solana_program::program::invoke(
+            &stake_instruction::initialize(
+                &ctx.accounts.native_stake.key(),
+                &Authorized {
+                    staker: authority_key,
+                    withdrawer: authority_key,
+                },
+                &Lockup::default(),
+            ),
+            &[
+                ctx.accounts.native_stake.to_account_info(),
+                ctx.accounts.rent.to_account_info(),
+            ],
+        )?;
+        anchor_lang::solana_program::program::invoke_signed(
+            &stake_instruction::delegate_stake(
+                &ctx.accounts.native_stake.key(),
+                &authority_key,
+                &validator.vote_account,
+            ),
+            &[
+                ctx.accounts.native_stake.to_account_info(),
+                ctx.accounts.vote_account.to_account_info(),
+                ctx.accounts.clock.to_account_info(),
+                ctx.accounts.stake_history.to_account_info(),
+                ctx.accounts.stake_config.to_account_info(),
+                ctx.accounts.pool_authority.to_account_info(),
+            ],
+            authority_seeds,
+        )?;
+
+        accrue_stake_seconds(&mut ctx.accounts.pool_stats, pool.total_staked, clock.unix_timestamp);
+
+        // First stake by this user anywhere, or first time in this pool
+        let user_stats = &mut ctx.accounts.user_stats;
+        if user_stats.owner == Pubkey::default() {
+            user_stats.owner = ctx.accounts.user.key();
+            user_stats.first_stake_at = clock.unix_timestamp;
+        }
+        if stake_account.pool == Pubkey::default() {
+            user_stats.total_pools_participated += 1;
+        }
+
+        let unlock_time = clock.unix_timestamp + pool.min_lock_duration;
+
+        stake_account.owner = ctx.accounts.user.key();
+        stake_account.pool = pool_key;
+        stake_account.amount = amount;
+        stake_account.staked_at = clock.unix_timestamp;
+        stake_account.last_claim_at = clock.unix_timestamp;
+        stake_account.unlock_at = unlock_time;
+        stake_account.boost_mint = None;
+        stake_account.boost_multiplier = BASIS_POINTS;
+        stake_account.status = if pool.min_lock_duration > 0 {
+            StakingStatus::Locked
+        } else {
+            StakingStatus::Active
+        };
+        stake_account.unstake_requested_at = None;
+
+        pool.total_staked += amount;
+        pool.total_stakers += 1;
+        validator.total_delegated += amount;
+
+        user_stats.total_staked_lifetime += amount;
+        user_stats.last_activity_at = clock.unix_timestamp;
+
+        let pool_stats = &mut ctx.accounts.pool_stats;
+        pool_stats.total_deposited += amount;
+        pool_stats.highest_stake = pool_stats.highest_stake.max(amount);
+        refresh_pool_stats(pool_stats, pool, &ctx.accounts.reward_config, clock.unix_timestamp);
+
+        msg!(
+            "Delegated {} lamports to {}, unlock at: {}",
+            amount,
+            validator.vote_account,
+            unlock_time
+        );
+
+        Ok(())
+    }
+
+    /// Request unstaking from a native pool: the position's stake is
+    /// deactivated and can be withdrawn once it has cooled down
+    pub fn request_native_unstake(
+        ctx: Context<RequestNativeUnstake>,
+    ) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        let stake_account = &mut ctx.accounts.stake_account;
+        let clock = Clock::get()?;
+
+        // Validations
+        require!(matches!(pool.mode, PoolMode::NativeStake), StakingError::InvalidPoolMode);
+        require!(stake_account.owner == ctx.accounts.user.key(), StakingError::Unauthorized);
+        require!(stake_account.amount > 0, StakingError::NoStakedAmount);
+        require!(
+            clock.unix_timestamp >= stake_account.unlock_at,
+            StakingError::StillLocked
+        );
+        require!(
+            matches!(stake_account.status, StakingStatus::Active | StakingStatus::Locked),
+            StakingError::InvalidStakingStatus
+        );
+
+        let pool_key = pool.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"pool_authority",
+            pool_key.as_ref(),
+            &[ctx.bumps.pool_authority],
+        ]];
+        anchor_lang::solana_program::program::invoke_signed(
+            &stake_instruction::deactivate_stake(
+                &ctx.accounts.native_stake.key(),
+                &ctx.accounts.pool_authority.key(),
+            ),
+            &[
+                ctx.accounts.native_stake.to_account_info(),
+                ctx.accounts.clock.to_account_info(),
+                ctx.accounts.pool_authority.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+
+        stake_account.status = StakingStatus::UnstakeRequested;
+        stake_account.unstake_requested_at = Some(clock.unix_timestamp);
+
+        msg!(
+            "Stake deactivating from epoch {}, can withdraw after: {} once cooled down",
+            clock.epoch,
+            clock.unix_timestamp + pool.cooldown_period
+        );
+
+        Ok(())
+    }
+
+    /// Complete unstaking from a native pool once the stake has cooled down,
+    /// withdrawing the principal plus the epoch rewards it earned
+    pub fn native_unstake(
+        ctx: Context<NativeUnstake>,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let stake_account = &mut ctx.accounts.stake_account;
+        let clock = Clock::get()?;
+
+        // Validations
+        require!(matches!(pool.mode, PoolMode::NativeStake), StakingError::InvalidPoolMode);
+        require!(stake_account.owner == ctx.accounts.user.key(), StakingError::Unauthorized);
+        require!(
+            matches!(stake_account.status, StakingStatus::UnstakeRequested),
+            StakingError::UnstakeNotRequested
+        );
+
+        let requested_at = stake_account.unstake_requested_at.ok_or(StakingError::UnstakeNotRequested)?;
+        let cooldown_end = requested_at + pool.cooldown_period;
+        require!(
+            clock.unix_timestamp >= cooldown_end,
+            StakingError::CooldownNotComplete
+        );
+
+        let amount = stake_account.amount;
+
+        accrue_stake_seconds(&mut ctx.accounts.pool_stats, pool.total_staked, clock.unix_timestamp);
+
+        // Inflation rewards were compounded into the delegation every epoch
+        let native_stake = &ctx.accounts.native_stake;
+        let lamports = native_stake.lamports();
+        let epoch_rewards = native_stake_rewards(
+            lamports,
+            ctx.accounts.rent.minimum_balance(native_stake.data_len()),
+            amount,
+        );
+        let vote_account = {
+            let data = native_stake.try_borrow_data()?;
+            StakeStateV2::deserialize(&mut &data[..])?
+                .delegation()
+                .map(|delegation| delegation.voter_pubkey)
+        };
+
+        // The Stake program refuses while any of it is still effective
+        let pool_key = pool.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"pool_authority",
+            pool_key.as_ref(),
+            &[ctx.bumps.pool_authority],
+        ]];
+        anchor_lang::solana_program::program::invoke_signed(
+            &stake_instruction::withdraw(
+                &native_stake.key(),
+                &ctx.accounts.pool_authority.key(),
+                &ctx.accounts.user.key(),
+                lamports,
+                None,
+            ),
+            &[
+                native_stake.to_account_info(),
+                ctx.accounts.user.to_account_info(),
+                ctx.accounts.clock.to_account_info(),
+                ctx.accounts.stake_history.to_account_info(),
+                ctx.accounts.pool_authority.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+
+        let validator = ctx
+            .accounts
+            .validator_list
+            .validators
+            .iter_mut()
+            .find(|validator| Some(validator.vote_account) == vote_account)
+            .ok_or(StakingError::InvalidValidator)?;
+        validator.total_delegated -= amount;
+        validator.total_rewards += epoch_rewards;
+
+        // Update state
+        pool.total_staked -= amount;
+        pool.total_stakers -= 1;
+        stake_account.amount = 0;
+        stake_account.total_claimed += epoch_rewards;
+        stake_account.status = StakingStatus::Unstaked;
+
+        let user_stats = &mut ctx.accounts.user_stats;
+        user_stats.total_rewards_claimed += epoch_rewards;
+        user_stats.last_activity_at = clock.unix_timestamp;
+
+        let pool_stats = &mut ctx.accounts.pool_stats;
+        pool_stats.total_rewards_distributed += epoch_rewards;
+        refresh_pool_stats(pool_stats, pool, &ctx.accounts.reward_config, clock.unix_timestamp);
+
+        msg!("Unstaked {} lamports plus {} in epoch rewards", amount, epoch_rewards);
+
+        Ok(())
+    }
+
+    /// View: base rewards a position could claim right now
+    pub fn get_pending_rewards(
+        ctx: Context<GetPendingRewards>,
+    ) -> Result<u64> {
+        let clock = Clock::get()?;
+
+        Ok(pending_rewards(&ctx.accounts.pool, &ctx.accounts.stake_account, clock.unix_timestamp))
+    }
+
+    /// View: seconds until unallocated rewards run out under the emission
+    /// schedule, or `None` if the current stake never exhausts them
+    pub fn get_solvency_runway(
+        ctx: Context<GetSolvencyRunway>,
+    ) -> Result<Option<i64>> {
+        let pool = &ctx.accounts.pool;
+        let reward_vault = &ctx.accounts.reward_vault;
+        let clock = Clock::get()?;
+
+        Ok(solvency_runway(pool, reward_vault.balance, clock.unix_timestamp))
+    }
+}
+
+// ===== REWARD STREAMS =====
+
+/// Load a `RewardStream` passed as a remaining account and check it is the
+/// pool's stream at `index`
+fn load_reward_stream(info: &AccountInfo, pool: &Pubkey, index: u8) -> Result<RewardStream> {
+    require_keys_eq!(*info.owner, crate::ID, StakingError::InvalidRewardStream);
+    require!(info.is_writable, StakingError::InvalidRewardStream);
+
+    let stream = {
+        let data = info.try_borrow_data()?;
+        RewardStream::try_deserialize(&mut &data[..])?
+    };
+    require_keys_eq!(stream.pool, *pool, StakingError::InvalidRewardStream);
+    require!(stream.index == index, StakingError::InvalidRewardStream);
+
+    Ok(stream)
+}
+
+/// Write a `RewardStream` loaded with `load_reward_stream` back to its account
+fn store_reward_stream(info: &AccountInfo, stream: &RewardStream) -> Result<()> {
+    let mut data = info.try_borrow_mut_data()?;
+    stream.try_serialize(&mut &mut data[..])
+}
+
+/// Bring every reward stream of the pool up to date for a position before its
+/// stake or the pool's total changes
+fn settle_reward_streams(
+    pool: &Account<StakingPool>,
+    stake_account: &mut StakeAccount,
+    streams: &[AccountInfo],
+    now: i64,
+) -> Result<()> {
+    require!(
+        streams.len() == pool.reward_stream_count as usize,
+        StakingError::InvalidRewardStream
+    );
+
+    for (index, info) in streams.iter().enumerate() {
+        let mut stream = load_reward_stream(info, &pool.key(), index as u8)?;
+        accrue_reward_stream(&mut stream, pool.total_staked, now);
+        settle_stream_position(&stream, stake_account);
+        store_reward_stream(info, &stream)?;
+    }
+
+    Ok(())
+}
+
+// ===== BOOST NFTS =====
+
+/// Optional accounts `unstake` and `emergency_withdraw` need to return a
+/// locked boost NFT
+struct BoostNftReturn<'a, 'info> {
+    nft_escrow: Option<&'a Account<'info, TokenAccount>>,
+    user_nft_account: Option<&'a Account<'info, TokenAccount>>,
+    pool_authority: Option<&'a UncheckedAccount<'info>>,
+    token_program: Option<&'a Program<'info, Token>>,
+    user: &'a Signer<'info>,
+}
+
+/// Send a position's boost NFT back to its owner and close the escrow
+fn return_boost_nft(
+    stake_account: &StakeAccount,
+    pool_key: &Pubkey,
+    authority_bump: Option<u8>,
+    accounts: BoostNftReturn,
+) -> Result<()> {
+    let (Some(nft_escrow), Some(user_nft_account), Some(pool_authority), Some(token_program), Some(bump)) = (
+        accounts.nft_escrow,
+        accounts.user_nft_account,
+        accounts.pool_authority,
+        accounts.token_program,
+        authority_bump,
+    ) else {
+        return err!(StakingError::MissingBoostAccounts);
+    };
+    require!(
+        Some(user_nft_account.mint) == stake_account.boost_mint
+            && user_nft_account.owner == accounts.user.key(),
+        StakingError::InvalidBoostNft
+    );
+
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_authority", pool_key.as_ref(), &[bump]]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: nft_escrow.to_account_info(),
+                to: user_nft_account.to_account_info(),
+                authority: pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        1,
+    )?;
+    token::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: nft_escrow.to_account_info(),
+            destination: accounts.user.to_account_info(),
+            authority: pool_authority.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    Ok(())
+}
+
+/// `Key::MetadataV1`, the first byte of a Metaplex metadata account
+const METADATA_V1_KEY: u8 = 4;
+
+/// Leading fields of a Metaplex metadata account, up to its collection
+///
+/// Borsh reads fields in order and ignores trailing bytes, so the fields
+/// after `collection` don't need to be spelled out.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct MetadataPrefix {
+    key: u8,
+    update_authority: Pubkey,
+    mint: Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    creators: Option<Vec<MetadataCreator>>,
+    primary_sale_happened: bool,
+    is_mutable: bool,
+    edition_nonce: Option<u8>,
+    token_standard: Option<u8>,
+    collection: Option<MetadataCollection>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct MetadataCreator {
+    address: Pubkey,
+    verified: bool,
+    share: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct MetadataCollection {
+    verified: bool,
+    key: Pubkey,
+}
+
+/// Collection an NFT's metadata account names and the collection authority
+/// has verified, if any
+fn verified_collection(data: &[u8]) -> Option<Pubkey> {
+    let metadata = MetadataPrefix::deserialize(&mut &data[..]).ok()?;
+    if metadata.key != METADATA_V1_KEY {
+        return None;
+    }
+    metadata
+        .collection
+        .filter(|collection| collection.verified)
+        .map(|collection| collection.key)
+}
+
+// ===== ALLOW-LIST =====
+
+/// Leaf of the allow-list Merkle tree for a wallet
+///
+/// Leaves and inner nodes use different prefixes so an inner node can never
+/// be passed off as a leaf.
+fn allowlist_leaf(wallet: &Pubkey) -> [u8; 32] {
+    hashv(&[&[0u8], wallet.as_ref()]).to_bytes()
+}
+
+/// Parent of two nodes; pairs are sorted so proofs don't need direction bits
+fn allowlist_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[1u8], left.as_ref(), right.as_ref()]).to_bytes()
+}
+
+/// Check the wallet's proof against the pool's allow-list root, if it has one
+fn verify_allowlist(pool: &StakingPool, wallet: &Pubkey, proof: &[[u8; 32]]) -> Result<()> {
+    let Some(root) = pool.allowlist_root else {
+        return Ok(());
+    };
+
+    let computed = proof
+        .iter()
+        .fold(allowlist_leaf(wallet), |node, sibling| allowlist_node(&node, sibling));
+    require!(computed == root, StakingError::NotAllowlisted);
+
+    Ok(())
+}
+
+// ===== ACCOUNT CONTEXTS =====
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<StakingPool>()
+            + MAX_EMISSION_EPOCHS * std::mem::size_of::<EmissionEpoch>()
+    )]
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Token mint for the staking pool
+    pub token_mint: AccountInfo<'info>,
+
+    /// CHECK: Vault account to hold staked tokens
+    #[account(mut)]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<RewardVault>(),
+        seeds = [b"reward_vault", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<RewardConfig>(),
+        seeds = [b"reward_config", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_config: Box<Account<'info, RewardConfig>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<PoolStats>(),
+        seeds = [b"pool_stats", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_stats: Box<Account<'info, PoolStats>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<StakeAccount>()
+            + MAX_REWARD_STREAMS as usize * std::mem::size_of::<StreamCheckpoint>(),
+        seeds = [b"stake", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Vault account to hold staked tokens
+    #[account(mut, address = pool.vault)]
+    pub vault_account: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<UserStakingStats>(),
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStakingStats>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_stats", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_stats: Box<Account<'info, PoolStats>>,
+
+    #[account(
+        seeds = [b"reward_config", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_config: Box<Account<'info, RewardConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(mut, has_one = pool)]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(mut, has_one = pool)]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Vault account holding staked tokens
+    #[account(mut, address = pool.vault)]
+    pub vault_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStakingStats>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_stats", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_stats: Box<Account<'info, PoolStats>>,
+
+    #[account(
+        seeds = [b"reward_config", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_config: Box<Account<'info, RewardConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_escrow", stake_account.key().as_ref()],
+        bump
+    )]
+    pub nft_escrow: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub user_nft_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// CHECK: PDA that owns the boost NFT escrow
+    #[account(
+        seeds = [b"pool_authority", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(mut, has_one = pool)]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+
+    /// CHECK: PDA that owns the reward stream vaults
+    #[account(
+        seeds = [b"pool_authority", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStakingStats>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_stats", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_stats: Box<Account<'info, PoolStats>>,
+
+    #[account(
+        seeds = [b"reward_config", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_config: Box<Account<'info, RewardConfig>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(mut, has_one = pool)]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Vault account holding staked tokens
+    #[account(mut, address = pool.vault)]
+    pub vault_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStakingStats>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_stats", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_stats: Box<Account<'info, PoolStats>>,
+
+    #[account(
+        seeds = [b"reward_config", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_config: Box<Account<'info, RewardConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"nft_escrow", stake_account.key().as_ref()],
+        bump
+    )]
+    pub nft_escrow: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub user_nft_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// CHECK: PDA that owns the boost NFT escrow
+    #[account(
+        seeds = [b"pool_authority", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePool<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_config", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_stats", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetBoostCollection<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct LockBoostNft<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(mut, has_one = pool)]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    /// CHECK: PDA that owns the boost NFT escrow
+    #[account(
+        seeds = [b"pool_authority", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Metadata PDA of the NFT, read with `verified_collection`
+    #[account(
+        owner = TOKEN_METADATA_PROGRAM_ID,
+        seeds = [b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), nft_mint.key().as_ref()],
+        seeds::program = TOKEN_METADATA_PROGRAM_ID,
+        bump
+    )]
+    pub nft_metadata: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = user
+    )]
+    pub user_nft_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"nft_escrow", stake_account.key().as_ref()],
+        bump,
+        token::mint = nft_mint,
+        token::authority = pool_authority
+    )]
+    pub nft_escrow: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetEmissionSchedule<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_config", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_stats", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetStakeLimits<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRewardConfig<'info> {
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_config", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_stats", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnallocatedRewards<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddRewardStream<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    /// CHECK: PDA that owns the reward stream vaults
+    #[account(
+        seeds = [b"pool_authority", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<RewardStream>(),
+        seeds = [b"reward_stream", pool.key().as_ref(), &[pool.reward_stream_count]],
+        bump
+    )]
+    pub reward_stream: Account<'info, RewardStream>,
+
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"stream_vault", reward_stream.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = pool_authority
+    )]
+    pub stream_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundRewardStream<'info> {
+    #[account(mut)]
+    pub reward_stream: Account<'info, RewardStream>,
+
+    #[account(mut, address = reward_stream.reward_vault)]
+    pub stream_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub funder_token_account: Account<'info, TokenAccount>,
+
+    pub funder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct EnableLiquidStaking<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    /// CHECK: PDA that signs for the receipt mint
+    #[account(
+        seeds = [b"pool_authority", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"receipt_mint", pool.key().as_ref()],
+        bump,
+        mint::decimals = 9,
+        mint::authority = pool_authority
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LiquidStake<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    /// CHECK: PDA that signs for the receipt mint
+    #[account(
+        seeds = [b"pool_authority", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"receipt_mint", pool.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = user
+    )]
+    pub receipt_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"reward_vault", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Vault account to hold staked tokens
+    #[account(mut, address = pool.vault)]
+    pub vault_account: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemReceipt<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"receipt_mint", pool.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = receipt_mint,
+        token::authority = user
+    )]
+    pub receipt_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Vault account holding staked tokens
+    #[account(mut, address = pool.vault)]
+    pub vault_account: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EnableNativeStaking<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<ValidatorList>()
+            + MAX_VALIDATORS * std::mem::size_of::<ValidatorStake>(),
+        seeds = [b"validator_list", pool.key().as_ref()],
+        bump
+    )]
+    pub validator_list: Account<'info, ValidatorList>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_config", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_stats", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct NativeStake<'info> {
+    #[account(mut)]
+    pub pool: Box<Account<'info, StakingPool>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<StakeAccount>()
+            + MAX_REWARD_STREAMS as usize * std::mem::size_of::<StreamCheckpoint>(),
+        seeds = [b"stake", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub stake_account: Box<Account<'info, StakeAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"validator_list", pool.key().as_ref()],
+        bump
+    )]
+    pub validator_list: Box<Account<'info, ValidatorList>>,
+
+    /// CHECK: Stake account created here for the position, owned by the Stake program
+    #[account(
+        mut,
+        seeds = [b"native_stake", stake_account.key().as_ref()],
+        bump
+    )]
+    pub native_stake: UncheckedAccount<'info>,
+
+    /// CHECK: Checked against the validator list; the Stake program checks it is a vote account
+    pub vote_account: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that is the stake and withdraw authority of native stake accounts
+    #[account(
+        seeds = [b"pool_authority", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<UserStakingStats>(),
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStakingStats>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_stats", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_stats: Box<Account<'info, PoolStats>>,
+
+    #[account(
+        seeds = [b"reward_config", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_config: Box<Account<'info, RewardConfig>>,
+
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: Stake history sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    /// CHECK: Stake config account, which the Stake program still expects on delegation
+    pub stake_config: UncheckedAccount<'info>,
+
+    /// CHECK: Stake program
+    #[account(address = solana_stake_interface::program::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestNativeUnstake<'info> {
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(mut, has_one = pool)]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    /// CHECK: The position's stake account, owned by the Stake program
+    #[account(
+        mut,
+        seeds = [b"native_stake", stake_account.key().as_ref()],
+        bump
+    )]
+    pub native_stake: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that is the stake and withdraw authority of native stake accounts
+    #[account(
+        seeds = [b"pool_authority", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub user: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+    /// CHECK: Stake program
+    #[account(address = solana_stake_interface::program::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct NativeUnstake<'info> {
+    #[account(mut)]
+    pub pool: Box<Account<'info, StakingPool>>,
+
+    #[account(mut, has_one = pool)]
+    pub stake_account: Box<Account<'info, StakeAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"validator_list", pool.key().as_ref()],
+        bump
+    )]
+    pub validator_list: Box<Account<'info, ValidatorList>>,
+
+    /// CHECK: The position's stake account, owned by the Stake program
+    #[account(
+        mut,
+        seeds = [b"native_stake", stake_account.key().as_ref()],
+        bump
+    )]
+    pub native_stake: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that is the stake and withdraw authority of native stake accounts
+    #[account(
+        seeds = [b"pool_authority", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStakingStats>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_stats", pool.key().as_ref()],
+        bump
+    )]
+    pub pool_stats: Box<Account<'info, PoolStats>>,
+
+    #[account(
+        seeds = [b"reward_config", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_config: Box<Account<'info, RewardConfig>>,
+
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: Stake history sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    /// CHECK: Stake program
+    #[account(address = solana_stake_interface::program::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetSolvencyRunway<'info> {
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(
+        seeds = [b"reward_vault", pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+}
+
+#[derive(Accounts)]
+pub struct GetPendingRewards<'info> {
+    pub pool: Account<'info, StakingPool>,
+
+    #[account(has_one = pool)]
+    pub stake_account: Account<'info, StakeAccount>,
+}
+
+// ===== ERRORS =====
+
+#[error_code]
+pub enum StakingError {
+    #[msg("Invalid reward rate (must be > 0 and <= 1,000,000 basis points)")]
+    InvalidRewardRate,
+
+    #[msg("Invalid minimum stake amount")]
+    InvalidMinStake,
+
+    #[msg("Invalid lock duration")]
+    InvalidLockDuration,
+
+    #[msg("Invalid cooldown period")]
+    InvalidCooldown,
+
+    #[msg("Pool is not active")]
+    PoolNotActive,
+
+    #[msg("Amount below minimum stake requirement")]
+    BelowMinStake,
+
+    #[msg("Stake is still locked")]
+    StillLocked,
+
+    #[msg("No staked amount")]
+    NoStakedAmount,
+
+    #[msg("Invalid staking status for this operation")]
+    InvalidStakingStatus,
+
+    #[msg("Unstake not requested")]
+    UnstakeNotRequested,
+
+    #[msg("Cooldown period not complete")]
+    CooldownNotComplete,
+
+    #[msg("No rewards to claim")]
+    NoRewardsToClaim,
+
+    #[msg("Unauthorized")]
+    Unauthorized,
+
+    #[msg("Invalid amount")]
+    InvalidAmount,
+
+    #[msg("Reward vault cannot cover this claim")]
+    RewardsExhausted,
+
+    #[msg("Amount exceeds unallocated rewards")]
+    InsufficientUnallocatedRewards,
+
+    #[msg("Instruction not supported in this pool mode")]
+    InvalidPoolMode,
+
+    #[msg("Pool must be empty")]
+    PoolNotEmpty,
+
+    #[msg("Pool already has the maximum number of reward streams")]
+    TooManyRewardStreams,
+
+    #[msg("Invalid emission rate")]
+    InvalidEmissionRate,
+
+    #[msg("Reward stream must end after it starts")]
+    InvalidStreamSchedule,
+
+    #[msg("Missing or invalid reward stream accounts")]
+    InvalidRewardStream,
+
+    #[msg("Invalid reward config")]
+    InvalidRewardConfig,
+
+    #[msg("Invalid stake limits")]
+    InvalidStakeLimits,
+
+    #[msg("Stake would exceed the pool's capacity")]
+    PoolCapExceeded,
+
+    #[msg("Stake would exceed the per-user cap")]
+    UserCapExceeded,
+
+    #[msg("Wallet is not on the pool's allow-list")]
+    NotAllowlisted,
+
+    #[msg("Invalid emission schedule")]
+    InvalidEmissionSchedule,
+
+    #[msg("Boost multiplier must be between 1x and 5x")]
+    InvalidBoostMultiplier,
+
+    #[msg("Pool has no boost collection")]
+    BoostNotEnabled,
+
+    #[msg("Position already has a boost NFT")]
+    BoostAlreadyLocked,
+
+    #[msg("NFT is not a verified member of the boost collection")]
+    InvalidBoostNft,
+
+    #[msg("Boost NFT accounts are required to return the locked NFT")]
+    MissingBoostAccounts,
+
+    #[msg("Validator set must be 1 to 8 distinct vote accounts")]
+    InvalidValidatorSet,
+
+    #[msg("Vote account is not the pool's validator for this stake")]
+    InvalidValidator,
+
+    #[msg("Native stake positions cannot be topped up")]
+    PositionAlreadyOpen,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::tests::pool;
+
+    fn wallet(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    #[test]
+    fn allowlist_pairs_are_order_independent() {
+        let (a, b) = (allowlist_leaf(&wallet(1)), allowlist_leaf(&wallet(2)));
+        assert_eq!(allowlist_node(&a, &b), allowlist_node(&b, &a));
+        assert_ne!(allowlist_node(&a, &b), allowlist_leaf(&wallet(1)));
+    }
+
+    #[test]
+    fn allowlist_accepts_members_and_rejects_everyone_else() {
+        // Three wallets: the odd leaf is carried up to the root unchanged
+        let [a, b, c] = [1, 2, 3].map(|seed| allowlist_leaf(&wallet(seed)));
+        let ab = allowlist_node(&a, &b);
+        let mut pool = pool(0, 1);
+        pool.allowlist_root = Some(allowlist_node(&ab, &c));
+
+        assert!(verify_allowlist(&pool, &wallet(1), &[b, c]).is_ok());
+        assert!(verify_allowlist(&pool, &wallet(2), &[a, c]).is_ok());
+        assert!(verify_allowlist(&pool, &wallet(3), &[ab]).is_ok());
+
+        assert!(verify_allowlist(&pool, &wallet(4), &[b, c]).is_err());
+        assert!(verify_allowlist(&pool, &wallet(1), &[c]).is_err());
+        assert!(verify_allowlist(&pool, &wallet(1), &[]).is_err());
+    }
+
+    #[test]
+    fn pools_without_an_allowlist_accept_anyone() {
+        assert!(verify_allowlist(&pool(0, 1), &wallet(4), &[]).is_ok());
+    }
+
+    fn metadata(collection: Option<(bool, Pubkey)>) -> Vec<u8> {
+        MetadataPrefix {
+            key: METADATA_V1_KEY,
+            update_authority: wallet(1),
+            mint: wallet(2),
+            name: "Boost #1".to_string(),
+            symbol: "BOOST".to_string(),
+            uri: "https://example.com/1.json".to_string(),
+            seller_fee_basis_points: 500,
+            creators: Some(vec![MetadataCreator {
+                address: wallet(1),
+                verified: true,
+                share: 100,
+            }]),
+            primary_sale_happened: false,
+            is_mutable: true,
+            edition_nonce: Some(255),
+            token_standard: Some(0),
+            collection: collection.map(|(verified, key)| MetadataCollection { verified, key }),
+        }
+        .try_to_vec()
+        .unwrap()
+    }
+
+    #[test]
+    fn reads_the_verified_collection_from_metadata() {
+        let mut data = metadata(Some((true, wallet(9))));
+        // Uses, collection details and the rest follow the collection
+        data.extend_from_slice(&[0; 64]);
+        assert_eq!(verified_collection(&data), Some(wallet(9)));
+    }
+
+    #[test]
+    fn rejects_unverified_missing_or_foreign_collections() {
+        assert_eq!(verified_collection(&metadata(Some((false, wallet(9))))), None);
+        assert_eq!(verified_collection(&metadata(None)), None);
+
+        let mut edition = metadata(Some((true, wallet(9))));
+        edition[0] = METADATA_V1_KEY + 2;
+        assert_eq!(verified_collection(&edition), None);
+        assert_eq!(verified_collection(&[METADATA_V1_KEY; 10]), None);
+    }
 }
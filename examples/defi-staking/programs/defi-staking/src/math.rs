@@ -0,0 +1,868 @@
+//! Reward, emission and statistics math shared by the staking instructions
+//!
+//! Everything here is pure so the same results can be reproduced off-chain.
+
+use crate::generated::*;
+
+// ===== BASE REWARDS =====
+
+/// Denominator of the reward formula: rate is in percent per year
+pub const REWARD_DENOMINATOR: u128 = 100 * 365 * 86400;
+
+/// Fixed-point scale for per-share reward accumulators
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// Maximum number of epochs in an `EmissionSchedule::Epochs` schedule
+pub const MAX_EMISSION_EPOCHS: usize = 8;
+
+/// Maximum number of concurrent reward streams per pool
+pub const MAX_REWARD_STREAMS: u8 = 4;
+
+/// Halvings after which a halving schedule stops emitting
+const MAX_HALVINGS: i64 = 64;
+
+/// Reward rate in effect at `t` and when it next changes (`None` if never)
+pub fn rate_segment(pool: &StakingPool, t: i64) -> (u64, Option<i64>) {
+    // Native positions earn epoch rewards from the Stake program instead
+    if matches!(pool.mode, PoolMode::NativeStake) {
+        return (0, None);
+    }
+
+    match pool.emission_schedule {
+        EmissionSchedule::Fixed => (pool.reward_rate, None),
+        EmissionSchedule::Epochs => {
+            // Epochs are sorted and never overlap; gaps between them emit nothing
+            match pool.emission_epochs.iter().find(|epoch| t < epoch.end_time) {
+                Some(epoch) if t >= epoch.start_time => (epoch.reward_rate, Some(epoch.end_time)),
+                Some(epoch) => (0, Some(epoch.start_time)),
+                None => (0, None),
+            }
+        }
+        EmissionSchedule::Halving {
+            start_time,
+            interval,
+            initial_rate,
+        } => {
+            if t < start_time {
+                return (0, Some(start_time));
+            }
+            let halvings = (t - start_time) / interval;
+            if halvings >= MAX_HALVINGS || initial_rate >> halvings == 0 {
+                return (0, None);
+            }
+            let next = start_time.saturating_add((halvings + 1).saturating_mul(interval));
+            (initial_rate >> halvings, Some(next))
+        }
+    }
+}
+
+/// Highest reward rate the pool's schedule pays at or after `now`
+pub fn peak_rate(pool: &StakingPool, now: i64) -> u64 {
+    match pool.emission_schedule {
+        EmissionSchedule::Fixed => pool.reward_rate,
+        EmissionSchedule::Epochs => pool
+            .emission_epochs
+            .iter()
+            .filter(|epoch| epoch.end_time > now)
+            .map(|epoch| epoch.reward_rate)
+            .max()
+            .unwrap_or(0),
+        EmissionSchedule::Halving { start_time, .. } => rate_segment(pool, now.max(start_time)).0,
+    }
+}
+
+/// Integral of the pool's reward rate over `[from, to)`, in rate-seconds
+///
+/// Walks the schedule one constant-rate segment at a time, so accrual is exact
+/// across epoch and halving boundaries.
+pub fn emission_between(pool: &StakingPool, from: i64, to: i64) -> u128 {
+    let mut emission = 0u128;
+    let mut t = from;
+
+    while t < to {
+        let (rate, next) = rate_segment(pool, t);
+        let end = next.map_or(to, |next| next.min(to));
+        emission += rate as u128 * (end - t) as u128;
+        t = end;
+    }
+
+    emission
+}
+
+/// Reward for `amount` staked over `emission` rate-seconds:
+/// (amount * rate * time) / (100 * 365 * 86400), rate in percent per year (e.g., 10 = 10%)
+pub fn calculate_reward(amount: u64, emission: u128) -> u64 {
+    (amount as u128 * emission / REWARD_DENOMINATOR).min(u64::MAX as u128) as u64
+}
+
+/// Stake a position earns base rewards on: its amount, times the boost
+/// multiplier of a locked NFT if it has one
+pub fn reward_weight(stake_account: &StakeAccount) -> u64 {
+    if stake_account.boost_mint.is_none() {
+        return stake_account.amount;
+    }
+    (stake_account.amount as u128 * stake_account.boost_multiplier as u128 / BASIS_POINTS as u128)
+        .min(u64::MAX as u128) as u64
+}
+
+/// Extra reward weight a position gets from its NFT boost
+pub fn boost_bonus(stake_account: &StakeAccount) -> u64 {
+    reward_weight(stake_account) - stake_account.amount
+}
+
+/// Total stake the pool pays base rewards on, including NFT boosts
+pub fn total_reward_weight(pool: &StakingPool) -> u64 {
+    pool.total_staked.saturating_add(pool.total_boost_weight)
+}
+
+/// Reward per staked lamport, scaled by `REWARD_PRECISION`, accrued from
+/// the pool's last accrual to `now`
+pub fn reward_per_share_since_accrual(pool: &StakingPool, now: i64) -> u128 {
+    emission_between(pool, pool.last_accrual_at, now) * REWARD_PRECISION / REWARD_DENOMINATOR
+}
+
+/// Bring the pool's reward accumulator and outstanding liabilities up to `now`
+pub fn accrue_pool_rewards(pool: &mut StakingPool, now: i64) {
+    if now <= pool.last_accrual_at {
+        return;
+    }
+    let delta = reward_per_share_since_accrual(pool, now);
+    let accrued = total_reward_weight(pool) as u128 * delta / REWARD_PRECISION;
+
+    pool.acc_reward_per_share += delta;
+    pool.rewards_owed = pool.rewards_owed.saturating_add(accrued.min(u64::MAX as u128) as u64);
+    pool.last_accrual_at = now;
+}
+
+/// Credit a position with what it earned since its last checkpoint; the pool
+/// must already be accrued
+pub fn settle_position_rewards(pool: &StakingPool, stake_account: &mut StakeAccount) {
+    let earned = reward_weight(stake_account) as u128
+        * (pool.acc_reward_per_share - stake_account.reward_per_share_paid)
+        / REWARD_PRECISION;
+    stake_account.pending_rewards = stake_account.pending_rewards.saturating_add(earned as u64);
+    stake_account.reward_per_share_paid = pool.acc_reward_per_share;
+}
+
+/// Rewards a position could claim at `now`, without mutating anything
+pub fn pending_rewards(pool: &StakingPool, stake_account: &StakeAccount, now: i64) -> u64 {
+    let acc = pool.acc_reward_per_share + reward_per_share_since_accrual(pool, now.max(pool.last_accrual_at));
+    let earned = reward_weight(stake_account) as u128 * (acc - stake_account.reward_per_share_paid)
+        / REWARD_PRECISION;
+    stake_account.pending_rewards.saturating_add(earned as u64)
+}
+
+/// Drop a closing position's unclaimed rewards from the pool's liabilities;
+/// the pool must already be accrued
+pub fn release_forfeited_rewards(pool: &mut StakingPool, stake_account: &mut StakeAccount) {
+    settle_position_rewards(pool, stake_account);
+    pool.rewards_owed = pool.rewards_owed.saturating_sub(stake_account.pending_rewards);
+    stake_account.pending_rewards = 0;
+}
+
+/// Accrue rewards for receipt holders, never promising more than the vault holds
+pub fn accrue_liquid_rewards(pool: &mut StakingPool, vault_balance: u64, now: i64) {
+    accrue_pool_rewards(pool, now);
+    pool.rewards_owed = pool.rewards_owed.min(vault_balance);
+}
+
+/// Receipt tokens minted for a deposit at the pool's current exchange rate
+pub fn receipts_for_deposit(pool: &StakingPool, receipt_supply: u64, amount: u64) -> u64 {
+    let underlying = pool.total_staked as u128 + pool.rewards_owed as u128;
+    if receipt_supply == 0 || underlying == 0 {
+        return amount;
+    }
+    (amount as u128 * receipt_supply as u128 / underlying) as u64
+}
+
+/// Principal and reward lamports backing `receipts` out of `receipt_supply`
+pub fn redemption_value(pool: &StakingPool, receipt_supply: u64, receipts: u64) -> (u64, u64) {
+    if receipt_supply == 0 {
+        return (0, 0);
+    }
+    let principal = receipts as u128 * pool.total_staked as u128 / receipt_supply as u128;
+    let reward = receipts as u128 * pool.rewards_owed as u128 / receipt_supply as u128;
+    (principal as u64, reward as u64)
+}
+
+/// Seconds until the vault can no longer cover newly accruing rewards, or
+/// `None` if the current stake will never exhaust it
+pub fn solvency_runway(pool: &StakingPool, vault_balance: u64, now: i64) -> Option<i64> {
+    let weight = total_reward_weight(pool);
+    if weight == 0 {
+        return None;
+    }
+
+    let from = now.max(pool.last_accrual_at);
+    let owed = pool.rewards_owed.saturating_add(calculate_reward(
+        weight,
+        emission_between(pool, pool.last_accrual_at, from),
+    ));
+    // Budget in rate-seconds for the current total stake
+    let mut budget =
+        vault_balance.saturating_sub(owed) as u128 * REWARD_DENOMINATOR / weight as u128;
+
+    let mut t = from;
+    loop {
+        let (rate, next) = rate_segment(pool, t);
+        match next {
+            Some(end) if (rate as u128 * (end - t) as u128) <= budget => {
+                budget -= rate as u128 * (end - t) as u128;
+                t = end;
+            }
+            _ if rate == 0 => return None,
+            _ => {
+                let exhausted_at = t as u128 + budget / rate as u128;
+                return Some((exhausted_at - now as u128).min(i64::MAX as u128) as i64);
+            }
+        }
+    }
+}
+
+// ===== REWARD STREAMS =====
+
+/// Accumulate a stream's emissions per staked token up to `now`
+///
+/// Nobody earns what the stream emits while the pool has no stake; those
+/// tokens stay in the stream vault and are counted in `total_unallocated`.
+pub fn accrue_reward_stream(stream: &mut RewardStream, total_staked: u64, now: i64) {
+    let from = stream.last_update_at.max(stream.start_time);
+    let to = now.min(stream.end_time);
+
+    if to > from {
+        let emitted = stream.emission_rate as u128 * (to - from) as u128;
+        if total_staked > 0 {
+            stream.acc_reward_per_share += emitted * REWARD_PRECISION / total_staked as u128;
+        } else {
+            stream.total_unallocated = stream
+                .total_unallocated
+                .saturating_add(emitted.min(u64::MAX as u128) as u64);
+        }
+    }
+    stream.last_update_at = stream.last_update_at.max(now);
+}
+
+/// Credit a position with what a stream has accrued since its last checkpoint
+pub fn settle_stream_position(stream: &RewardStream, stake_account: &mut StakeAccount) {
+    let index = stream.index as usize;
+    while stake_account.stream_checkpoints.len() <= index {
+        stake_account.stream_checkpoints.push(StreamCheckpoint {
+            reward_per_share_paid: 0,
+            pending: 0,
+        });
+    }
+
+    let checkpoint = &mut stake_account.stream_checkpoints[index];
+    let earned = stake_account.amount as u128
+        * (stream.acc_reward_per_share - checkpoint.reward_per_share_paid)
+        / REWARD_PRECISION;
+    checkpoint.pending += earned as u64;
+    checkpoint.reward_per_share_paid = stream.acc_reward_per_share;
+}
+
+/// Drop a closing position's unclaimed stream rewards
+pub fn forfeit_stream_rewards(stake_account: &mut StakeAccount) {
+    for checkpoint in stake_account.stream_checkpoints.iter_mut() {
+        checkpoint.pending = 0;
+    }
+}
+
+// ===== NATIVE STAKE =====
+
+/// Validator the next native deposit is delegated to: the one with the least
+/// stake, the earliest in the list on ties
+pub fn next_validator(validator_list: &ValidatorList) -> Option<usize> {
+    validator_list
+        .validators
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, validator)| validator.total_delegated)
+        .map(|(index, _)| index)
+}
+
+/// Epoch rewards compounded into a native position's stake account: its
+/// balance beyond the principal and the rent-exempt reserve
+pub fn native_stake_rewards(stake_lamports: u64, rent_exempt_reserve: u64, principal: u64) -> u64 {
+    stake_lamports
+        .saturating_sub(rent_exempt_reserve)
+        .saturating_sub(principal)
+}
+
+// ===== POOL STATISTICS =====
+
+/// Basis points in 100%
+pub const BASIS_POINTS: u64 = 10_000;
+
+/// Seconds per year, for the reward formula and the advertised APY
+pub const SECONDS_PER_YEAR: u64 = 365 * 86400;
+
+/// Fixed-point scale for compounding
+pub const APY_PRECISION: u128 = 1_000_000_000_000_000_000;
+
+/// Add the stake held since the last update to the pool's stake-seconds
+pub fn accrue_stake_seconds(stats: &mut PoolStats, total_staked: u64, now: i64) {
+    let elapsed = now.saturating_sub(stats.last_updated).max(0);
+    stats.stake_seconds += total_staked as u128 * elapsed as u128;
+    stats.last_updated = stats.last_updated.max(now);
+}
+
+/// Recompute the derived figures after the pool changed
+///
+/// `stake_seconds / total_deposited` is the amount-weighted mean time a
+/// deposited token stays staked (tokens still staked count up to now), so
+/// top-ups and partial histories are weighted correctly.
+pub fn refresh_pool_stats(stats: &mut PoolStats, pool: &StakingPool, config: &RewardConfig, now: i64) {
+    if stats.total_deposited > 0 {
+        stats.average_stake_duration =
+            (stats.stake_seconds / stats.total_deposited as u128).min(i64::MAX as u128) as i64;
+    }
+    stats.current_apy = current_apy(config, pool, now);
+}
+
+/// Annual rate in basis points that a reward rate pays
+pub fn rate_to_apy(rate: u64) -> u64 {
+    (rate as u128 * BASIS_POINTS as u128 * SECONDS_PER_YEAR as u128 / REWARD_DENOMINATOR)
+        .min(u64::MAX as u128) as u64
+}
+
+/// Keep a `FixedAPY` reward config in step with the pool's emission schedule
+/// after the reward rate or schedule changed
+///
+/// Every staked lamport earns the same scheduled rate, so a fixed quote is
+/// that rate: `base_apy` is the rate in effect at `now` and `max_apy` the
+/// highest APY the schedule will still pay, both in basis points. Tiered and
+/// dynamic configs are figures the authority advertises and are left alone.
+pub fn sync_reward_config(config: &mut RewardConfig, pool: &StakingPool, now: i64) {
+    if matches!(config.calculation_type, RewardCalculationType::FixedAPY) {
+        config.base_apy = rate_to_apy(rate_segment(pool, now).0);
+        config.max_apy = compound_apy(rate_to_apy(peak_rate(pool, now)), config.compound_frequency);
+        config.bonus_multiplier = BASIS_POINTS;
+    }
+}
+
+/// Pool-wide APY in basis points implied by the reward config
+pub fn current_apy(config: &RewardConfig, pool: &StakingPool, now: i64) -> u64 {
+    // Native positions earn epoch rewards from the Stake program instead
+    if matches!(pool.mode, PoolMode::NativeStake) {
+        return 0;
+    }
+
+    let rate = match config.calculation_type {
+        RewardCalculationType::FixedAPY => rate_to_apy(rate_segment(pool, now).0),
+        // Tiers apply per position, so quote the tier of the average position
+        RewardCalculationType::TieredAPY {
+            tier1_amount,
+            tier1_apy,
+            tier2_amount,
+            tier2_apy,
+            tier3_apy,
+        } => {
+            let average_stake = pool.total_staked.checked_div(pool.total_stakers).unwrap_or(0);
+            if average_stake < tier1_amount {
+                tier1_apy
+            } else if average_stake < tier2_amount {
+                tier2_apy
+            } else {
+                tier3_apy
+            }
+        }
+        // Falls linearly from max_apy on an empty pool to min_apy at the threshold
+        RewardCalculationType::DynamicAPY {
+            min_apy,
+            max_apy,
+            utilization_threshold,
+        } => {
+            let utilization = pool.total_staked.min(utilization_threshold) as u128;
+            let spread = (max_apy - min_apy) as u128;
+            max_apy - (spread * utilization / utilization_threshold as u128) as u64
+        }
+    };
+
+    // Every position in a locked pool earns the lock bonus
+    let rate = if pool.min_lock_duration > 0 {
+        (rate as u128 * config.bonus_multiplier as u128 / BASIS_POINTS as u128)
+            .min(u64::MAX as u128) as u64
+    } else {
+        rate
+    };
+
+    compound_apy(rate, config.compound_frequency).min(config.max_apy)
+}
+
+/// Convert a simple annual rate to its effective yield when compounded every
+/// `compound_frequency` seconds: (1 + r/n)^n - 1
+pub fn compound_apy(rate: u64, compound_frequency: i64) -> u64 {
+    let periods = match SECONDS_PER_YEAR.checked_div(compound_frequency.max(0) as u64) {
+        Some(periods) if periods > 1 => periods,
+        _ => return rate,
+    };
+
+    let period_growth =
+        APY_PRECISION + rate as u128 * APY_PRECISION / BASIS_POINTS as u128 / periods as u128;
+
+    // Exponentiation by squaring in fixed point; saturates on overflow
+    let mut result = APY_PRECISION;
+    let mut base = period_growth;
+    let mut exponent = periods;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = match result.checked_mul(base) {
+                Some(product) => product / APY_PRECISION,
+                None => return u64::MAX,
+            };
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = match base.checked_mul(base) {
+                Some(product) => product / APY_PRECISION,
+                None => return u64::MAX,
+            };
+        }
+    }
+
+    ((result - APY_PRECISION) * BASIS_POINTS as u128 / APY_PRECISION).min(u64::MAX as u128) as u64
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+
+    /// Stake that earns exactly one lamport per second at a reward rate of 1
+    const ONE_PER_SECOND: u64 = REWARD_DENOMINATOR as u64;
+
+    pub(crate) fn pool(total_staked: u64, reward_rate: u64) -> StakingPool {
+        StakingPool {
+            authority: Pubkey::default(),
+            token_mint: Pubkey::default(),
+            vault: Pubkey::default(),
+            reward_vault: Pubkey::default(),
+            mode: PoolMode::Standard,
+            receipt_mint: None,
+            total_staked,
+            total_stakers: 1,
+            reward_rate,
+            emission_schedule: EmissionSchedule::Fixed,
+            acc_reward_per_share: 0,
+            rewards_owed: 0,
+            last_accrual_at: 0,
+            reward_stream_count: 0,
+            min_stake_amount: 1,
+            max_total_staked: None,
+            max_per_user: None,
+            allowlist_root: None,
+            boost_collection: None,
+            boost_multiplier: BASIS_POINTS,
+            total_boost_weight: 0,
+            min_lock_duration: 0,
+            cooldown_period: 0,
+            is_active: true,
+            created_at: 0,
+            emission_epochs: Vec::new(),
+        }
+    }
+
+    fn position(amount: u64) -> StakeAccount {
+        StakeAccount {
+            owner: Pubkey::default(),
+            pool: Pubkey::default(),
+            amount,
+            staked_at: 0,
+            last_claim_at: 0,
+            unlock_at: 0,
+            total_claimed: 0,
+            reward_per_share_paid: 0,
+            pending_rewards: 0,
+            boost_mint: None,
+            boost_multiplier: BASIS_POINTS,
+            status: StakingStatus::Active,
+            unstake_requested_at: None,
+            stream_checkpoints: Vec::new(),
+        }
+    }
+
+    fn stream(index: u8, emission_rate: u64, start_time: i64, end_time: i64) -> RewardStream {
+        RewardStream {
+            pool: Pubkey::default(),
+            index,
+            reward_mint: Pubkey::default(),
+            reward_vault: Pubkey::default(),
+            emission_rate,
+            start_time,
+            end_time,
+            acc_reward_per_share: 0,
+            last_update_at: 0,
+            total_funded: 0,
+            total_distributed: 0,
+            total_unallocated: 0,
+        }
+    }
+
+    fn epoch(start_time: i64, end_time: i64, reward_rate: u64) -> EmissionEpoch {
+        EmissionEpoch {
+            start_time,
+            end_time,
+            reward_rate,
+        }
+    }
+
+    fn config(calculation_type: RewardCalculationType) -> RewardConfig {
+        RewardConfig {
+            pool: Pubkey::default(),
+            calculation_type,
+            base_apy: 0,
+            bonus_multiplier: BASIS_POINTS,
+            max_apy: 0,
+            compound_frequency: 0,
+        }
+    }
+
+    #[test]
+    fn runway_is_unbounded_without_stake_or_emissions() {
+        assert_eq!(solvency_runway(&pool(0, 1), 1_000, 0), None);
+
+        let mut ended = pool(ONE_PER_SECOND, 1);
+        ended.emission_schedule = EmissionSchedule::Epochs;
+        ended.emission_epochs = vec![epoch(0, 100, 1)];
+        assert_eq!(solvency_runway(&ended, 1_000, 0), None);
+    }
+
+    #[test]
+    fn runway_only_counts_unallocated_rewards() {
+        let mut pool = pool(ONE_PER_SECOND, 1);
+        assert_eq!(solvency_runway(&pool, 1_000, 0), Some(1_000));
+
+        // Accrued liabilities are already spoken for
+        pool.rewards_owed = 400;
+        assert_eq!(solvency_runway(&pool, 1_000, 0), Some(600));
+
+        // So is whatever accrued since the last accrual, at t = 0
+        assert_eq!(solvency_runway(&pool, 1_000, 100), Some(500));
+
+        // A vault that can't cover its liabilities has no runway left
+        assert_eq!(solvency_runway(&pool, 300, 0), Some(0));
+    }
+
+    #[test]
+    fn runway_follows_the_emission_schedule() {
+        // 100 lamports cover the first epoch; the remaining 50 last 25s at twice the rate
+        let mut epochs = pool(ONE_PER_SECOND, 1);
+        epochs.emission_schedule = EmissionSchedule::Epochs;
+        epochs.emission_epochs = vec![epoch(0, 100, 1), epoch(200, 300, 2)];
+        assert_eq!(solvency_runway(&epochs, 150, 0), Some(225));
+
+        // 4/s for 10s, then 2/s: 60 lamports last 10s + 10s
+        let mut halving = pool(ONE_PER_SECOND, 0);
+        halving.emission_schedule = EmissionSchedule::Halving {
+            start_time: 0,
+            interval: 10,
+            initial_rate: 4,
+        };
+        assert_eq!(solvency_runway(&halving, 60, 0), Some(20));
+    }
+
+    #[test]
+    fn runway_counts_boosted_weight() {
+        let mut pool = pool(ONE_PER_SECOND, 1);
+        pool.total_boost_weight = ONE_PER_SECOND;
+        assert_eq!(solvency_runway(&pool, 1_000, 0), Some(500));
+    }
+
+    #[test]
+    fn first_liquid_deposit_mints_one_to_one() {
+        assert_eq!(receipts_for_deposit(&pool(0, 1), 0, 500), 500);
+        assert_eq!(redemption_value(&pool(0, 1), 0, 500), (0, 0));
+    }
+
+    #[test]
+    fn receipts_are_priced_at_the_exchange_rate() {
+        // 1000 receipts back 1000 principal + 500 rewards: 1.5 lamports each
+        let mut pool = pool(1_000, 1);
+        pool.rewards_owed = 500;
+
+        assert_eq!(receipts_for_deposit(&pool, 1_000, 300), 200);
+        assert_eq!(redemption_value(&pool, 1_000, 200), (200, 100));
+
+        // Rounding always favours the pool
+        assert_eq!(receipts_for_deposit(&pool, 1_000, 2), 1);
+        let (principal, reward) = redemption_value(&pool, 1_000, 1);
+        assert!(principal + reward <= 2);
+    }
+
+    #[test]
+    fn liquid_rewards_never_exceed_the_vault() {
+        let mut pool = pool(ONE_PER_SECOND, 1);
+        accrue_liquid_rewards(&mut pool, 60, 100);
+        assert_eq!(pool.rewards_owed, 60);
+        assert_eq!(pool.last_accrual_at, 100);
+
+        // Ten more seconds, less what the per-share accumulator rounds away
+        accrue_liquid_rewards(&mut pool, 1_000, 110);
+        assert!((69..=70).contains(&pool.rewards_owed));
+    }
+
+    #[test]
+    fn streams_split_emissions_by_stake() {
+        let mut stream = stream(0, 10, 0, 100);
+        accrue_reward_stream(&mut stream, 100, 10);
+
+        let (mut small, mut large) = (position(40), position(60));
+        settle_stream_position(&stream, &mut small);
+        settle_stream_position(&stream, &mut large);
+        assert_eq!(small.stream_checkpoints[0].pending, 40);
+        assert_eq!(large.stream_checkpoints[0].pending, 60);
+
+        // Settling again without new emissions pays nothing twice
+        settle_stream_position(&stream, &mut small);
+        assert_eq!(small.stream_checkpoints[0].pending, 40);
+    }
+
+    #[test]
+    fn streams_only_emit_between_start_and_end() {
+        let mut stream = stream(0, 10, 50, 100);
+        accrue_reward_stream(&mut stream, 10, 40);
+        assert_eq!(stream.acc_reward_per_share, 0);
+
+        accrue_reward_stream(&mut stream, 10, 200);
+        assert_eq!(stream.acc_reward_per_share, 500 * REWARD_PRECISION / 10);
+        assert_eq!(stream.last_update_at, 200);
+    }
+
+    #[test]
+    fn stream_emissions_without_stake_are_unallocated() {
+        let mut stream = stream(0, 10, 0, 100);
+        accrue_reward_stream(&mut stream, 0, 30);
+        assert_eq!(stream.acc_reward_per_share, 0);
+        assert_eq!(stream.total_unallocated, 300);
+
+        // The first staker only earns from when they staked
+        let mut staker = position(10);
+        settle_stream_position(&stream, &mut staker);
+        accrue_reward_stream(&mut stream, 10, 40);
+        settle_stream_position(&stream, &mut staker);
+        assert_eq!(staker.stream_checkpoints[0].pending, 100);
+        assert_eq!(stream.total_unallocated, 300);
+    }
+
+    #[test]
+    fn stream_checkpoints_grow_to_the_stream_index() {
+        let mut stream = stream(2, 10, 0, 100);
+        accrue_reward_stream(&mut stream, 10, 10);
+
+        let mut staker = position(10);
+        settle_stream_position(&stream, &mut staker);
+        assert_eq!(staker.stream_checkpoints.len(), 3);
+        assert_eq!(staker.stream_checkpoints[2].pending, 100);
+
+        forfeit_stream_rewards(&mut staker);
+        assert_eq!(staker.stream_checkpoints[2].pending, 0);
+    }
+
+    #[test]
+    fn apy_follows_the_emission_schedule() {
+        let mut pool = pool(ONE_PER_SECOND, 10);
+        let mut config = config(RewardCalculationType::FixedAPY);
+        sync_reward_config(&mut config, &pool, 0);
+        assert_eq!(current_apy(&config, &pool, 0), 1_000);
+
+        // The quote is the rate that pays out: 10% for a year is a tenth of the stake
+        let year = SECONDS_PER_YEAR as i64;
+        assert_eq!(calculate_reward(1_000_000, emission_between(&pool, 0, year)), 100_000);
+
+        pool.emission_schedule = EmissionSchedule::Halving {
+            start_time: 0,
+            interval: 100,
+            initial_rate: 20,
+        };
+        sync_reward_config(&mut config, &pool, 150);
+        assert_eq!((config.base_apy, config.max_apy), (1_000, 1_000));
+        assert_eq!(current_apy(&config, &pool, 250), 500);
+
+        pool.emission_schedule = EmissionSchedule::Epochs;
+        pool.emission_epochs = vec![epoch(0, 100, 5), epoch(200, 300, 30)];
+        sync_reward_config(&mut config, &pool, 50);
+        assert_eq!((config.base_apy, config.max_apy), (500, 3_000));
+
+        // Daily compounding of 10% is about 10.52%
+        config.compound_frequency = 86400;
+        sync_reward_config(&mut config, &pool, 50);
+        assert_eq!(current_apy(&config, &pool, 250), compound_apy(3_000, 86400));
+        assert_eq!(compound_apy(1_000, 86400), 1_051);
+    }
+
+    #[test]
+    fn tiered_and_dynamic_quotes_are_left_to_the_authority() {
+        let mut pool = pool(300, 10);
+        pool.total_stakers = 2;
+
+        let mut tiered = config(RewardCalculationType::TieredAPY {
+            tier1_amount: 100,
+            tier1_apy: 500,
+            tier2_amount: 1_000,
+            tier2_apy: 800,
+            tier3_apy: 1_200,
+        });
+        tiered.max_apy = 1_000;
+        sync_reward_config(&mut tiered, &pool, 0);
+        assert_eq!(tiered.max_apy, 1_000);
+        // The average position of 150 is in the second tier
+        assert_eq!(current_apy(&tiered, &pool, 0), 800);
+
+        let mut dynamic = config(RewardCalculationType::DynamicAPY {
+            min_apy: 500,
+            max_apy: 2_000,
+            utilization_threshold: 1_000,
+        });
+        dynamic.max_apy = 2_000;
+        // 30% utilization is 30% of the way from max_apy down to min_apy
+        assert_eq!(current_apy(&dynamic, &pool, 0), 1_550);
+
+        // Locked pools quote the lock bonus, up to max_apy
+        pool.min_lock_duration = 86400;
+        dynamic.bonus_multiplier = 12_000;
+        assert_eq!(current_apy(&dynamic, &pool, 0), 1_860);
+        dynamic.bonus_multiplier = 20_000;
+        assert_eq!(current_apy(&dynamic, &pool, 0), 2_000);
+
+        pool.mode = PoolMode::NativeStake;
+        assert_eq!(current_apy(&dynamic, &pool, 0), 0);
+    }
+
+    fn boosted(amount: u64, multiplier: u64) -> StakeAccount {
+        let mut position = position(amount);
+        position.boost_mint = Some(Pubkey::new_unique());
+        position.boost_multiplier = multiplier;
+        position
+    }
+
+    #[test]
+    fn boosts_scale_reward_weight() {
+        assert_eq!(reward_weight(&position(1_000)), 1_000);
+        assert_eq!(boost_bonus(&position(1_000)), 0);
+
+        assert_eq!(reward_weight(&boosted(1_000, 15_000)), 1_500);
+        assert_eq!(boost_bonus(&boosted(1_000, 15_000)), 500);
+
+        // The multiplier only counts while an NFT is locked
+        let mut returned = boosted(1_000, 15_000);
+        returned.boost_mint = None;
+        assert_eq!(reward_weight(&returned), 1_000);
+    }
+
+    #[test]
+    fn boosted_positions_earn_their_share_of_pool_rewards() {
+        let (mut plain, mut boost) = (position(100), boosted(100, 30_000));
+        let mut pool = pool(200, 100_000);
+        pool.total_boost_weight = boost_bonus(&boost);
+        assert_eq!(total_reward_weight(&pool), 400);
+
+        // Long enough at 100,000% a year for each unit of weight to earn exactly one lamport
+        let now = (REWARD_DENOMINATOR / 100_000) as i64;
+        accrue_pool_rewards(&mut pool, now);
+        assert_eq!(pool.rewards_owed, 400);
+        assert_eq!(pending_rewards(&pool, &boost, now), 300);
+
+        settle_position_rewards(&pool, &mut plain);
+        settle_position_rewards(&pool, &mut boost);
+        assert_eq!((plain.pending_rewards, boost.pending_rewards), (100, 300));
+        assert_eq!(solvency_runway(&pool, 400, now), Some(0));
+    }
+
+    fn halving(start_time: i64, interval: i64, initial_rate: u64) -> StakingPool {
+        let mut pool = pool(ONE_PER_SECOND, 0);
+        pool.emission_schedule = EmissionSchedule::Halving {
+            start_time,
+            interval,
+            initial_rate,
+        };
+        pool
+    }
+
+    #[test]
+    fn rate_segments_end_at_every_epoch_boundary() {
+        let mut pool = pool(ONE_PER_SECOND, 0);
+        pool.emission_schedule = EmissionSchedule::Epochs;
+        pool.emission_epochs = vec![epoch(10, 20, 5), epoch(30, 40, 7)];
+
+        assert_eq!(rate_segment(&pool, 0), (0, Some(10)));
+        assert_eq!(rate_segment(&pool, 10), (5, Some(20)));
+        assert_eq!(rate_segment(&pool, 19), (5, Some(20)));
+        assert_eq!(rate_segment(&pool, 20), (0, Some(30)));
+        assert_eq!(rate_segment(&pool, 35), (7, Some(40)));
+        assert_eq!(rate_segment(&pool, 40), (0, None));
+    }
+
+    #[test]
+    fn rate_segments_halve_each_interval_until_nothing_is_left() {
+        let pool = halving(100, 10, 8);
+
+        assert_eq!(rate_segment(&pool, 0), (0, Some(100)));
+        assert_eq!(rate_segment(&pool, 100), (8, Some(110)));
+        assert_eq!(rate_segment(&pool, 125), (2, Some(130)));
+        assert_eq!(rate_segment(&pool, 130), (1, Some(140)));
+        assert_eq!(rate_segment(&pool, 140), (0, None));
+        assert_eq!(rate_segment(&halving(0, 1, u64::MAX), 64), (0, None));
+    }
+
+    #[test]
+    fn emission_is_exact_across_boundaries() {
+        let mut epochs = pool(ONE_PER_SECOND, 0);
+        epochs.emission_schedule = EmissionSchedule::Epochs;
+        epochs.emission_epochs = vec![epoch(10, 20, 5), epoch(30, 40, 7)];
+        // 5 from 15 to 20, nothing until 30, 7 from 30 to 35
+        assert_eq!(emission_between(&epochs, 15, 35), 5 * 5 + 7 * 5);
+        assert_eq!(emission_between(&epochs, 0, 100), 5 * 10 + 7 * 10);
+
+        let halving = halving(100, 10, 8);
+        // 8 from 105 to 110, 4 from 110 to 120, 2 from 120 to 123
+        assert_eq!(emission_between(&halving, 105, 123), 8 * 5 + 4 * 10 + 2 * 3);
+        // Everything the schedule will ever emit
+        assert_eq!(emission_between(&halving, 0, 1_000), (8 + 4 + 2 + 1) * 10);
+
+        // Splitting an interval anywhere gives the same total
+        for split in [101, 110, 115, 130] {
+            assert_eq!(
+                emission_between(&halving, 100, split) + emission_between(&halving, split, 150),
+                emission_between(&halving, 100, 150)
+            );
+        }
+    }
+
+    #[test]
+    fn claims_spanning_a_halving_pay_both_rates() {
+        // Multiples of this rate accrue per share without rounding
+        const EXACT: u64 = 1_971;
+        let mut pool = halving(0, 100, 2 * EXACT);
+        let mut staker = position(ONE_PER_SECOND);
+
+        // Claimed once before the halving and once 50 seconds after it
+        accrue_pool_rewards(&mut pool, 60);
+        settle_position_rewards(&pool, &mut staker);
+        assert_eq!(staker.pending_rewards, 60 * 2 * EXACT);
+
+        staker.pending_rewards = 0;
+        let expected = (40 * 2 + 50) * EXACT;
+        assert_eq!(pending_rewards(&pool, &staker, 150), expected);
+        accrue_pool_rewards(&mut pool, 150);
+        settle_position_rewards(&pool, &mut staker);
+        assert_eq!(staker.pending_rewards, expected);
+        assert_eq!(pool.rewards_owed, 60 * 2 * EXACT + expected);
+
+        // One accrual over the whole interval owes the same
+        let mut once = halving(0, 100, 2 * EXACT);
+        accrue_pool_rewards(&mut once, 150);
+        assert_eq!(once.rewards_owed, pool.rewards_owed);
+    }
+
+    #[test]
+    fn native_pools_accrue_no_base_rewards() {
+        let mut pool = pool(ONE_PER_SECOND, 1_000);
+        pool.mode = PoolMode::NativeStake;
+        let staker = position(ONE_PER_SECOND);
+
+        accrue_pool_rewards(&mut pool, 1_000);
+        assert_eq!((pool.acc_reward_per_share, pool.rewards_owed), (0, 0));
+        assert_eq!(pending_rewards(&pool, &staker, 2_000), 0);
+        assert_eq!(solvency_runway(&pool, 0, 1_000), None);
+    }
+}
@@ -8,28 +8,173 @@ export interface StakingPool {
   authority: PublicKey;
   token_mint: PublicKey;
   vault: PublicKey;
+  reward_vault: PublicKey;
+  mode: PoolMode;
+  receipt_mint?: PublicKey | undefined;
   total_staked: number;
   total_stakers: number;
   reward_rate: number;
+  emission_schedule: EmissionSchedule;
+  acc_reward_per_share: number;
+  rewards_owed: number;
+  last_accrual_at: number;
+  reward_stream_count: number;
   min_stake_amount: number;
+  max_total_staked?: number | undefined;
+  max_per_user?: number | undefined;
+  allowlist_root?: number[] | undefined;
+  boost_collection?: PublicKey | undefined;
+  boost_multiplier: number;
+  total_boost_weight: number;
   min_lock_duration: number;
   cooldown_period: number;
   is_active: boolean;
   created_at: number;
+  emission_epochs: Vec;
 }
 
 export const StakingPoolSchema = borsh.struct([
   borsh.publicKey('authority'),
   borsh.publicKey('token_mint'),
   borsh.publicKey('vault'),
+  borsh.publicKey('reward_vault'),
+  borsh.poolmode('mode'),
+  borsh.option(borsh.publicKey)('receipt_mint'),
   borsh.u64('total_staked'),
   borsh.u64('total_stakers'),
   borsh.u64('reward_rate'),
+  borsh.emissionschedule('emission_schedule'),
+  borsh.u128('acc_reward_per_share'),
+  borsh.u64('rewards_owed'),
+  borsh.i64('last_accrual_at'),
+  borsh.u8('reward_stream_count'),
   borsh.u64('min_stake_amount'),
+  borsh.option(borsh.u64)('max_total_staked'),
+  borsh.option(borsh.u64)('max_per_user'),
+  borsh.option(borsh.array(borsh.u8, 32))('allowlist_root'),
+  borsh.option(borsh.publicKey)('boost_collection'),
+  borsh.u64('boost_multiplier'),
+  borsh.u64('total_boost_weight'),
   borsh.i64('min_lock_duration'),
   borsh.i64('cooldown_period'),
   borsh.bool('is_active'),
   borsh.i64('created_at'),
+  borsh.vec('emission_epochs'),
+]);
+
+
+export type PoolMode =
+  | { kind: 'Standard' }
+  | { kind: 'Liquid' }
+  | { kind: 'NativeStake' }
+;
+
+export const PoolModeSchema = borsh.rustEnum([
+  borsh.unit('Standard'),
+  borsh.unit('Liquid'),
+  borsh.unit('NativeStake'),
+]);
+
+
+export type EmissionSchedule =
+  | { kind: 'Fixed' }
+  | { kind: 'Epochs' }
+  | { kind: 'Halving'; start_time: number; interval: number; initial_rate: number }
+;
+
+export const EmissionScheduleSchema = borsh.rustEnum([
+  borsh.unit('Fixed'),
+  borsh.unit('Epochs'),
+  borsh.struct([
+    borsh.i64('start_time'),
+    borsh.i64('interval'),
+    borsh.u64('initial_rate'),
+  ], 'Halving'),
+]);
+
+
+export interface EmissionEpoch {
+  start_time: number;
+  end_time: number;
+  reward_rate: number;
+}
+
+export const EmissionEpochSchema = borsh.struct([
+  borsh.i64('start_time'),
+  borsh.i64('end_time'),
+  borsh.u64('reward_rate'),
+]);
+
+
+export interface ValidatorList {
+  pool: PublicKey;
+  validators: Vec;
+}
+
+export const ValidatorListSchema = borsh.struct([
+  borsh.publicKey('pool'),
+  borsh.vec('validators'),
+]);
+
+
+export interface ValidatorStake {
+  vote_account: PublicKey;
+  total_delegated: number;
+  total_rewards: number;
+}
+
+export const ValidatorStakeSchema = borsh.struct([
+  borsh.publicKey('vote_account'),
+  borsh.u64('total_delegated'),
+  borsh.u64('total_rewards'),
+]);
+
+
+export interface RewardVault {
+  pool: PublicKey;
+  balance: number;
+  total_funded: number;
+  total_distributed: number;
+  total_withdrawn: number;
+}
+
+export const RewardVaultSchema = borsh.struct([
+  borsh.publicKey('pool'),
+  borsh.u64('balance'),
+  borsh.u64('total_funded'),
+  borsh.u64('total_distributed'),
+  borsh.u64('total_withdrawn'),
+]);
+
+
+export interface RewardStream {
+  pool: PublicKey;
+  index: number;
+  reward_mint: PublicKey;
+  reward_vault: PublicKey;
+  emission_rate: number;
+  start_time: number;
+  end_time: number;
+  acc_reward_per_share: number;
+  last_update_at: number;
+  total_funded: number;
+  total_distributed: number;
+  total_unallocated: number;
+}
+
+export const RewardStreamSchema = borsh.struct([
+  borsh.publicKey('pool'),
+  borsh.u8('index'),
+  borsh.publicKey('reward_mint'),
+  borsh.publicKey('reward_vault'),
+  borsh.u64('emission_rate'),
+  borsh.i64('start_time'),
+  borsh.i64('end_time'),
+  borsh.u128('acc_reward_per_share'),
+  borsh.i64('last_update_at'),
+  borsh.u64('total_funded'),
+  borsh.u64('total_distributed'),
+  borsh.u64('total_unallocated'),
 ]);
 
 
@@ -41,8 +186,13 @@ export interface StakeAccount {
   last_claim_at: number;
   unlock_at: number;
   total_claimed: number;
+  reward_per_share_paid: number;
+  pending_rewards: number;
+  boost_mint?: PublicKey | undefined;
+  boost_multiplier: number;
   status: StakingStatus;
   unstake_requested_at?: number | undefined;
+  stream_checkpoints: Vec;
 }
 
 export const StakeAccountSchema = borsh.struct([
@@ -53,8 +203,24 @@ export const StakeAccountSchema = borsh.struct([
   borsh.i64('last_claim_at'),
   borsh.i64('unlock_at'),
   borsh.u64('total_claimed'),
+  borsh.u128('reward_per_share_paid'),
+  borsh.u64('pending_rewards'),
+  borsh.option(borsh.publicKey)('boost_mint'),
+  borsh.u64('boost_multiplier'),
   borsh.stakingstatus('status'),
   borsh.option(borsh.i64)('unstake_requested_at'),
+  borsh.vec('stream_checkpoints'),
+]);
+
+
+export interface StreamCheckpoint {
+  reward_per_share_paid: number;
+  pending: number;
+}
+
+export const StreamCheckpointSchema = borsh.struct([
+  borsh.u128('reward_per_share_paid'),
+  borsh.u64('pending'),
 ]);
 
 
@@ -184,6 +350,8 @@ export interface PoolStats {
   average_stake_duration: number;
   highest_stake: number;
   current_apy: number;
+  total_deposited: number;
+  stake_seconds: number;
   last_updated: number;
 }
 
@@ -193,5 +361,7 @@ export const PoolStatsSchema = borsh.struct([
   borsh.i64('average_stake_duration'),
   borsh.u64('highest_stake'),
   borsh.u64('current_apy'),
+  borsh.u64('total_deposited'),
+  borsh.u128('stake_seconds'),
   borsh.i64('last_updated'),
 ]);
@@ -0,0 +1,76 @@
+//! Reading account dumps written by the Solana CLI
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anchor_lang::AccountDeserialize;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DumpError {
+    #[error("cannot read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("{0}: expected the output of `solana account <address> --output json`")]
+    Format(PathBuf),
+
+    #[error("{path}: unsupported data encoding `{encoding}`, dump with base64")]
+    Encoding { path: PathBuf, encoding: String },
+
+    #[error("{0}: invalid base64 account data")]
+    Base64(PathBuf),
+
+    #[error("{path}: not a {account} account: {source}")]
+    Account {
+        path: PathBuf,
+        account: &'static str,
+        source: anchor_lang::error::Error,
+    },
+}
+
+/// Account data from either `solana account <address> --output json`
+/// (or a `getAccountInfo` result) or the raw bytes written by
+/// `solana account <address> --output-file <file>`
+pub fn read_account_data(path: &Path) -> Result<Vec<u8>, DumpError> {
+    let bytes = fs::read(path).map_err(|source| DumpError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let Ok(json) = serde_json::from_slice::<serde_json::Value>(&bytes) else {
+        return Ok(bytes);
+    };
+
+    let account = json.get("account").unwrap_or(&json);
+    let (Some(data), Some(encoding)) = (account["data"][0].as_str(), account["data"][1].as_str())
+    else {
+        return Err(DumpError::Format(path.to_path_buf()));
+    };
+    if encoding != "base64" {
+        return Err(DumpError::Encoding {
+            path: path.to_path_buf(),
+            encoding: encoding.to_string(),
+        });
+    }
+
+    STANDARD
+        .decode(data)
+        .map_err(|_| DumpError::Base64(path.to_path_buf()))
+}
+
+/// Load a program account of type `T` from a dump, checking its discriminator
+pub fn load_account<T: AccountDeserialize>(path: &Path) -> Result<T, DumpError> {
+    let data = read_account_data(path)?;
+    T::try_deserialize(&mut &data[..]).map_err(|source| DumpError::Account {
+        path: path.to_path_buf(),
+        account: std::any::type_name::<T>()
+            .rsplit("::")
+            .next()
+            .unwrap_or_default(),
+        source,
+    })
+}
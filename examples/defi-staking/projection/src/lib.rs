@@ -0,0 +1,349 @@
+//! Off-chain reward projections for defi-staking positions
+//!
+//! Rewards are computed with the program's own `defi_staking::math`, so a
+//! projection at a given timestamp is exactly what `get_pending_rewards`
+//! returns at that time. What `claim_rewards` would actually pay is projected
+//! separately: nothing once unstake is requested, since `unstake` forfeits
+//! those rewards, and nothing the reward vault can't cover, when its balance
+//! is known. Reward streams and native stake epoch rewards are not projected.
+
+use anchor_lang::prelude::Pubkey;
+use serde::Serialize;
+
+pub use defi_staking::generated::{
+    PoolMode, RewardConfig, RewardVault, StakeAccount, StakingPool, StakingStatus,
+};
+pub use defi_staking::math;
+
+pub mod dump;
+
+use math::*;
+
+/// Where a position stands at a point in time
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Still inside the pool's minimum lock
+    Locked,
+    /// Unstake can be requested
+    Unlocked,
+    /// Unstake requested, cooldown running
+    CoolingDown,
+    /// Cooldown over, `unstake` can complete
+    Withdrawable,
+    /// Position already unstaked
+    Closed,
+}
+
+/// Projected state of a position at `at`
+#[derive(Serialize, Debug, Clone)]
+pub struct TimelinePoint {
+    pub at: i64,
+    /// Base rewards accrued to the position at `at`, as `get_pending_rewards`
+    /// would report
+    pub rewards: u64,
+    /// What `claim_rewards` would pay at `at`: none of `rewards` once unstake
+    /// is requested, and none while they exceed the reward vault's current
+    /// balance (assuming no funding or other claims in between)
+    pub claimable: u64,
+    pub phase: Phase,
+}
+
+/// Reward, unlock and cooldown timeline of a position
+#[derive(Serialize, Debug, Clone)]
+pub struct Timeline {
+    pub amount: u64,
+    /// Stake the position earns base rewards on, including its NFT boost
+    pub reward_weight: u64,
+    /// Pool APY in basis points at the start, when the reward config was given
+    pub apy_bps: Option<u64>,
+    pub unlock_at: i64,
+    /// When `unstake` can complete: after the actual unstake request, or
+    /// after one made as soon as the position unlocks
+    pub withdrawable_at: i64,
+    /// When the reward vault stops covering every staker's accrued rewards,
+    /// when its balance was given and the current stake ever exhausts it
+    pub vault_exhausted_at: Option<i64>,
+    pub points: Vec<TimelinePoint>,
+}
+
+/// Pool and position as `stake` would leave them after a first stake of
+/// `amount` by `owner` at `now`
+pub fn hypothetical_position(
+    pool: &StakingPool,
+    pool_key: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+    now: i64,
+) -> (StakingPool, StakeAccount) {
+    let mut pool = pool.clone();
+    accrue_pool_rewards(&mut pool, now);
+
+    let stake_account = StakeAccount {
+        owner,
+        pool: pool_key,
+        amount,
+        staked_at: now,
+        last_claim_at: now,
+        unlock_at: now + pool.min_lock_duration,
+        total_claimed: 0,
+        reward_per_share_paid: pool.acc_reward_per_share,
+        pending_rewards: 0,
+        boost_mint: None,
+        boost_multiplier: BASIS_POINTS,
+        status: if pool.min_lock_duration > 0 {
+            StakingStatus::Locked
+        } else {
+            StakingStatus::Active
+        },
+        unstake_requested_at: None,
+        stream_checkpoints: Vec::new(),
+    };
+
+    pool.total_staked += amount;
+    pool.total_stakers += 1;
+
+    (pool, stake_account)
+}
+
+/// Earliest time `unstake` can complete for the position
+pub fn withdrawable_at(pool: &StakingPool, stake_account: &StakeAccount) -> i64 {
+    stake_account
+        .unstake_requested_at
+        .unwrap_or(stake_account.unlock_at)
+        + pool.cooldown_period
+}
+
+/// Phase of the position at `at`, assuming it requests unstake no earlier
+/// than it already has
+pub fn phase_at(pool: &StakingPool, stake_account: &StakeAccount, at: i64) -> Phase {
+    match stake_account.status {
+        StakingStatus::Unstaked => Phase::Closed,
+        StakingStatus::UnstakeRequested if at < withdrawable_at(pool, stake_account) => {
+            Phase::CoolingDown
+        }
+        StakingStatus::UnstakeRequested => Phase::Withdrawable,
+        StakingStatus::Active | StakingStatus::Locked if at < stake_account.unlock_at => {
+            Phase::Locked
+        }
+        StakingStatus::Active | StakingStatus::Locked => Phase::Unlocked,
+    }
+}
+
+/// What `claim_rewards` would pay the position at `at`, given the reward
+/// vault's balance if known
+pub fn claimable_rewards(
+    pool: &StakingPool,
+    stake_account: &StakeAccount,
+    vault_balance: Option<u64>,
+    at: i64,
+) -> u64 {
+    let claimable = !matches!(pool.mode, PoolMode::NativeStake)
+        && matches!(
+            stake_account.status,
+            StakingStatus::Active | StakingStatus::Locked
+        );
+    let rewards = pending_rewards(pool, stake_account, at);
+    if !claimable || vault_balance.is_some_and(|balance| rewards > balance) {
+        return 0;
+    }
+    rewards
+}
+
+/// Project a position from `from` to `until`, one point every `step`
+/// seconds plus one at its unlock and withdrawable times
+pub fn timeline(
+    pool: &StakingPool,
+    config: Option<&RewardConfig>,
+    vault: Option<&RewardVault>,
+    stake_account: &StakeAccount,
+    from: i64,
+    until: i64,
+    step: i64,
+) -> Timeline {
+    let unlock_at = stake_account.unlock_at;
+    let withdrawable_at = withdrawable_at(pool, stake_account);
+    let vault_balance = vault.map(|vault| vault.balance);
+
+    let mut times: Vec<i64> = (0..)
+        .map(|n| from.saturating_add(step.max(1).saturating_mul(n)))
+        .take_while(|t| *t < until)
+        .chain([until])
+        .collect();
+    times.extend(
+        [unlock_at, withdrawable_at]
+            .into_iter()
+            .filter(|t| (from..=until).contains(t)),
+    );
+    times.sort_unstable();
+    times.dedup();
+
+    Timeline {
+        amount: stake_account.amount,
+        reward_weight: reward_weight(stake_account),
+        apy_bps: config.map(|config| current_apy(config, pool, from)),
+        unlock_at,
+        withdrawable_at,
+        vault_exhausted_at: vault_balance
+            .and_then(|balance| solvency_runway(pool, balance, from))
+            .map(|runway| from.saturating_add(runway)),
+        points: times
+            .into_iter()
+            .map(|at| TimelinePoint {
+                at,
+                rewards: pending_rewards(pool, stake_account, at),
+                claimable: claimable_rewards(pool, stake_account, vault_balance, at),
+                phase: phase_at(pool, stake_account, at),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use defi_staking::generated::EmissionSchedule;
+
+    /// Stake that earns one lamport per second at a reward rate of 1
+    const ONE_PER_SECOND: u64 = REWARD_DENOMINATOR as u64;
+    /// Smallest rate that accrues to `ONE_PER_SECOND` without rounding
+    const RATE: u64 = 1971;
+
+    fn empty_pool(mode: PoolMode) -> StakingPool {
+        StakingPool {
+            authority: Pubkey::default(),
+            token_mint: Pubkey::default(),
+            vault: Pubkey::default(),
+            reward_vault: Pubkey::default(),
+            mode,
+            receipt_mint: None,
+            total_staked: 0,
+            total_stakers: 0,
+            reward_rate: RATE,
+            emission_schedule: EmissionSchedule::Fixed,
+            acc_reward_per_share: 0,
+            rewards_owed: 0,
+            last_accrual_at: 0,
+            reward_stream_count: 0,
+            min_stake_amount: 1,
+            max_total_staked: None,
+            max_per_user: None,
+            allowlist_root: None,
+            boost_collection: None,
+            boost_multiplier: BASIS_POINTS,
+            total_boost_weight: 0,
+            min_lock_duration: 100,
+            cooldown_period: 50,
+            is_active: true,
+            created_at: 0,
+            emission_epochs: Vec::new(),
+        }
+    }
+
+    /// The only position in the pool, staked at 0 and earning `RATE` a second
+    fn staked(mode: PoolMode) -> (StakingPool, StakeAccount) {
+        hypothetical_position(
+            &empty_pool(mode),
+            Pubkey::default(),
+            Pubkey::default(),
+            ONE_PER_SECOND,
+            0,
+        )
+    }
+
+    fn vault(balance: u64) -> RewardVault {
+        RewardVault {
+            pool: Pubkey::default(),
+            balance,
+            total_funded: balance,
+            total_distributed: 0,
+            total_withdrawn: 0,
+        }
+    }
+
+    #[test]
+    fn new_stake_locks_then_unlocks() {
+        let (pool, stake_account) = staked(PoolMode::Standard);
+        assert!(matches!(stake_account.status, StakingStatus::Locked));
+
+        let timeline = timeline(&pool, None, None, &stake_account, 0, 200, 30);
+        assert_eq!(timeline.unlock_at, 100);
+        // Unstake requested as soon as it unlocks
+        assert_eq!(timeline.withdrawable_at, 150);
+        assert_eq!(timeline.vault_exhausted_at, None);
+
+        let times: Vec<i64> = timeline.points.iter().map(|point| point.at).collect();
+        assert_eq!(times, [0, 30, 60, 90, 100, 120, 150, 180, 200]);
+        for point in &timeline.points {
+            assert_eq!(point.rewards, RATE * point.at as u64);
+            assert_eq!(point.claimable, point.rewards);
+            let phase = if point.at < 100 { Phase::Locked } else { Phase::Unlocked };
+            assert_eq!(point.phase, phase);
+        }
+    }
+
+    #[test]
+    fn rewards_are_forfeited_once_unstake_is_requested() {
+        let (pool, mut stake_account) = staked(PoolMode::Standard);
+        stake_account.status = StakingStatus::UnstakeRequested;
+        stake_account.unstake_requested_at = Some(120);
+
+        let timeline = timeline(&pool, None, None, &stake_account, 120, 200, 40);
+        assert_eq!(timeline.withdrawable_at, 170);
+
+        let phases: Vec<(i64, Phase)> = timeline
+            .points
+            .iter()
+            .map(|point| (point.at, point.phase))
+            .collect();
+        assert_eq!(
+            phases,
+            [
+                (120, Phase::CoolingDown),
+                (160, Phase::CoolingDown),
+                (170, Phase::Withdrawable),
+                (200, Phase::Withdrawable),
+            ]
+        );
+        // Still accrued until `unstake`, which drops them
+        for point in &timeline.points {
+            assert_eq!(point.rewards, RATE * point.at as u64);
+            assert_eq!(point.claimable, 0);
+        }
+
+        stake_account.status = StakingStatus::Unstaked;
+        assert_eq!(phase_at(&pool, &stake_account, 200), Phase::Closed);
+        assert_eq!(claimable_rewards(&pool, &stake_account, None, 200), 0);
+    }
+
+    #[test]
+    fn claims_stop_when_the_vault_runs_dry() {
+        let (pool, stake_account) = staked(PoolMode::Standard);
+
+        let timeline = timeline(&pool, None, Some(&vault(150 * RATE)), &stake_account, 0, 200, 50);
+        assert_eq!(timeline.vault_exhausted_at, Some(150));
+
+        let claimable: Vec<(i64, u64)> = timeline
+            .points
+            .iter()
+            .map(|point| (point.at, point.claimable))
+            .collect();
+        assert_eq!(
+            claimable,
+            [(0, 0), (50, 50 * RATE), (100, 100 * RATE), (150, 150 * RATE), (200, 0)]
+        );
+        assert_eq!(timeline.points.last().unwrap().rewards, 200 * RATE);
+    }
+
+    #[test]
+    fn native_positions_have_no_claimable_base_rewards() {
+        let (pool, stake_account) = staked(PoolMode::NativeStake);
+
+        let timeline = timeline(&pool, None, Some(&vault(1_000)), &stake_account, 0, 100, 50);
+        assert_eq!(timeline.vault_exhausted_at, None);
+        assert!(timeline
+            .points
+            .iter()
+            .all(|point| point.rewards == 0 && point.claimable == 0));
+    }
+}
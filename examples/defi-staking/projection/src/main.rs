@@ -0,0 +1,135 @@
+//! `staking-projection`: reward, unlock and cooldown timelines for
+//! defi-staking positions, from account dumps
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+
+use staking_projection::dump::load_account;
+use staking_projection::{
+    hypothetical_position, timeline, RewardConfig, RewardVault, StakeAccount, StakingPool,
+    Timeline,
+};
+
+const SECONDS_PER_DAY: i64 = 86400;
+
+#[derive(Parser)]
+#[command(
+    name = "staking-projection",
+    about = "Project defi-staking rewards, unlock and cooldown times from account dumps"
+)]
+struct Args {
+    /// StakingPool dump (`solana account <pool> --output json`, or raw account data)
+    #[arg(long)]
+    pool: PathBuf,
+
+    /// RewardConfig dump, to include the pool's APY
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// RewardVault dump, to check that the vault can pay the rewards
+    #[arg(long)]
+    vault: Option<PathBuf>,
+
+    /// StakeAccount dump of an existing position
+    #[arg(long, required_unless_present = "amount", conflicts_with = "amount")]
+    stake: Option<PathBuf>,
+
+    /// Lamports of a new stake to project instead of an existing position
+    #[arg(long)]
+    amount: Option<u64>,
+
+    /// Start of the projection, in unix seconds [default: now]
+    #[arg(long)]
+    now: Option<i64>,
+
+    /// Days to project
+    #[arg(long, default_value_t = 30)]
+    days: u32,
+
+    /// Days between timeline rows
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    step_days: u32,
+
+    #[arg(long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let now = match args.now {
+        Some(now) => now,
+        None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+    };
+    let until = now + args.days as i64 * SECONDS_PER_DAY;
+
+    let pool: StakingPool = load_account(&args.pool)?;
+    let config: Option<RewardConfig> = args.config.as_deref().map(load_account).transpose()?;
+    let vault: Option<RewardVault> = args.vault.as_deref().map(load_account).transpose()?;
+
+    let (pool, stake_account) = match (&args.stake, args.amount) {
+        (Some(path), _) => (pool, load_account::<StakeAccount>(path)?),
+        (None, Some(amount)) => {
+            hypothetical_position(&pool, Pubkey::default(), Pubkey::default(), amount, now)
+        }
+        (None, None) => unreachable!("clap requires --stake or --amount"),
+    };
+
+    let timeline = timeline(
+        &pool,
+        config.as_ref(),
+        vault.as_ref(),
+        &stake_account,
+        now,
+        until,
+        args.step_days as i64 * SECONDS_PER_DAY,
+    );
+
+    match args.format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(&timeline)?),
+        Format::Table => print_table(&timeline, now),
+    }
+
+    Ok(())
+}
+
+fn print_table(timeline: &Timeline, now: i64) {
+    println!(
+        "Stake:          {} lamports (reward weight {})",
+        timeline.amount, timeline.reward_weight
+    );
+    if let Some(apy) = timeline.apy_bps {
+        println!("Pool APY:       {}.{:02}%", apy / 100, apy % 100);
+    }
+    println!("Unlocks at:     {}", timeline.unlock_at);
+    println!("Withdrawable:   {}", timeline.withdrawable_at);
+    if let Some(exhausted_at) = timeline.vault_exhausted_at {
+        println!("Vault runs dry: {}", exhausted_at);
+    }
+    println!();
+    println!(
+        "{:>8}  {:>12}  {:>20}  {:>20}  PHASE",
+        "DAY", "TIMESTAMP", "REWARDS (lamports)", "CLAIMABLE (lamports)"
+    );
+
+    for point in &timeline.points {
+        println!(
+            "{:>8.2}  {:>12}  {:>20}  {:>20}  {:?}",
+            (point.at - now) as f64 / SECONDS_PER_DAY as f64,
+            point.at,
+            point.rewards,
+            point.claimable,
+            point.phase
+        );
+    }
+}
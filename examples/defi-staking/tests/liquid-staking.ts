@@ -0,0 +1,75 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { expect } from "chai";
+import { DefiStaking } from "../target/types/defi_staking";
+import { StakingClient } from "../app/src/staking-client";
+
+describe("liquid staking", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.defiStaking as Program<DefiStaking>;
+  const connection = provider.connection;
+  const client = new StakingClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const user = Keypair.generate();
+
+  async function airdrop(to: PublicKey, lamports: number) {
+    const signature = await connection.requestAirdrop(to, lamports);
+    await connection.confirmTransaction(signature, "confirmed");
+  }
+
+  async function expectError(promise: Promise<unknown>, error: string) {
+    try {
+      await promise;
+      expect.fail(`expected ${error}`);
+    } catch (e) {
+      expect(String(e)).to.include(error);
+    }
+  }
+
+  async function createPool(minLockDuration: number, cooldownPeriod: number): Promise<PublicKey> {
+    return client.initializePool({
+      authority,
+      tokenMint: PublicKey.default,
+      vault: Keypair.generate().publicKey,
+      rewardRate: 1000,
+      minStakeAmount: LAMPORTS_PER_SOL,
+      minLockDuration,
+      cooldownPeriod,
+    });
+  }
+
+  before(async () => {
+    await airdrop(authority.publicKey, 10 * LAMPORTS_PER_SOL);
+    await airdrop(user.publicKey, 10 * LAMPORTS_PER_SOL);
+  });
+
+  it("refuses pools with a lock or cooldown, which receipts can't honour", async () => {
+    for (const [lock, cooldown] of [[60, 0], [0, 60]]) {
+      const pool = await createPool(lock, cooldown);
+      await expectError(client.enableLiquidStaking({ pool, authority }), "InvalidPoolMode");
+    }
+  });
+
+  it("refuses pools with a boost collection, which needs positions to lock NFTs into", async () => {
+    const pool = await createPool(0, 0);
+    await client.setBoostCollection({ pool, authority, collection: Keypair.generate().publicKey, multiplier: 15_000 });
+    await expectError(client.enableLiquidStaking({ pool, authority }), "InvalidPoolMode");
+  });
+
+  it("mints receipts one to one until rewards accrue, and only while active", async () => {
+    const pool = await createPool(0, 0);
+    const receiptMint = await client.enableLiquidStaking({ pool, authority });
+
+    await client.liquidStake({ pool, user, amount: 2 * LAMPORTS_PER_SOL });
+    const receipts = await getAccount(connection, getAssociatedTokenAddressSync(receiptMint, user.publicKey));
+    expect(Number(receipts.amount)).to.equal(2 * LAMPORTS_PER_SOL);
+
+    await client.updatePool({ pool, authority, isActive: false });
+    await expectError(client.liquidStake({ pool, user, amount: LAMPORTS_PER_SOL }), "PoolNotActive");
+  });
+});
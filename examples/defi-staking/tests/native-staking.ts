@@ -0,0 +1,172 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import { expect } from "chai";
+import { DefiStaking } from "../target/types/defi_staking";
+import { StakingClient } from "../app/src/staking-client";
+
+// Runs against `anchor test`'s local validator, which Anchor.toml starts with
+// 32-slot epochs so stake warms up and cools down in seconds.
+describe("native staking", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.defiStaking as Program<DefiStaking>;
+  const connection = provider.connection;
+  const client = new StakingClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const user = Keypair.generate();
+  const amount = 2 * LAMPORTS_PER_SOL;
+
+  let pool: PublicKey;
+  let voteAccount: PublicKey;
+  let stakeAccount: PublicKey;
+  let nativeStake: PublicKey;
+
+  async function airdrop(to: PublicKey, lamports: number) {
+    const signature = await connection.requestAirdrop(to, lamports);
+    await connection.confirmTransaction(signature, "confirmed");
+  }
+
+  // Stands in for `solana-test-validator --warp-slot`, which only works at startup
+  async function warpToNextEpoch() {
+    const { epoch } = await connection.getEpochInfo("confirmed");
+    while ((await connection.getEpochInfo("confirmed")).epoch <= epoch) {
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+    }
+  }
+
+  before(async () => {
+    await airdrop(authority.publicKey, 10 * LAMPORTS_PER_SOL);
+    await airdrop(user.publicKey, 10 * LAMPORTS_PER_SOL);
+
+    // The test validator's own vote account is the only validator available
+    const { current } = await connection.getVoteAccounts();
+    voteAccount = new PublicKey(current[0].votePubkey);
+
+    pool = await client.initializePool({
+      authority,
+      tokenMint: PublicKey.default,
+      vault: Keypair.generate().publicKey,
+      rewardRate: 1000,
+      minStakeAmount: LAMPORTS_PER_SOL,
+      minLockDuration: 0,
+      cooldownPeriod: 0,
+    });
+
+    [stakeAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), pool.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+    nativeStake = client.getNativeStakeAddress(stakeAccount);
+  });
+
+  it("enables native staking with a validator set", async () => {
+    await client.enableNativeStaking({ pool, authority, validators: [voteAccount] });
+
+    const poolData = await program.account.stakingPool.fetch(pool);
+    expect(poolData.mode).to.have.property("nativeStake");
+
+    const { validators } = await client.getValidatorList(pool);
+    expect(validators).to.have.length(1);
+    expect(validators[0].voteAccount.toBase58()).to.equal(voteAccount.toBase58());
+  });
+
+  it("delegates deposits to the validator through the Stake program", async () => {
+    await client.nativeStake({ pool, user, amount });
+
+    const stake = await connection.getParsedAccountInfo(nativeStake);
+    const parsed = (stake.value.data as any).parsed;
+    expect(parsed.type).to.equal("delegated");
+    expect(parsed.info.stake.delegation.voter).to.equal(voteAccount.toBase58());
+    expect(Number(parsed.info.stake.delegation.stake)).to.equal(amount);
+    expect(parsed.info.meta.authorized.withdrawer).to.equal(
+      client.getPoolAuthorityAddress(pool).toBase58()
+    );
+
+    const { validators } = await client.getValidatorList(pool);
+    expect(validators[0].totalDelegated).to.equal(amount);
+  });
+
+  it("pays no base rewards on native positions", async () => {
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    expect(await client.calculateRewards(pool, user.publicKey)).to.equal(0);
+    expect((await client.getPoolStats(pool)).currentApy).to.equal(0);
+
+    try {
+      await client.claimRewards({ pool, user });
+      expect.fail("claim_rewards should reject native pools");
+    } catch (e) {
+      expect(String(e)).to.include("InvalidPoolMode");
+    }
+  });
+
+  it("rejects the token-pool unstake flow on native positions", async () => {
+    try {
+      await client.requestUnstake({ pool, user });
+      expect.fail("request_unstake should reject native pools");
+    } catch (e) {
+      expect(String(e)).to.include("InvalidPoolMode");
+    }
+  });
+
+  it("pays epoch rewards out with the principal after deactivation", async () => {
+    await warpToNextEpoch();
+
+    // Inflation over 32-slot epochs rounds down to nothing, so credit the
+    // stake account directly to stand in for an epoch's rewards
+    const epochRewards = 0.05 * LAMPORTS_PER_SOL;
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: authority.publicKey,
+          toPubkey: nativeStake,
+          lamports: epochRewards,
+        })
+      ),
+      [authority]
+    );
+    expect(await client.getNativeStakeRewards(pool, user.publicKey)).to.equal(epochRewards);
+
+    await client.requestNativeUnstake({ pool, user });
+    const position = await client.getStakeAccount(pool, user.publicKey);
+    expect(position.status).to.have.property("unstakeRequested");
+
+    // Still cooling down: the Stake program refuses the withdrawal
+    try {
+      await client.nativeUnstake({ pool, user });
+      expect.fail("withdrawal should wait for the stake to deactivate");
+    } catch (e) {
+      expect(String(e)).to.include("insufficient funds");
+    }
+
+    await warpToNextEpoch();
+
+    const reserve = await connection.getMinimumBalanceForRentExemption(200);
+    const before = await connection.getBalance(user.publicKey);
+    await client.nativeUnstake({ pool, user });
+    const after = await connection.getBalance(user.publicKey);
+
+    // The user paid the fee and gets the rent reserve back with the stake
+    expect(after - before).to.be.closeTo(amount + epochRewards + reserve, 10_000);
+    expect(await connection.getAccountInfo(nativeStake)).to.be.null;
+
+    const closed = await client.getStakeAccount(pool, user.publicKey);
+    expect(closed.amount).to.equal(0);
+    expect(closed.totalClaimed).to.equal(epochRewards);
+
+    const { validators } = await client.getValidatorList(pool);
+    expect(validators[0].totalDelegated).to.equal(0);
+    expect(validators[0].totalRewards).to.equal(epochRewards);
+
+    const stats = await client.getPoolStats(pool);
+    expect(stats.totalRewardsDistributed).to.equal(epochRewards);
+  });
+});
@@ -0,0 +1,141 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { execFileSync } from "child_process";
+import * as fs from "fs";
+import * as os from "os";
+import * as path from "path";
+import { DefiStaking } from "../target/types/defi_staking";
+import { StakingClient } from "../app/src/staking-client";
+
+// Checks the off-chain projection crate against what the program actually pays
+describe("reward projection", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.defiStaking as Program<DefiStaking>;
+  const connection = provider.connection;
+  const client = new StakingClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const user = Keypair.generate();
+  const dumpDir = fs.mkdtempSync(path.join(os.tmpdir(), "staking-projection-"));
+
+  let pool: PublicKey;
+  let stakeAccount: PublicKey;
+
+  async function airdrop(to: PublicKey, lamports: number) {
+    const signature = await connection.requestAirdrop(to, lamports);
+    await connection.confirmTransaction(signature, "confirmed");
+  }
+
+  // Same layout as `solana account <address> --output json`
+  async function dumpAccount(address: PublicKey, file: string): Promise<string> {
+    const info = await connection.getAccountInfo(address, "confirmed");
+    const dump = path.join(dumpDir, file);
+    fs.writeFileSync(
+      dump,
+      JSON.stringify({
+        pubkey: address.toBase58(),
+        account: {
+          lamports: info.lamports,
+          data: [info.data.toString("base64"), "base64"],
+          owner: info.owner.toBase58(),
+          executable: info.executable,
+          rentEpoch: info.rentEpoch,
+        },
+      })
+    );
+    return dump;
+  }
+
+  function project(args: string[]): any {
+    const output = execFileSync(
+      "cargo",
+      [
+        "run",
+        "--quiet",
+        "--manifest-path",
+        path.join(__dirname, "../projection/Cargo.toml"),
+        "--",
+        ...args,
+        "--format",
+        "json",
+      ],
+      { encoding: "utf8" }
+    );
+    return JSON.parse(output);
+  }
+
+  before(async () => {
+    await airdrop(authority.publicKey, 10 * LAMPORTS_PER_SOL);
+    await airdrop(user.publicKey, 10 * LAMPORTS_PER_SOL);
+
+    pool = await client.initializePool({
+      authority,
+      tokenMint: PublicKey.default,
+      vault: Keypair.generate().publicKey,
+      rewardRate: 1000,
+      minStakeAmount: LAMPORTS_PER_SOL,
+      minLockDuration: 3600,
+      cooldownPeriod: 600,
+    });
+    await client.fundRewards({ pool, funder: authority, amount: LAMPORTS_PER_SOL });
+
+    [stakeAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), pool.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+  });
+
+  it("projects a new stake's unlock and cooldown like the program", async () => {
+    const poolDump = await dumpAccount(pool, "pool.json");
+    const projected = project([
+      "--pool",
+      poolDump,
+      "--amount",
+      String(2 * LAMPORTS_PER_SOL),
+      "--days",
+      "0",
+    ]);
+
+    await client.stake({ pool, user, amount: 2 * LAMPORTS_PER_SOL });
+    const position = await client.getStakeAccount(pool, user.publicKey);
+
+    expect(projected.amount).to.equal(position.amount);
+    expect(projected.unlock_at - projected.points[0].at).to.equal(
+      position.unlockAt - position.stakedAt
+    );
+    expect(projected.withdrawable_at - projected.unlock_at).to.equal(600);
+  });
+
+  it("projects exactly the rewards claim_rewards pays", async () => {
+    await new Promise((resolve) => setTimeout(resolve, 5000));
+
+    const poolDump = await dumpAccount(pool, "pool.json");
+    const stakeDump = await dumpAccount(stakeAccount, "stake.json");
+
+    await client.claimRewards({ pool, user });
+    const position = await client.getStakeAccount(pool, user.publicKey);
+    expect(position.totalClaimed).to.be.greaterThan(0);
+
+    // Project the dumped state to the block time the claim executed at
+    const projected = project([
+      "--pool",
+      poolDump,
+      "--stake",
+      stakeDump,
+      "--now",
+      String(position.lastClaimAt),
+      "--days",
+      "0",
+    ]);
+
+    expect(projected.points[0].at).to.equal(position.lastClaimAt);
+    expect(projected.points[0].rewards).to.equal(position.totalClaimed);
+    expect(projected.points[0].claimable).to.equal(position.totalClaimed);
+    expect(projected.points[0].phase).to.equal("locked");
+    expect(projected.unlock_at).to.equal(position.unlockAt);
+  });
+});
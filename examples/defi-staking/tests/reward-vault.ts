@@ -0,0 +1,132 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import { DefiStaking } from "../target/types/defi_staking";
+import { StakingClient } from "../app/src/staking-client";
+
+describe("reward vault", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.defiStaking as Program<DefiStaking>;
+  const connection = provider.connection;
+  const client = new StakingClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const user = Keypair.generate();
+
+  let pool: PublicKey;
+
+  async function airdrop(to: PublicKey, lamports: number) {
+    const signature = await connection.requestAirdrop(to, lamports);
+    await connection.confirmTransaction(signature, "confirmed");
+  }
+
+  async function expectError(promise: Promise<unknown>, error: string) {
+    try {
+      await promise;
+      expect.fail(`expected ${error}`);
+    } catch (e) {
+      expect(String(e)).to.include(error);
+    }
+  }
+
+  before(async () => {
+    await airdrop(authority.publicKey, 20 * LAMPORTS_PER_SOL);
+    await airdrop(user.publicKey, 10 * LAMPORTS_PER_SOL);
+
+    // The highest rate, so 1 SOL of stake accrues about 300k lamports a second
+    pool = await client.initializePool({
+      authority,
+      tokenMint: PublicKey.default,
+      vault: Keypair.generate().publicKey,
+      rewardRate: 1_000_000,
+      minStakeAmount: LAMPORTS_PER_SOL,
+      minLockDuration: 0,
+      cooldownPeriod: 0,
+    });
+
+    await client.fundRewards({ pool, funder: authority, amount: 100_000 });
+    await client.stake({ pool, user, amount: LAMPORTS_PER_SOL });
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+  });
+
+  it("refuses claims larger than the vault instead of paying from principal", async () => {
+    await expectError(client.claimRewards({ pool, user }), "RewardsExhausted");
+    expect((await client.getRewardVault(pool)).balance).to.equal(100_000);
+    expect(await client.getSolvencyRunway(pool)).to.equal(0);
+
+    // Nothing is unallocated while stakers are owed more than the vault holds
+    await expectError(
+      client.withdrawUnallocatedRewards({ pool, authority, amount: 1 }),
+      "InsufficientUnallocatedRewards"
+    );
+  });
+
+  it("only lets the authority withdraw what no staker is owed", async () => {
+    await client.fundRewards({ pool, funder: authority, amount: 10 * LAMPORTS_PER_SOL });
+    await client.claimRewards({ pool, user });
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+    // The user is owed again what accrued since the claim
+    const { balance } = await client.getRewardVault(pool);
+    await expectError(
+      client.withdrawUnallocatedRewards({ pool, authority, amount: balance }),
+      "InsufficientUnallocatedRewards"
+    );
+
+    await client.withdrawUnallocatedRewards({ pool, authority, amount: LAMPORTS_PER_SOL });
+    const vault = await client.getRewardVault(pool);
+    expect(vault.balance).to.equal(balance - LAMPORTS_PER_SOL);
+    expect(vault.totalWithdrawn).to.equal(LAMPORTS_PER_SOL);
+
+    // Liabilities accrued up to the withdrawal stay fully covered
+    const poolData = await client.getPool(pool);
+    expect(poolData.rewardsOwed).to.be.greaterThan(0);
+    expect(vault.balance).to.be.at.least(poolData.rewardsOwed);
+  });
+
+  it("rejects a position or vault from another pool", async () => {
+    const other = await client.initializePool({
+      authority,
+      tokenMint: PublicKey.default,
+      vault: Keypair.generate().publicKey,
+      rewardRate: 1_000_000,
+      minStakeAmount: LAMPORTS_PER_SOL,
+      minLockDuration: 0,
+      cooldownPeriod: 0,
+    });
+    const [stakeAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), pool.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+    const withdraw = (target: PublicKey, vaultAccount: PublicKey) =>
+      program.methods
+        .emergencyWithdraw()
+        .accounts({
+          pool: target,
+          stakeAccount,
+          user: user.publicKey,
+          vaultAccount,
+          userStats: client.getUserStatsAddress(user.publicKey),
+          poolStats: client.getPoolStatsAddress(target),
+          rewardConfig: client.getRewardConfigAddress(target),
+          nftEscrow: null,
+          userNftAccount: null,
+          poolAuthority: null,
+          tokenProgram: null,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([user])
+        .rpc();
+
+    // This pool's position can't unwind the other pool's totals and liabilities
+    await expectError(withdraw(other, (await client.getPool(other)).vault), "ConstraintHasOne");
+    // Nor can it be paid out of any vault but its pool's
+    await expectError(withdraw(pool, Keypair.generate().publicKey), "ConstraintAddress");
+
+    expect((await client.getPool(other)).totalStaked).to.equal(0);
+    expect((await client.getPool(pool)).totalStaked).to.equal(LAMPORTS_PER_SOL);
+  });
+});
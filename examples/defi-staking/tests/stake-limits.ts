@@ -0,0 +1,108 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { DefiStaking } from "../target/types/defi_staking";
+import { StakingClient, buildAllowlist } from "../app/src/staking-client";
+
+describe("stake limits", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.defiStaking as Program<DefiStaking>;
+  const connection = provider.connection;
+  const client = new StakingClient(connection, program as Program, provider);
+
+  const authority = Keypair.generate();
+  const alice = Keypair.generate();
+  const bob = Keypair.generate();
+  const carol = Keypair.generate();
+  // Three wallets, so one leaf is carried up a level unpaired
+  const allowlist = buildAllowlist([alice.publicKey, bob.publicKey, authority.publicKey]);
+
+  let pool: PublicKey;
+
+  async function airdrop(to: PublicKey, lamports: number) {
+    const signature = await connection.requestAirdrop(to, lamports);
+    await connection.confirmTransaction(signature, "confirmed");
+  }
+
+  async function expectError(promise: Promise<unknown>, error: string) {
+    try {
+      await promise;
+      expect.fail(`expected ${error}`);
+    } catch (e) {
+      expect(String(e)).to.include(error);
+    }
+  }
+
+  function stake(user: Keypair, amount: number, proof = allowlist.getProof(user.publicKey)) {
+    return client.stake({ pool, user, amount: amount * LAMPORTS_PER_SOL, proof });
+  }
+
+  before(async () => {
+    for (const wallet of [authority, alice, bob, carol]) {
+      await airdrop(wallet.publicKey, 10 * LAMPORTS_PER_SOL);
+    }
+
+    pool = await client.initializePool({
+      authority,
+      tokenMint: PublicKey.default,
+      vault: Keypair.generate().publicKey,
+      rewardRate: 1000,
+      minStakeAmount: LAMPORTS_PER_SOL,
+      minLockDuration: 0,
+      cooldownPeriod: 0,
+    });
+  });
+
+  it("rejects caps below the minimum stake or a user cap above the pool cap", async () => {
+    await expectError(
+      client.setStakeLimits({ pool, authority, maxTotalStaked: LAMPORTS_PER_SOL / 2 }),
+      "InvalidStakeLimits"
+    );
+    await expectError(
+      client.setStakeLimits({
+        pool,
+        authority,
+        maxTotalStaked: 2 * LAMPORTS_PER_SOL,
+        maxPerUser: 3 * LAMPORTS_PER_SOL,
+      }),
+      "InvalidStakeLimits"
+    );
+  });
+
+  it("enforces the pool cap, the per-user cap and the allow-list", async () => {
+    await client.setStakeLimits({
+      pool,
+      authority,
+      maxTotalStaked: 3 * LAMPORTS_PER_SOL,
+      maxPerUser: 2 * LAMPORTS_PER_SOL,
+      allowlistRoot: allowlist.root,
+    });
+
+    // Neither a member's proof nor no proof gets an outsider in
+    await expectError(stake(carol, 1, allowlist.getProof(alice.publicKey)), "NotAllowlisted");
+    await expectError(stake(carol, 1, []), "NotAllowlisted");
+    await expectError(stake(bob, 1, allowlist.getProof(alice.publicKey)), "NotAllowlisted");
+
+    await stake(alice, 2);
+    await expectError(stake(alice, 1), "UserCapExceeded");
+
+    await stake(bob, 1);
+    await expectError(stake(bob, 1), "PoolCapExceeded");
+
+    const poolData = await client.getPool(pool);
+    expect(poolData.totalStaked).to.equal(3 * LAMPORTS_PER_SOL);
+  });
+
+  it("lifts every limit it is not given", async () => {
+    await client.setStakeLimits({ pool, authority });
+    await stake(carol, 2, []);
+    await stake(alice, 1, []);
+
+    const poolData = await client.getPool(pool);
+    expect(poolData.totalStaked).to.equal(6 * LAMPORTS_PER_SOL);
+    expect(poolData.allowlistRoot).to.be.undefined;
+  });
+});